@@ -1,4 +1,4 @@
-use crate::{Entity, Log, OnChange, OnChanged, OnRemove};
+use crate::{Entity, EntityPolicy, Log, OnChange, OnChanged, OnRemove};
 use attached::Var;
 use parking_lot::RwLock;
 
@@ -44,6 +44,8 @@ pub trait EntityAccessor: Entity + Sized + 'static {
     fn on_changed() -> &'static OnChanged<Self>;
 
     fn on_remove() -> &'static OnRemove<Self>;
+
+    fn policy() -> &'static EntityPolicy<Self>;
 }
 
 pub trait LogAccessor: Entity + Sized + 'static {
@@ -1,13 +1,23 @@
+#[cfg(feature = "fault-injection")]
+use crate::provider::FaultOp;
 use crate::{
     provider::{Delete, LoadAll, LoadArgs, LoadOne, TransactionProvider, Upsert, UpsertMut},
-    Accessor, ApplyLog, AsRefAsync, AsyncTryFrom, BoxFuture, CtxTypeInfo, Entity, EntityAccessor,
-    EntityValidate, Gc, GcCtx, Get, HashTable, Insert, InsertIfChanged, InsertMut,
-    InsertMutIfChanged, Log, LogAccessor, LogState, Logs, LogsVar, NotifyTag, ProviderContainer,
-    Remove, Result, Tag, Transaction, TrxErrGate, Vars, VecTable,
+    with_deadline, Accessor, ApplyLog, AsRefAsync, AsyncTryFrom, Authorized, BoxFuture,
+    CtxTypeInfo, Entity, EntityAccessor, EntityValidate, Error, ErrorFrame, Gc, GcCtx, Get,
+    HashTable, Insert, InsertIfChanged, InsertMut, InsertMutIfChanged, Log, LogAccessor, LogState,
+    Logs, LogsVar, NotifyTag, Operation, ProviderContainer, Remove, Result, Tag, Transaction,
+    TrxErrGate, Vars, VecTable,
 };
 use fxhash::FxHashMap;
 use parking_lot::RwLock;
-use std::{borrow::Cow, hash::Hash, marker::PhantomData};
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    time::Instant,
+};
 use version_tag::VersionTag;
 
 pub struct Ctx {
@@ -49,6 +59,12 @@ impl Ctx {
         &self.provider
     }
 
+    /// The tenant of the provider container.
+    #[inline]
+    pub fn tenant<T: Any>(&self) -> Option<&T> {
+        self.provider.tenant()
+    }
+
     #[inline]
     pub fn ref_as<T>(&self) -> BoxFuture<'_, Result<&'_ T>>
     where
@@ -277,11 +293,23 @@ impl<'a> CtxTransaction<'a> {
     pub fn commit(self) -> BoxFuture<'a, Result<Logs>> {
         Box::pin(async move {
             self.err_gate.check()?;
-            self.provider.commit().await?;
+            with_deadline(self.provider.commit(), self.deadline(), None).await?;
             Ok(Logs(self.log_ctx))
         })
     }
 
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.provider.deadline()
+    }
+
+    /// Bounds the database operations of the transaction, the loads of the tables and the
+    /// loads through its provider included, and the commit. An operation still running at the
+    /// deadline fails with `Error::Timeout` and the transaction can no longer be committed.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.provider.set_deadline(deadline);
+    }
+
     #[inline]
     pub fn provider(&self) -> &TransactionProvider<'a> {
         &self.provider
@@ -407,7 +435,7 @@ impl<'a> CtxTransaction<'a> {
         Ctx: AsRefAsync<E::Tbl>,
     {
         Box::pin(async move {
-            let tbl = self.ctx.tbl_of::<E>().await?;
+            let tbl = with_deadline(self.ctx.tbl_of::<E>(), self.deadline(), None).await?;
             Ok(TblTransaction { tbl, ctx: self })
         })
     }
@@ -604,7 +632,11 @@ where
     Ctx: AsRefAsync<T>,
 {
     fn as_ref_async(&self) -> BoxFuture<'_, Result<&'_ T>> {
-        self.ctx.as_ref_async()
+        Box::pin(with_deadline(
+            self.ctx.as_ref_async(),
+            self.deadline(),
+            None,
+        ))
     }
 }
 
@@ -618,6 +650,12 @@ where
     E: Entity + EntityAccessor,
     E::Key: Eq + Hash,
 {
+    /// The entities of the transaction the caller can read according to the policies of
+    /// the entity.
+    pub fn authorized<'c>(&'c self, caller: &'c E::TrackCtx) -> Authorized<'c, E, Self> {
+        Authorized::new(self, caller, E::policy())
+    }
+
     /// gets a reference from the log or the underlying ctx.
     ///
     /// You can take the TblTransaction by ownership and have a longer
@@ -862,8 +900,8 @@ where
 impl<E> Insert<E> for TblTransaction<'_, '_, E>
 where
     for<'c> TransactionProvider<'c>: Upsert<E>,
-    E: Entity + EntityAccessor + EntityValidate + LogAccessor,
-    E::Key: Eq + Hash,
+    E: CtxTypeInfo + Entity + EntityAccessor + EntityValidate + LogAccessor,
+    E::Key: Debug + Eq + Hash,
     E::Tbl: Get<E>,
 {
     fn insert<'c>(
@@ -873,17 +911,38 @@ where
         track: &'c E::TrackCtx,
     ) -> BoxFuture<'c, Result<()>> {
         Box::pin(async move {
+            E::policy()
+                .check_insert(track, &k, &v, Get::get(&*self, &k))
+                .map_err(context::<E>(Operation::Insert, &k))?;
+
             let gate = self.ctx.err_gate.open()?;
 
-            validate_on_change(self.ctx, &k, &mut v, track).await?;
-            self.ctx.provider.upsert(&k, &v).await?;
+            validate_on_change(self.ctx, &k, &mut v, track)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k))?;
+
+            let upsert = async {
+                #[cfg(feature = "fault-injection")]
+                self.ctx
+                    .provider
+                    .inject_faults::<E>(FaultOp::Upsert)
+                    .await?;
+                self.ctx.provider.upsert(&k, &v).await
+            };
+
+            with_deadline(upsert, self.ctx.deadline(), None)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k))?;
 
             // remove first because if the track change the entity, we want to keep only the latest version.
             log_mut::<E>(&mut self.ctx.log_ctx).remove(&k);
 
             // change tracking...
             let old = self.tbl.get(&k);
-            let result = v.track_insert(&k, old, self.ctx, track).await;
+            let result = v
+                .track_insert(&k, old, self.ctx, track)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k));
 
             // if the value is present, this is because the track has changed the value.
             log_mut(&mut self.ctx.log_ctx)
@@ -967,8 +1026,8 @@ where
 impl<E> InsertMut<E> for TblTransaction<'_, '_, E>
 where
     for<'c> TransactionProvider<'c>: UpsertMut<E>,
-    E: Entity + EntityAccessor + EntityValidate + LogAccessor,
-    E::Key: Clone + Eq + Hash,
+    E: CtxTypeInfo + Entity + EntityAccessor + EntityValidate + LogAccessor,
+    E::Key: Clone + Debug + Eq + Hash,
     E::Tbl: Get<E>,
 {
     fn insert_mut<'c>(
@@ -978,17 +1037,38 @@ where
         track: &'c E::TrackCtx,
     ) -> BoxFuture<'c, Result<E::Key>> {
         Box::pin(async move {
+            E::policy()
+                .check_insert(track, &k, &v, Get::get(&*self, &k))
+                .map_err(context::<E>(Operation::Insert, &k))?;
+
             let gate = self.ctx.err_gate.open()?;
 
-            validate_on_change(self.ctx, &k, &mut v, track).await?;
-            self.ctx.provider.upsert_mut(&mut k, &mut v).await?;
+            validate_on_change(self.ctx, &k, &mut v, track)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k))?;
+
+            let upsert = async {
+                #[cfg(feature = "fault-injection")]
+                self.ctx
+                    .provider
+                    .inject_faults::<E>(FaultOp::Upsert)
+                    .await?;
+                self.ctx.provider.upsert_mut(&mut k, &mut v).await
+            };
+
+            with_deadline(upsert, self.ctx.deadline(), None)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k))?;
 
             // remove first because if the track change the entity, we want to keep only the latest version.
             log_mut::<E>(&mut self.ctx.log_ctx).remove(&k);
 
             // change tracking...
             let old = self.tbl.get(&k);
-            let result = v.track_insert(&k, old, self.ctx, track).await;
+            let result = v
+                .track_insert(&k, old, self.ctx, track)
+                .await
+                .map_err(context::<E>(Operation::Insert, &k));
 
             // if the value is present, this is because the track has changed the value.
             log_mut(&mut self.ctx.log_ctx)
@@ -1072,12 +1152,16 @@ where
 impl<E> Remove<E> for TblTransaction<'_, '_, E>
 where
     for<'c> TransactionProvider<'c>: Delete<E>,
-    E: Entity + EntityAccessor + LogAccessor,
-    E::Key: Clone + Eq + Hash,
+    E: CtxTypeInfo + Entity + EntityAccessor + LogAccessor,
+    E::Key: Clone + Debug + Eq + Hash,
     E::Tbl: Accessor + Get<E>,
 {
     fn remove<'c>(&'c mut self, k: E::Key, track: &'c E::TrackCtx) -> BoxFuture<'c, Result<()>> {
         Box::pin(async move {
+            E::policy()
+                .check_remove(track, &k, Get::get(&*self, &k))
+                .map_err(context::<E>(Operation::Remove, &k))?;
+
             let gate = self.ctx.err_gate.open()?;
 
             if let Some(LogState::Removed) =
@@ -1087,15 +1171,32 @@ where
                 return Ok(());
             }
 
-            E::on_remove().__call(self.ctx, &k, track).await?;
+            E::on_remove()
+                .__call(self.ctx, &k, track)
+                .await
+                .map_err(context::<E>(Operation::Remove, &k))?;
 
             let mut result = Ok(());
 
             if let Some(LogState::Removed) = log::<E>(&self.ctx.log_ctx).get(&k) {
-                self.ctx.provider.delete(&k).await?;
+                let delete = async {
+                    #[cfg(feature = "fault-injection")]
+                    self.ctx
+                        .provider
+                        .inject_faults::<E>(FaultOp::Delete)
+                        .await?;
+                    self.ctx.provider.delete(&k).await
+                };
+
+                with_deadline(delete, self.ctx.deadline(), None)
+                    .await
+                    .map_err(context::<E>(Operation::Remove, &k))?;
 
                 if let Some(old) = self.tbl.get(&k) {
-                    result = old.track_remove(&k, self.ctx, track).await;
+                    result = old
+                        .track_remove(&k, self.ctx, track)
+                        .await
+                        .map_err(context::<E>(Operation::Remove, &k));
                 }
             }
 
@@ -1160,7 +1261,7 @@ fn table_as_ref_async<'a, E, T>(
 ) -> BoxFuture<'a, Result<&'a T>>
 where
     T: Accessor + Default + Extend<(E::Key, E)> + Send + Sync,
-    E: Entity + EntityAccessor<Tbl = T>,
+    E: Entity + CtxTypeInfo + EntityAccessor<Tbl = T>,
     ProviderContainer: LoadAll<E, (), T>,
 {
     Box::pin(async move {
@@ -1171,8 +1272,8 @@ where
             return Ok(v);
         }
 
-        // lock the provider to load the table.
-        let _gate = provider.gate().await;
+        // lock the table so it is loaded only once, other tables can load concurrently.
+        let _gate = provider.table_gate(TypeId::of::<E>()).await;
 
         // if the table is already loaded when we gain access to the provider.
         if let Some(v) = ctx.get(var) {
@@ -1180,6 +1281,9 @@ where
         }
 
         // load the table
+        #[cfg(feature = "fault-injection")]
+        provider.inject_faults::<E>(FaultOp::Load).await?;
+
         let v = provider.load_all(&()).await?;
         Ok(ctx.get_or_init(var, || v))
     })
@@ -1233,6 +1337,17 @@ fn register_apply_log_dyn(app: Box<dyn LogApplier>) {
 
 static LOG_APPLIERS: RwLock<Vec<Box<dyn LogApplier>>> = RwLock::new(Vec::new());
 
+/// Adds a frame identifying the entity and the key to an error.
+fn context<E: CtxTypeInfo + Entity>(
+    operation: Operation,
+    key: &E::Key,
+) -> impl FnOnce(Error) -> Error + '_
+where
+    E::Key: Debug,
+{
+    move |e| e.context(ErrorFrame::new(E::NAME, operation).key(key))
+}
+
 async fn validate_on_change<'a, E>(
     trx: &mut CtxTransaction<'a>,
     key: &E::Key,
@@ -1,3 +1,6 @@
 pub trait CtxTypeInfo {
     const NAME: &'static str;
+
+    /// The name of the provider of the entity in the `ProviderContainer`.
+    const PROVIDER: &'static str = "";
 }
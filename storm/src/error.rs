@@ -1,4 +1,5 @@
-use crate::Fields;
+use crate::{Fields, ValidationRule};
+use serde::{ser::SerializeMap, Serialize, Serializer};
 use std::{
     fmt::{self, Debug, Display},
     mem::{replace, swap},
@@ -7,8 +8,15 @@ use std::{
 pub enum Error {
     AlreadyInTransaction,
     AsyncCellLock(async_cell_lock::Error),
+    /// The operation was cancelled by its cancellation token.
+    Cancelled,
     ClientInError,
     ColumnNull,
+    /// An error with the frames describing where it occurred, innermost first.
+    Context {
+        error: Box<Error>,
+        frames: Vec<ErrorFrame>,
+    },
     ConvertFailed(String),
     EntityNotFound,
     FieldTooLong {
@@ -20,21 +28,96 @@ pub enum Error {
     Multiple(Vec<Error>),
     NotInTransaction,
     ProviderNotFound,
+    /// The operation did not complete before its timeout or deadline.
+    Timeout,
     TransactionError,
     Std(StdError),
     Str(&'static str),
     String(String),
+    /// The entity belongs to another tenant than the one of the provider container.
+    TenantMismatch,
+    /// The entity is scoped by tenant but the provider container has no tenant.
+    TenantNotSet,
+    /// A policy of the entity denied the operation to the caller.
+    Unauthorized,
+    Validation {
+        field: Box<dyn Fields>,
+        rule: ValidationRule,
+    },
 
     #[cfg(feature = "mssql")]
     Mssql(tiberius::error::Error),
 }
 
 impl Error {
+    /// Stable code identifying the variant, suitable for API responses.
+    ///
+    /// `Context` reports the code of the error it wraps.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AlreadyInTransaction => "already_in_transaction",
+            Self::AsyncCellLock(_) => "async_cell_lock",
+            Self::Cancelled => "cancelled",
+            Self::ClientInError => "client_in_error",
+            Self::ColumnNull => "column_null",
+            Self::Context { error, .. } => error.code(),
+            Self::ConvertFailed(_) => "convert_failed",
+            Self::EntityNotFound => "entity_not_found",
+            Self::FieldTooLong { .. } => "field_too_long",
+            Self::Internal => "internal",
+            Self::Multiple(_) => "multiple",
+            Self::NotInTransaction => "not_in_transaction",
+            Self::ProviderNotFound => "provider_not_found",
+            Self::Timeout => "timeout",
+            Self::TransactionError => "transaction_error",
+            Self::Std(_) => "std",
+            Self::Str(_) | Self::String(_) => "message",
+            Self::TenantMismatch => "tenant_mismatch",
+            Self::TenantNotSet => "tenant_not_set",
+            Self::Unauthorized => "unauthorized",
+            Self::Validation { .. } => "validation",
+
+            #[cfg(feature = "mssql")]
+            Self::Mssql(_) => "mssql",
+        }
+    }
+
+    /// Adds a frame describing where the error occurred.
+    ///
+    /// A frame naming the same entity and operation as the innermost frame is merged into it,
+    /// the provider and the transaction layers both describe the same failure.
+    pub fn context(self, frame: ErrorFrame) -> Self {
+        match self {
+            Self::Context { error, mut frames } => {
+                match frames.last_mut() {
+                    Some(last)
+                        if last.entity == frame.entity && last.operation == frame.operation =>
+                    {
+                        last.column = last.column.take().or(frame.column);
+                        last.key = last.key.take().or(frame.key);
+                        last.provider = last.provider.or(frame.provider);
+                    }
+                    _ => frames.push(frame),
+                }
+
+                Self::Context { error, frames }
+            }
+            error => Self::Context {
+                error: Box::new(error),
+                frames: vec![frame],
+            },
+        }
+    }
+
     pub fn downcast<T>(self) -> Result<Box<T>, Self>
     where
         T: std::error::Error + 'static,
     {
         match self {
+            Self::Context { error, frames } => error.downcast().map_err(|error| Self::Context {
+                error: Box::new(error),
+                frames,
+            }),
             Self::Std(v) => v
                 .downcast()
                 .or_else(|v| v.downcast().map(|v| *v))
@@ -47,7 +130,7 @@ impl Error {
     where
         T: std::error::Error + 'static,
     {
-        match self {
+        match self.root() {
             Self::Std(v) => v.downcast_ref(),
             _ => None,
         }
@@ -82,12 +165,36 @@ impl Error {
 
     #[cfg(feature = "mssql")]
     pub fn as_mssql(&self) -> Option<&tiberius::error::Error> {
-        match self {
+        match self.root() {
             Self::Mssql(e) => Some(e),
             _ => None,
         }
     }
 
+    /// The context frames of the error, innermost first.
+    pub fn frames(&self) -> &[ErrorFrame] {
+        match self {
+            Self::Context { frames, .. } => frames,
+            _ => &[],
+        }
+    }
+
+    /// The error without its context frames.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Context { error, .. } => error.root(),
+            e => e,
+        }
+    }
+
+    /// Takes the error without its context frames.
+    pub fn into_root(self) -> Self {
+        match self {
+            Self::Context { error, .. } => error.into_root(),
+            e => e,
+        }
+    }
+
     pub fn std<E: Into<StdError>>(e: E) -> Self {
         Self::Std(e.into())
     }
@@ -97,6 +204,10 @@ impl Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::AsyncCellLock(e) => Debug::fmt(e, f),
+            Self::Context { error, frames } => {
+                Debug::fmt(error, f)?;
+                fmt_frames(frames, f)
+            }
             Self::Std(e) => Debug::fmt(e, f),
             Self::Str(e) => write!(f, "storm::Error::Str({e})"),
             Self::String(e) => write!(f, "storm::Error::Str({e})"),
@@ -114,21 +225,43 @@ impl Display for Error {
         match self {
             Self::AlreadyInTransaction => f.write_str("Already in transaction."),
             Self::AsyncCellLock(e) => Display::fmt(e, f),
+            Self::Cancelled => f.write_str("Operation cancelled."),
             Self::ClientInError => f.write_str("Client in error state."),
             Self::ColumnNull => f.write_str("Column is null."),
+            Self::Context { error, frames } => {
+                Display::fmt(error, f)?;
+                fmt_frames(frames, f)
+            }
             Self::ConvertFailed(s) => write!(f, "Convert failed: `{s}`"),
             Self::EntityNotFound => f.write_str("Entity not found."),
             Self::FieldTooLong { len, max, field } => {
                 write!(f, "{field} field too long, len: {len}, max {max}")
             }
             Self::Multiple(vec) => match &vec[..] {
+                [] => f.write_str("Multiple errors."),
                 [e] => Display::fmt(&e, f),
-                _ => f.write_str("Multiple errors"),
+                _ => {
+                    write!(f, "{} errors: ", vec.len())?;
+
+                    for (index, e) in vec.iter().enumerate() {
+                        if index > 0 {
+                            f.write_str("; ")?;
+                        }
+
+                        Display::fmt(e, f)?;
+                    }
+
+                    Ok(())
+                }
             },
             Self::TransactionError => f.write_str("Transaction error."),
             Self::Internal => f.write_str("Internal."),
             Self::NotInTransaction => f.write_str("Not in transaction."),
             Self::ProviderNotFound => f.write_str("Provider not found."),
+            Self::TenantMismatch => f.write_str("Tenant mismatch."),
+            Self::TenantNotSet => f.write_str("Tenant not set."),
+            Self::Timeout => f.write_str("Operation timed out."),
+            Self::Unauthorized => f.write_str("Unauthorized."),
 
             #[cfg(feature = "mssql")]
             Self::Mssql(e) => Display::fmt(e, f),
@@ -136,12 +269,164 @@ impl Display for Error {
             Self::Str(e) => Display::fmt(e, f),
             Self::String(e) => Display::fmt(e, f),
             Self::Std(e) => Display::fmt(e, f),
+            Self::Validation { field, rule } => write!(f, "{field} field is invalid, {rule}"),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Serializes the error tree as an object with a `code`, a `message`, the fields
+/// specific to the variant and the `context` frames when present.
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let root = self.root();
+        let mut map = serializer.serialize_map(None)?;
+
+        map.serialize_entry("code", root.code())?;
+        map.serialize_entry("message", &root.to_string())?;
+
+        match root {
+            Self::FieldTooLong { len, max, field } => {
+                map.serialize_entry("field", &field.to_string())?;
+                map.serialize_entry("len", len)?;
+                map.serialize_entry("max", max)?;
+            }
+            Self::Multiple(vec) => map.serialize_entry("errors", vec)?,
+            Self::Validation { field, rule } => {
+                map.serialize_entry("field", &field.to_string())?;
+                map.serialize_entry("rule", rule)?;
+            }
+
+            #[cfg(feature = "mssql")]
+            Self::Mssql(e) => {
+                if let Some(number) = e.code() {
+                    map.serialize_entry("number", &number)?;
+                }
+            }
+
+            _ => {}
+        }
+
+        let frames = self.frames();
+
+        if !frames.is_empty() {
+            map.serialize_entry("context", frames)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Describes where an error occurred.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorFrame {
+    /// The column read or written when the error occurred.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+
+    pub entity: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
+    pub operation: Operation,
+
+    /// The name of the provider in the `ProviderContainer`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<&'static str>,
+}
+
+impl ErrorFrame {
+    pub fn new(entity: &'static str, operation: Operation) -> Self {
+        Self {
+            column: None,
+            entity,
+            key: None,
+            operation,
+            provider: None,
+        }
+    }
+
+    pub fn column(mut self, name: &str) -> Self {
+        self.column = Some(name.to_string());
+        self
+    }
+
+    pub fn key<K: Debug + ?Sized>(mut self, key: &K) -> Self {
+        self.key = Some(format!("{key:?}"));
+        self
+    }
+
+    /// Sets the provider name, an empty name (the default provider) is ignored.
+    pub fn provider(mut self, name: &'static str) -> Self {
+        self.provider = Some(name).filter(|n| !n.is_empty());
+        self
+    }
+}
+
+impl Display for ErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.operation, self.entity)?;
+
+        if let Some(key) = &self.key {
+            write!(f, ", key: {key}")?;
+        }
+
+        if let Some(column) = &self.column {
+            write!(f, ", column: {column}")?;
+        }
+
+        if let Some(provider) = self.provider {
+            write!(f, ", provider: {provider}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    Delete,
+    Insert,
+    Load,
+    Migrate,
+    Remove,
+    Upsert,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Insert => "insert",
+            Self::Load => "load",
+            Self::Migrate => "migrate",
+            Self::Remove => "remove",
+            Self::Upsert => "upsert",
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn fmt_frames(frames: &[ErrorFrame], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for frame in frames {
+        write!(f, " [{frame}]")?;
+    }
+
+    Ok(())
+}
+
 impl From<async_cell_lock::Error> for Error {
     fn from(e: async_cell_lock::Error) -> Self {
         Error::AsyncCellLock(e)
@@ -186,3 +471,75 @@ fn check_downcast() {
 
     e.downcast::<MyErr>().unwrap();
 }
+
+#[test]
+fn check_serialize() {
+    #[derive(Debug)]
+    struct Name;
+
+    impl Display for Name {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("name")
+        }
+    }
+
+    impl Fields for Name {}
+
+    let mut e = Error::FieldTooLong {
+        len: 12,
+        max: 10,
+        field: Box::new(Name),
+    };
+
+    e.extend_one(Error::EntityNotFound);
+
+    let e = e.context(ErrorFrame::new("User", Operation::Insert).key(&3));
+
+    assert_eq!(e.code(), "multiple");
+    assert_eq!(
+        e.to_string(),
+        "2 errors: name field too long, len: 12, max 10; Entity not found. [insert User, key: 3]"
+    );
+
+    assert_eq!(
+        serde_json::to_value(&e).unwrap(),
+        serde_json::json!({
+            "code": "multiple",
+            "message": "2 errors: name field too long, len: 12, max 10; Entity not found.",
+            "errors": [
+                {
+                    "code": "field_too_long",
+                    "message": "name field too long, len: 12, max 10",
+                    "field": "name",
+                    "len": 12,
+                    "max": 10,
+                },
+                {
+                    "code": "entity_not_found",
+                    "message": "Entity not found.",
+                },
+            ],
+            "context": [{ "entity": "User", "key": "3", "operation": "insert" }],
+        })
+    );
+}
+
+#[test]
+fn check_context_merge() {
+    let e = Error::EntityNotFound
+        .context(ErrorFrame::new("User", Operation::Insert).provider("db"))
+        .context(ErrorFrame::new("User", Operation::Insert).key(&3))
+        .context(ErrorFrame::new("Role", Operation::Insert))
+        .context(ErrorFrame::new("Role", Operation::Insert).column("Name"));
+
+    assert_eq!(
+        e.frames()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "insert User, key: 3, provider: db".to_string(),
+            "insert Role, column: Name".to_string()
+        ]
+    );
+}
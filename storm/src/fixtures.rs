@@ -0,0 +1,257 @@
+use crate::{
+    Accessor, Ctx, CtxTransaction, CtxTypeInfo, Entity, EntityAccessor, Error, Insert, NotifyTag,
+    Result,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{cmp::Ordering, fs, path::Path};
+
+/// Entity rows of tests, read from and written to JSON or YAML files.
+///
+/// The document is an object with a list of rows per table, keyed by `CtxTypeInfo::NAME`:
+///
+/// ```yaml
+/// User:
+///   - key: 1
+///     entity:
+///       name: Alice
+/// ```
+///
+/// The rows are deserialized with serde and either loaded directly in the tables of a `Ctx`,
+/// bypassing the providers, or inserted through a transaction so the change handlers run.
+/// [Fixtures::dump] writes the tables of a `Ctx` back to the same format, ordered by key, to
+/// create golden files.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Fixtures(Map<String, Value>);
+
+#[derive(Deserialize, Serialize)]
+struct Row<K, E> {
+    key: K,
+    entity: E,
+}
+
+impl Fixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map(Self).map_err(Error::std)
+    }
+
+    pub fn from_yaml(s: &str) -> Result<Self> {
+        serde_yaml::from_str(s).map(Self).map_err(Error::std)
+    }
+
+    /// Reads a `.json`, `.yaml` or `.yml` file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let s = fs::read_to_string(path).map_err(Error::std)?;
+
+        match Format::of(path)? {
+            Format::Json => Self::from_json(&s),
+            Format::Yaml => Self::from_yaml(&s),
+        }
+    }
+
+    /// Writes a `.json`, `.yaml` or `.yml` file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        let s = match Format::of(path)? {
+            Format::Json => self.to_json()?,
+            Format::Yaml => self.to_yaml()?,
+        };
+
+        fs::write(path, s).map_err(Error::std)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.0).map_err(Error::std)
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(&self.0).map_err(Error::std)
+    }
+
+    /// The rows of the table of `E`, empty when the table is absent.
+    pub fn entities<E>(&self) -> Result<Vec<(E::Key, E)>>
+    where
+        E: CtxTypeInfo + Entity + DeserializeOwned,
+        E::Key: DeserializeOwned,
+    {
+        let Some(rows) = self.0.get(E::NAME) else {
+            return Ok(Vec::new());
+        };
+
+        let rows = Vec::<Row<E::Key, E>>::deserialize(rows).map_err(|e| {
+            Error::ConvertFailed(format!("Fixtures of `{}` are invalid: {e}", E::NAME))
+        })?;
+
+        Ok(rows.into_iter().map(|r| (r.key, r.entity)).collect())
+    }
+
+    /// Loads the rows of `E` directly in its table, without going through the providers nor
+    /// the change handlers. The table is created empty when it is not loaded yet.
+    pub fn load<E>(&self, ctx: &mut Ctx) -> Result<usize>
+    where
+        E: CtxTypeInfo + EntityAccessor + DeserializeOwned,
+        E::Key: DeserializeOwned,
+        E::Tbl: Accessor + Default + Extend<(E::Key, E)> + NotifyTag,
+    {
+        let rows = self.entities::<E>()?;
+        let count = rows.len();
+
+        ctx.vars().get_or_init(E::entity_var(), Default::default);
+        ctx.tbl_of_mut::<E>().ok_or(Error::Internal)?.extend(rows);
+
+        Ok(count)
+    }
+
+    /// Inserts the rows of `E` through a transaction, running the change handlers and saving
+    /// them with the providers.
+    pub async fn insert<E>(
+        &self,
+        trx: &mut CtxTransaction<'_>,
+        track: &E::TrackCtx,
+    ) -> Result<usize>
+    where
+        E: CtxTypeInfo + Entity + DeserializeOwned,
+        E::Key: DeserializeOwned,
+        for<'a> CtxTransaction<'a>: Insert<E>,
+    {
+        let rows = self.entities::<E>()?;
+        trx.insert_all(rows, track).await
+    }
+
+    /// Replaces the rows of `E` by the content of its table in `ctx`, ordered by key. Nothing
+    /// is written when the table is not loaded.
+    pub fn dump<E>(&mut self, ctx: &Ctx) -> Result<()>
+    where
+        E: CtxTypeInfo + EntityAccessor + Serialize,
+        E::Key: Serialize,
+        E::Tbl: Accessor,
+        for<'a> &'a E::Tbl: IntoIterator<Item = (&'a E::Key, &'a E)>,
+    {
+        let Some(tbl) = ctx.tbl_of_opt::<E>() else {
+            return Ok(());
+        };
+
+        self.dump_iter::<E, _>(tbl)
+    }
+
+    /// Replaces the rows of `E` by the entities of `iter`, ordered by key.
+    pub fn dump_iter<'a, E, I>(&mut self, iter: I) -> Result<()>
+    where
+        E: CtxTypeInfo + Entity + Serialize + 'a,
+        E::Key: Serialize + 'a,
+        I: IntoIterator<Item = (&'a E::Key, &'a E)>,
+    {
+        let mut rows = iter
+            .into_iter()
+            .map(|(key, entity)| serde_json::to_value(Row { key, entity }))
+            .collect::<serde_json::Result<Vec<_>>>()
+            .map_err(Error::std)?;
+
+        rows.sort_by(|a, b| cmp_values(a.get("key"), b.get("key")));
+
+        self.0.insert(E::NAME.to_string(), Value::Array(rows));
+        Ok(())
+    }
+}
+
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            _ => Err(Error::String(format!(
+                "Fixtures file `{}` must be a .json, .yaml or .yml file.",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Orders the keys numerically, then as strings and element by element for the composite keys.
+fn cmp_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .partial_cmp(&b.as_f64())
+                .unwrap_or(Ordering::Equal),
+        },
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        (Some(Value::Array(a)), Some(Value::Array(b))) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| cmp_values(Some(a), Some(b)))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (a, b) => a.map(Value::to_string).cmp(&b.map(Value::to_string)),
+    }
+}
+
+#[test]
+fn fixtures_formats() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct User {
+        name: String,
+    }
+
+    impl Entity for User {
+        type Key = (i32, String);
+        type TrackCtx = ();
+    }
+
+    impl CtxTypeInfo for User {
+        const NAME: &'static str = "User";
+    }
+
+    let yaml = "
+User:
+  - key: [10, b]
+    entity:
+      name: Bob
+  - key: [2, a]
+    entity:
+      name: Alice
+";
+
+    let fixtures = Fixtures::from_yaml(yaml).unwrap_or_default();
+    let users = fixtures.entities::<User>().unwrap_or_default();
+
+    assert_eq!(users.len(), 2);
+    assert_eq!(
+        users.first().map(|u| (&u.0 .1, &u.1.name[..])),
+        Some((&"b".to_string(), "Bob"))
+    );
+
+    // the rows are dumped ordered by key.
+    let mut dumped = Fixtures::new();
+    assert!(dumped
+        .dump_iter::<User, _>(users.iter().map(|(k, v)| (k, v)))
+        .is_ok());
+
+    let json = dumped.to_json().unwrap_or_default();
+    let users = Fixtures::from_json(&json)
+        .and_then(|f| f.entities::<User>())
+        .unwrap_or_default();
+
+    assert_eq!(
+        users.iter().map(|u| &u.1.name[..]).collect::<Vec<_>>(),
+        vec!["Alice", "Bob"]
+    );
+
+    assert!(matches!(
+        Fixtures::from_json(r#"{ "User": [{ "key": 1 }] }"#).and_then(|f| f.entities::<User>()),
+        Err(Error::ConvertFailed(_))
+    ));
+}
@@ -221,6 +221,9 @@ gc!(usize);
 #[cfg(feature = "chrono")]
 gc!(chrono::DateTime<chrono::FixedOffset>);
 
+#[cfg(feature = "chrono")]
+gc!(chrono::DateTime<chrono::Local>);
+
 #[cfg(feature = "chrono")]
 gc!(chrono::DateTime<chrono::Utc>);
 
@@ -236,6 +239,12 @@ gc!(chrono::NaiveTime);
 #[cfg(feature = "dec19x5")]
 gc!(dec19x5::Decimal);
 
+#[cfg(feature = "rust_decimal")]
+gc!(rust_decimal::Decimal);
+
+#[cfg(feature = "mssql")]
+gc!(tiberius::xml::XmlData);
+
 #[cfg(feature = "uuid")]
 gc!(uuid::Uuid);
 
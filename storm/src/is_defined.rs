@@ -35,3 +35,10 @@ is_defined!(u32);
 is_defined!(u64);
 is_defined!(u8);
 is_defined!(usize);
+
+#[cfg(feature = "uuid")]
+impl IsDefined for uuid::Uuid {
+    fn is_defined(&self) -> bool {
+        !self.is_nil()
+    }
+}
@@ -14,6 +14,8 @@ mod entity_of;
 mod entity_validate;
 mod error;
 mod fields;
+#[cfg(feature = "fixtures")]
+mod fixtures;
 pub mod gc;
 mod get;
 mod get_mut;
@@ -29,6 +31,8 @@ mod on_change;
 mod on_changed;
 mod on_remove;
 mod one_to_many;
+mod policy;
+mod preload;
 pub mod prelude;
 pub mod provider;
 mod remove;
@@ -37,9 +41,13 @@ mod tag;
 #[cfg(feature = "telemetry")]
 #[doc(hidden)]
 pub mod telemetry;
+mod tenant_registry;
+mod timeout;
 mod transaction;
+mod translations;
 mod trx_err_gate;
 pub mod trx_iter;
+mod validate;
 mod vec_table;
 
 pub use accessor::*;
@@ -57,8 +65,10 @@ pub use entity::Entity;
 pub use entity_fields::{EntityFields, FieldsOrStr};
 pub use entity_of::EntityOf;
 pub use entity_validate::EntityValidate;
-pub use error::Error;
+pub use error::{Error, ErrorFrame, Operation};
 pub use fields::Fields;
+#[cfg(feature = "fixtures")]
+pub use fixtures::Fixtures;
 pub use gc::*;
 pub use get::Get;
 pub use get_mut::GetMut;
@@ -77,19 +87,36 @@ pub use on_remove::{OnRemove, RemoveHandler};
 pub use once_cell::sync::OnceCell;
 pub use one_to_many::{OneToMany, OneToManyFromIter};
 pub use parking_lot;
+pub use policy::{Authorized, EntityPolicy, Policy};
+pub use preload::{Preload, PreloadReport, PreloadTiming, DEFAULT_PRELOAD_PARALLELISM};
 pub use provider::ProviderContainer;
+#[cfg(feature = "regex")]
+pub use regex;
 pub use remove::Remove;
+#[doc(hidden)]
+pub use serde;
 pub use state::LogState;
 pub use tag::{NotifyTag, Tag};
+pub use tenant_registry::TenantRegistry;
+pub use timeout::{with_deadline, with_timeout};
 pub use tokio;
+pub use tokio_util::sync::CancellationToken;
 pub use transaction::Transaction;
+pub use translations::{FallbackChain, Translations};
 use trx_err_gate::TrxErrGate;
 pub use trx_iter::TrxIter;
+#[cfg(feature = "regex")]
+pub use validate::macro_check_regex;
+pub use validate::{
+    macro_check_custom, macro_check_min_len, macro_check_not_empty, macro_check_one_of,
+    macro_check_range, ValidateValue, ValidationRule,
+};
 pub use vec_map::{self, VecMap};
 pub use vec_table::VecTable;
 pub use version_tag::{self, VersionTag};
 
 pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + 'a + Send>>;
+pub type BoxStream<'a, T> = std::pin::Pin<Box<dyn futures::Stream<Item = T> + 'a + Send>>;
 pub type Log<E> = fxhash::FxHashMap<<E as Entity>::Key, LogState<E>>;
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -97,10 +124,14 @@ pub const EV_CREATED: &str = "created";
 pub const OBJ_INDEX: &str = "index";
 pub const OBJ_TABLE: &str = "table";
 
-#[cfg(feature = "derive")]
-pub use storm_derive::{indexing, Ctx, LocksAwait, NoopDelete, NoopLoad, NoopSave};
 #[cfg(feature = "mssql")]
-pub use storm_derive::{MssqlDelete, MssqlLoad, MssqlSave};
+pub use storm_derive::{
+    embed_migrations, FromRow, MssqlDelete, MssqlFlatten, MssqlLoad, MssqlSave, SqlEnum,
+};
+#[cfg(feature = "derive")]
+pub use storm_derive::{
+    indexing, Ctx, EntityValidate, LocksAwait, NoopDelete, NoopLoad, NoopSave, StormKey,
+};
 
 #[macro_export]
 macro_rules! tri {
@@ -0,0 +1,225 @@
+use crate::{Entity, Error, Get, Result};
+use std::{marker::PhantomData, sync::Arc};
+
+/// Row-level authorization of an entity, the caller is described by the `TrackCtx` passed to
+/// the writes and to [Authorized] views.
+///
+/// Every method allows by default.
+pub trait Policy<E: Entity> {
+    /// Whether the entity is visible to the caller through an [Authorized] view.
+    fn can_read(&self, _caller: &E::TrackCtx, _key: &E::Key, _entity: &E) -> bool {
+        true
+    }
+
+    /// Checked before the `on_change` handlers, `old` is the current version in the transaction.
+    fn can_insert(&self, _caller: &E::TrackCtx, _key: &E::Key, _new: &E, _old: Option<&E>) -> bool {
+        true
+    }
+
+    /// Checked before the `on_remove` handlers, `old` is the current version in the transaction.
+    fn can_remove(&self, _caller: &E::TrackCtx, _key: &E::Key, _old: Option<&E>) -> bool {
+        true
+    }
+}
+
+type ArcPolicy<E> = Arc<dyn Policy<E> + Send + Sync>;
+type Policies<E> = Arc<Box<[ArcPolicy<E>]>>;
+
+/// The policies registered for an entity, an operation is allowed only if all of them allow it.
+pub struct EntityPolicy<E>(parking_lot::Mutex<Policies<E>>);
+
+impl<E: Entity> EntityPolicy<E> {
+    pub fn register<P: Policy<E> + Send + Sync + 'static>(&self, policy: P) {
+        let mut gate = self.0.lock();
+        let mut vec = Vec::with_capacity(gate.len() + 1);
+
+        vec.extend(gate.iter().cloned());
+        vec.push(Arc::new(policy) as ArcPolicy<E>);
+
+        *gate = Arc::new(vec.into_boxed_slice());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().is_empty()
+    }
+
+    pub fn can_read(&self, caller: &E::TrackCtx, key: &E::Key, entity: &E) -> bool {
+        can_read(&self.policies(), caller, key, entity)
+    }
+
+    /// Fails with [Error::Unauthorized] if a policy denies the insert.
+    pub fn check_insert(
+        &self,
+        caller: &E::TrackCtx,
+        key: &E::Key,
+        new: &E,
+        old: Option<&E>,
+    ) -> Result<()> {
+        match self
+            .policies()
+            .iter()
+            .all(|p| p.can_insert(caller, key, new, old))
+        {
+            true => Ok(()),
+            false => Err(Error::Unauthorized),
+        }
+    }
+
+    /// Fails with [Error::Unauthorized] if a policy denies the remove.
+    pub fn check_remove(&self, caller: &E::TrackCtx, key: &E::Key, old: Option<&E>) -> Result<()> {
+        match self
+            .policies()
+            .iter()
+            .all(|p| p.can_remove(caller, key, old))
+        {
+            true => Ok(()),
+            false => Err(Error::Unauthorized),
+        }
+    }
+
+    fn policies(&self) -> Policies<E> {
+        Arc::clone(&self.0.lock())
+    }
+}
+
+impl<E> Default for EntityPolicy<E> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+fn can_read<E: Entity>(
+    policies: &[ArcPolicy<E>],
+    caller: &E::TrackCtx,
+    key: &E::Key,
+    entity: &E,
+) -> bool {
+    policies.iter().all(|p| p.can_read(caller, key, entity))
+}
+
+/// A view over a table (`HashTable`, `VecTable` or `TblTransaction`) showing only the entities
+/// the caller can read.
+///
+/// The policies are captured when the view is created.
+pub struct Authorized<'a, E: Entity, T> {
+    caller: &'a E::TrackCtx,
+    policies: Policies<E>,
+    tbl: &'a T,
+    _e: PhantomData<E>,
+}
+
+impl<'a, E: Entity, T> Authorized<'a, E, T> {
+    pub fn new(tbl: &'a T, caller: &'a E::TrackCtx, policy: &EntityPolicy<E>) -> Self {
+        Self {
+            caller,
+            policies: policy.policies(),
+            tbl,
+            _e: PhantomData,
+        }
+    }
+
+    pub fn caller(&self) -> &'a E::TrackCtx {
+        self.caller
+    }
+
+    pub fn contains_key(&self, key: &E::Key) -> bool
+    where
+        T: Get<E>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// The entity, `None` if it does not exist or the caller cannot read it.
+    pub fn get(&self, key: &E::Key) -> Option<&'a E>
+    where
+        T: Get<E>,
+    {
+        let tbl: &'a T = self.tbl;
+
+        tbl.get(key)
+            .filter(|e| can_read(&self.policies, self.caller, key, e))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'a E::Key, &'a E)> + '_
+    where
+        &'a T: IntoIterator<Item = (&'a E::Key, &'a E)>,
+    {
+        self.tbl
+            .into_iter()
+            .filter(move |(k, e)| can_read(&self.policies, self.caller, k, e))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &'a E::Key> + '_
+    where
+        &'a T: IntoIterator<Item = (&'a E::Key, &'a E)>,
+    {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &'a E> + '_
+    where
+        &'a T: IntoIterator<Item = (&'a E::Key, &'a E)>,
+    {
+        self.iter().map(|(_, e)| e)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct Doc {
+    owner: u32,
+}
+
+#[cfg(test)]
+impl Entity for Doc {
+    type Key = u32;
+    type TrackCtx = u32;
+}
+
+#[cfg(test)]
+impl crate::CtxTypeInfo for Doc {
+    const NAME: &'static str = "Doc";
+}
+
+#[cfg(test)]
+struct OwnerPolicy;
+
+#[cfg(test)]
+impl Policy<Doc> for OwnerPolicy {
+    fn can_read(&self, caller: &u32, _key: &u32, entity: &Doc) -> bool {
+        entity.owner == *caller
+    }
+
+    fn can_remove(&self, caller: &u32, _key: &u32, old: Option<&Doc>) -> bool {
+        old.is_none_or(|d| d.owner == *caller)
+    }
+}
+
+#[test]
+fn authorized_views() {
+    let policy = EntityPolicy::<Doc>::default();
+    policy.register(OwnerPolicy);
+
+    let mut tbl = crate::HashTable::<Doc>::new();
+    tbl.extend([
+        (1, Doc { owner: 10 }),
+        (2, Doc { owner: 20 }),
+        (3, Doc { owner: 10 }),
+    ]);
+
+    let view = Authorized::new(&tbl, &10, &policy);
+    let mut keys = view.keys().copied().collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    assert_eq!(keys, vec![1, 3]);
+    assert!(view.contains_key(&1));
+    assert!(view.get(&2).is_none());
+
+    let doc = Doc { owner: 20 };
+    assert!(policy.check_insert(&10, &2, &doc, tbl.get(&2)).is_ok());
+    assert!(policy.check_remove(&20, &2, tbl.get(&2)).is_ok());
+    assert!(matches!(
+        policy.check_remove(&10, &2, tbl.get(&2)),
+        Err(Error::Unauthorized)
+    ));
+}
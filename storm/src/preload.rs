@@ -0,0 +1,151 @@
+use crate::{AsRefAsync, BoxFuture, Ctx, EntityAccessor, Error, Result};
+use futures::{stream, StreamExt};
+use std::{
+    any::type_name,
+    time::{Duration, Instant},
+};
+
+/// The default number of tables loaded at the same time by [Preload].
+pub const DEFAULT_PRELOAD_PARALLELISM: usize = 8;
+
+impl Ctx {
+    /// Prepares the concurrent loading of a set of tables or indexes, usually at startup.
+    ///
+    /// ```ignore
+    /// let report = ctx
+    ///     .preload()
+    ///     .tbl::<User>()
+    ///     .ref_as::<UserByEmail>()
+    ///     .parallelism(4)
+    ///     .run()
+    ///     .await?;
+    /// ```
+    pub fn preload(&self) -> Preload<'_> {
+        Preload {
+            ctx: self,
+            loaders: Vec::new(),
+            parallelism: DEFAULT_PRELOAD_PARALLELISM,
+        }
+    }
+}
+
+/// A set of tables and indexes loaded concurrently by [Ctx::preload].
+///
+/// Each table is loaded only once, even if it is requested at the same time by another task.
+pub struct Preload<'a> {
+    ctx: &'a Ctx,
+    loaders: Vec<Loader<'a>>,
+    parallelism: usize,
+}
+
+type Loader<'a> = (&'static str, BoxFuture<'a, Result<()>>);
+
+impl<'a> Preload<'a> {
+    /// Maximum number of tables loaded at the same time. A value of 0 is treated as 1.
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Adds a table, an index or any value the context can provide.
+    pub fn ref_as<T>(mut self) -> Self
+    where
+        Ctx: AsRefAsync<T>,
+        T: 'a,
+    {
+        let ctx = self.ctx;
+
+        self.loaders.push((
+            type_name::<T>(),
+            Box::pin(async move {
+                AsRefAsync::<T>::as_ref_async(ctx).await?;
+                Ok(())
+            }),
+        ));
+
+        self
+    }
+
+    /// Adds the table of an entity.
+    pub fn tbl<E>(self) -> Self
+    where
+        E: EntityAccessor,
+        Ctx: AsRefAsync<E::Tbl>,
+        E::Tbl: 'a,
+    {
+        self.ref_as::<E::Tbl>()
+    }
+
+    /// Loads all the tables, at most `parallelism` at the same time.
+    ///
+    /// All the tables are attempted even if some of them fail; the errors are then
+    /// returned together.
+    pub async fn run(self) -> Result<PreloadReport> {
+        // the futures are created beforehand to keep the returned future `Send`.
+        let loaders = self
+            .loaders
+            .into_iter()
+            .map(|(name, loader)| timed(name, loader))
+            .collect::<Vec<_>>();
+
+        let results = stream::iter(loaders)
+            .buffer_unordered(self.parallelism)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut error = None;
+        let mut timings = Vec::with_capacity(results.len());
+
+        for (timing, result) in results {
+            if let Err(e) = result {
+                Error::extend_one_opt(&mut error, e);
+            }
+
+            timings.push(timing);
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(PreloadReport { timings }),
+        }
+    }
+}
+
+async fn timed(
+    name: &'static str,
+    loader: BoxFuture<'_, Result<()>>,
+) -> (PreloadTiming, Result<()>) {
+    let start = Instant::now();
+    let result = loader.await;
+
+    (
+        PreloadTiming {
+            name,
+            elapsed: start.elapsed(),
+        },
+        result,
+    )
+}
+
+/// The time taken by each table of a [Preload], in completion order.
+#[derive(Clone, Debug, Default)]
+pub struct PreloadReport {
+    pub timings: Vec<PreloadTiming>,
+}
+
+impl PreloadReport {
+    /// The slowest table to load.
+    pub fn slowest(&self) -> Option<&PreloadTiming> {
+        self.timings.iter().max_by_key(|t| t.elapsed)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PreloadTiming {
+    /// The type name of the table or index.
+    pub name: &'static str,
+
+    /// The time taken to load it, including the time waiting on another task loading
+    /// the same table. A table already loaded is close to zero.
+    pub elapsed: Duration,
+}
@@ -11,10 +11,13 @@ impl CastProvider {
     pub fn new(p: impl Provider) -> Self {
         let provider = Box::new(p);
 
-        Self {
-            downcast: &*provider,
-            provider,
-        }
+        #[cfg(feature = "fault-injection")]
+        let downcast = provider.provided();
+
+        #[cfg(not(feature = "fault-injection"))]
+        let downcast = &*provider;
+
+        Self { downcast, provider }
     }
 
     pub fn downcast<T: 'static>(&self) -> Option<&T> {
@@ -0,0 +1,326 @@
+use super::{Provider, ProviderFactory};
+use crate::{BoxFuture, Error, Result};
+use fxhash::FxHashMap;
+use parking_lot::Mutex;
+use std::{
+    any::{Any, TypeId},
+    fmt::{self, Display},
+    sync::Arc,
+    time::Duration,
+};
+
+/// The operations a [FaultFactory] can fail or delay.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FaultOp {
+    Commit,
+    Delete,
+    Load,
+    Upsert,
+}
+
+impl FaultOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Commit => "commit",
+            Self::Delete => "delete",
+            Self::Load => "load",
+            Self::Upsert => "upsert",
+        }
+    }
+}
+
+impl Display for FaultOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The script of the faults of a [FaultFactory], shared with the tests to add faults and
+/// count the calls after the factory is registered.
+///
+/// The entity operations are the ones done through a `Ctx`: the loads of the tables, the
+/// upserts of the inserts and the deletes of the removes.
+#[derive(Clone, Default)]
+pub struct Faults(Arc<Mutex<State>>);
+
+#[derive(Default)]
+struct State {
+    calls: FxHashMap<(FaultOp, Option<TypeId>), usize>,
+    cancels: usize,
+    dropped: bool,
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    action: Action,
+    entity: Option<TypeId>,
+    nth: Option<usize>,
+    op: FaultOp,
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Drop,
+    Fail,
+    Latency(Duration),
+}
+
+impl Faults {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of calls of `op` on `E`, the failed ones included.
+    pub fn calls<E: 'static>(&self, op: FaultOp) -> usize {
+        self.count(op, Some(TypeId::of::<E>()))
+    }
+
+    /// The number of times the transaction of the provider was cancelled.
+    pub fn cancels(&self) -> usize {
+        self.0.lock().cancels
+    }
+
+    /// The number of commits, the failed ones included.
+    pub fn commits(&self) -> usize {
+        self.count(FaultOp::Commit, None)
+    }
+
+    /// Simulates a connection drop at the `nth` call of `op` on `E`, starting at 1. The call
+    /// and every later one, commits and new providers included, fail with
+    /// `Error::ClientInError` until [Faults::reconnect].
+    pub fn drop_connection<E: 'static>(&self, op: FaultOp, nth: usize) {
+        self.push(op, Some(TypeId::of::<E>()), Some(nth), Action::Drop);
+    }
+
+    /// Fails the `nth` call of `op` on `E`, starting at 1.
+    pub fn fail<E: 'static>(&self, op: FaultOp, nth: usize) {
+        self.push(op, Some(TypeId::of::<E>()), Some(nth), Action::Fail);
+    }
+
+    /// Fails the `nth` commit, starting at 1.
+    pub fn fail_commit(&self, nth: usize) {
+        self.push(FaultOp::Commit, None, Some(nth), Action::Fail);
+    }
+
+    /// Delays every call of `op` on `E`.
+    pub fn latency<E: 'static>(&self, op: FaultOp, delay: Duration) {
+        self.push(op, Some(TypeId::of::<E>()), None, Action::Latency(delay));
+    }
+
+    /// Delays every commit.
+    pub fn commit_latency(&self, delay: Duration) {
+        self.push(FaultOp::Commit, None, None, Action::Latency(delay));
+    }
+
+    /// Ends a connection drop.
+    pub fn reconnect(&self) {
+        self.0.lock().dropped = false;
+    }
+
+    /// Removes the faults and resets the counters.
+    pub fn reset(&self) {
+        *self.0.lock() = State::default();
+    }
+
+    fn count(&self, op: FaultOp, entity: Option<TypeId>) -> usize {
+        self.0
+            .lock()
+            .calls
+            .get(&(op, entity))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn push(&self, op: FaultOp, entity: Option<TypeId>, nth: Option<usize>, action: Action) {
+        self.0.lock().rules.push(Rule {
+            action,
+            entity,
+            nth,
+            op,
+        });
+    }
+
+    fn inject(
+        &self,
+        op: FaultOp,
+        entity: Option<TypeId>,
+    ) -> Option<BoxFuture<'static, Result<()>>> {
+        let mut state = self.0.lock();
+        let count = state.calls.entry((op, entity)).or_default();
+
+        *count += 1;
+
+        let count = *count;
+        let mut delay = Duration::ZERO;
+        let mut error = None;
+
+        for rule in &state.rules {
+            if rule.op != op || rule.entity != entity || rule.nth.is_some_and(|n| n != count) {
+                continue;
+            }
+
+            match rule.action {
+                Action::Drop => error = Some(Action::Drop),
+                Action::Fail => error = error.or(Some(Action::Fail)),
+                Action::Latency(d) => delay += d,
+            }
+        }
+
+        if let Some(Action::Drop) = error {
+            state.dropped = true;
+        }
+
+        let error = match (state.dropped, error) {
+            (true, _) => Some(Error::ClientInError),
+            (false, Some(_)) => Some(Error::String(format!("Injected {op} fault #{count}."))),
+            (false, None) => None,
+        };
+
+        if error.is_none() && delay.is_zero() {
+            return None;
+        }
+
+        Some(Box::pin(async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+
+            match error {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        }))
+    }
+}
+
+/// Decorates a [ProviderFactory] to fail or delay the operations as scripted by its
+/// [Faults], to test the error paths of the transactions deterministically.
+///
+/// The decorated provider is still provided under its own type, the entities saved through
+/// it are not aware of the faults. The entity faults only apply to the entities whose
+/// provider is the name the factory is registered under.
+///
+/// Only available with the `fault-injection` feature.
+///
+/// ```ignore
+/// let factory = FaultFactory::new(MssqlFactory(config));
+/// let faults = factory.faults();
+///
+/// faults.fail::<User>(FaultOp::Upsert, 2);
+/// faults.fail_commit(1);
+///
+/// container.register("", factory);
+/// ```
+pub struct FaultFactory<F> {
+    factory: F,
+    faults: Faults,
+}
+
+impl<F> FaultFactory<F> {
+    pub fn new(factory: F) -> Self {
+        Self::with_faults(factory, Faults::new())
+    }
+
+    pub fn with_faults(factory: F, faults: Faults) -> Self {
+        Self { factory, faults }
+    }
+
+    pub fn faults(&self) -> Faults {
+        self.faults.clone()
+    }
+}
+
+impl<F: ProviderFactory> ProviderFactory for FaultFactory<F> {
+    type Provider = FaultProvider<F::Provider>;
+
+    fn create_provider(&self) -> BoxFuture<'_, Result<Self::Provider>> {
+        Box::pin(async move {
+            if self.faults.0.lock().dropped {
+                return Err(Error::ClientInError);
+            }
+
+            Ok(FaultProvider {
+                faults: self.faults.clone(),
+                provider: self.factory.create_provider().await?,
+            })
+        })
+    }
+
+    fn inject_fault(&self, op: FaultOp, entity: TypeId) -> Option<BoxFuture<'static, Result<()>>> {
+        self.faults.inject(op, Some(entity))
+    }
+
+    fn provided_type() -> TypeId {
+        F::provided_type()
+    }
+}
+
+/// The provider of a [FaultFactory], failing or delaying its commits.
+pub struct FaultProvider<P> {
+    faults: Faults,
+    provider: P,
+}
+
+impl<P: Provider> Provider for FaultProvider<P> {
+    fn cancel(&self) {
+        self.faults.0.lock().cancels += 1;
+        self.provider.cancel();
+    }
+
+    fn commit(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            if let Some(fault) = self.faults.inject(FaultOp::Commit, None) {
+                if let Err(e) = fault.await {
+                    // the transaction is lost like when the connection fails.
+                    self.provider.cancel();
+                    return Err(e);
+                }
+            }
+
+            self.provider.commit().await
+        })
+    }
+
+    fn gc(&self) {
+        self.provider.gc();
+    }
+
+    fn provided(&self) -> &(dyn Any + Send + Sync) {
+        self.provider.provided()
+    }
+}
+
+#[test]
+fn faults_script() {
+    struct User;
+
+    let faults = Faults::new();
+    faults.fail::<User>(FaultOp::Upsert, 2);
+    faults.drop_connection::<User>(FaultOp::Delete, 1);
+
+    let user = Some(TypeId::of::<User>());
+    let run = |op| faults.inject(op, user).map(futures::executor::block_on);
+
+    assert!(run(FaultOp::Upsert).is_none());
+    assert!(
+        matches!(run(FaultOp::Upsert), Some(Err(Error::String(s))) if s == "Injected upsert fault #2.")
+    );
+    assert!(run(FaultOp::Upsert).is_none());
+    assert!(run(FaultOp::Load).is_none());
+
+    // once dropped, everything fails until the reconnection.
+    assert!(matches!(
+        run(FaultOp::Delete),
+        Some(Err(Error::ClientInError))
+    ));
+    assert!(matches!(
+        run(FaultOp::Load),
+        Some(Err(Error::ClientInError))
+    ));
+
+    faults.reconnect();
+
+    assert!(run(FaultOp::Delete).is_none());
+    assert_eq!(faults.calls::<User>(FaultOp::Upsert), 3);
+    assert_eq!(faults.calls::<User>(FaultOp::Delete), 2);
+}
@@ -1,8 +1,18 @@
-use crate::{BoxFuture, Entity, Result};
+use crate::{BoxFuture, BoxStream, CancellationToken, Entity, Result};
+use std::time::Duration;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct LoadArgs {
     pub use_transaction: bool,
+
+    /// Fails the load with `Error::Timeout` if it takes longer.
+    pub timeout: Option<Duration>,
+
+    /// Fails the load with `Error::Cancelled` when the token is cancelled.
+    pub cancel: Option<CancellationToken>,
+
+    /// Includes the rows marked as deleted of the entities using a soft delete.
+    pub include_deleted: bool,
 }
 
 pub trait LoadAll<E: Entity, FILTER: Send + Sync, C>: Send + Sync
@@ -36,6 +46,43 @@ where
     }
 }
 
+/// Loads the entities one at a time as the stream is polled, without collecting them.
+///
+/// Useful for the exports of large tables. The connection of the provider is held until the
+/// stream is exhausted or dropped; the timeout and the cancellation of the [LoadArgs] apply
+/// until then. The entities with translated fields, filled by a second query, are not
+/// streamed.
+#[diagnostic::on_unimplemented(
+    message = "`{E}` cannot be streamed with the filter `{FILTER}`",
+    note = "the entities with translated fields are not streamed, load them with `LoadAll`"
+)]
+pub trait LoadAllStream<E: Entity, FILTER: Send + Sync>: Send + Sync {
+    fn load_all_stream_with_args<'a>(
+        &'a self,
+        filter: &'a FILTER,
+        args: LoadArgs,
+    ) -> BoxStream<'a, Result<(E::Key, E)>>;
+
+    fn load_all_stream<'a>(&'a self, filter: &'a FILTER) -> BoxStream<'a, Result<(E::Key, E)>> {
+        self.load_all_stream_with_args(filter, LoadArgs::default())
+    }
+}
+
+impl<E, FILTER, P> LoadAllStream<E, FILTER> for &P
+where
+    E: Entity,
+    FILTER: Send + Sync,
+    P: LoadAllStream<E, FILTER>,
+{
+    fn load_all_stream_with_args<'a>(
+        &'a self,
+        filter: &'a FILTER,
+        args: LoadArgs,
+    ) -> BoxStream<'a, Result<(E::Key, E)>> {
+        (**self).load_all_stream_with_args(filter, args)
+    }
+}
+
 pub struct LoadAllKeyOnly<E: Entity>(Vec<E::Key>);
 
 impl<E: Entity> LoadAllKeyOnly<E> {
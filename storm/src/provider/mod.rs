@@ -1,5 +1,7 @@
 mod cast_provider;
 mod delete;
+#[cfg(feature = "fault-injection")]
+mod fault;
 mod load_all;
 mod load_one;
 #[allow(clippy::module_inception)]
@@ -11,6 +13,8 @@ mod upsert;
 
 use cast_provider::CastProvider;
 pub use delete::Delete;
+#[cfg(feature = "fault-injection")]
+pub use fault::{FaultFactory, FaultOp, FaultProvider, Faults};
 pub use load_all::*;
 pub use load_one::*;
 pub use provider::Provider;
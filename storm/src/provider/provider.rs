@@ -4,4 +4,18 @@ use std::any::Any;
 pub trait Provider: Any + Send + Sync {
     fn cancel(&self);
     fn commit(&self) -> BoxFuture<'_, Result<()>>;
+
+    /// Called by [ProviderContainer::gc](super::ProviderContainer::gc) on the providers
+    /// still in use, to release idle resources such as pooled connections.
+    fn gc(&self) {}
+
+    /// The provider returned by [ProviderContainer::provide](super::ProviderContainer::provide),
+    /// a decorating provider returns the one it wraps.
+    #[cfg(feature = "fault-injection")]
+    fn provided(&self) -> &(dyn Any + Send + Sync)
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
@@ -1,12 +1,20 @@
+#[cfg(feature = "fault-injection")]
+use super::FaultOp;
 use super::{CastProvider, Provider, ProviderFactory, TransactionProvider};
+#[cfg(feature = "fault-injection")]
+use crate::CtxTypeInfo;
 use crate::{BoxFuture, Error, Result};
 use async_cell_lock::AsyncOnceCell;
+use fxhash::FxHashMap;
 use std::{
-    any::TypeId,
+    any::{Any, TypeId},
     marker::PhantomData,
-    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
 };
-use tokio::sync::{Mutex, MutexGuard};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 use tracing::error;
 
 /// Last recent use counter
@@ -15,6 +23,9 @@ type Lru = AtomicU64;
 /// A trait that wrap the ProviderFactory to be able to use it in a Box<Any> trait object context.
 trait AnyFactory: Send + Sync + 'static {
     fn create(&self) -> BoxFuture<'_, Result<CastProvider>>;
+
+    #[cfg(feature = "fault-injection")]
+    fn inject_fault(&self, op: FaultOp, entity: TypeId) -> Option<BoxFuture<'static, Result<()>>>;
 }
 
 /// Wrap a ProviderFactory trait to be able to use it in a Box<Any> trait object context.
@@ -41,6 +52,11 @@ where
     fn create(&self) -> BoxFuture<'_, Result<CastProvider>> {
         Box::pin(async move { Ok(CastProvider::new(self.factory.create_provider().await?)) })
     }
+
+    #[cfg(feature = "fault-injection")]
+    fn inject_fault(&self, op: FaultOp, entity: TypeId) -> Option<BoxFuture<'static, Result<()>>> {
+        self.factory.inject_fault(op, entity)
+    }
 }
 
 /// A dependency container to be able to instantiate and provide connection to databases.
@@ -48,9 +64,11 @@ where
 /// A database provider can be named and have a type.
 pub struct ProviderContainer {
     last_gc: u64,
-    lock: Mutex<()>,
     lru: Lru,
     records: Vec<Rec>,
+    table_gates: parking_lot::Mutex<FxHashMap<TypeId, Arc<Mutex<()>>>>,
+    tenant: Option<Arc<dyn Any + Send + Sync>>,
+    tenant_param: Option<Arc<dyn Any + Send + Sync>>,
 }
 
 impl ProviderContainer {
@@ -69,28 +87,74 @@ impl ProviderContainer {
         }
     }
 
-    pub(crate) async fn gate(&self) -> MutexGuard<'_, ()> {
-        self.lock.lock().await
+    /// Scopes the container to a tenant. The entities having a tenant column are loaded,
+    /// saved and deleted for this tenant only.
+    pub fn with_tenant<T: Any + Send + Sync>(mut self, tenant: T) -> Self {
+        self.tenant = Some(Arc::new(tenant));
+        self
+    }
+
+    /// The tenant of the container, `None` when there is no tenant or it is of another type.
+    pub fn tenant<T: Any>(&self) -> Option<&T> {
+        self.tenant.as_deref()?.downcast_ref()
+    }
+
+    pub fn tenant_any(&self) -> Option<&(dyn Any + Send + Sync)> {
+        self.tenant.as_deref()
+    }
+
+    /// Sets how a provider passes the tenant to its queries, the providers offer a helper
+    /// checking at compile time that the type of the tenant is supported.
+    pub fn with_tenant_param<P: Any + Send + Sync>(mut self, param: P) -> Self {
+        self.tenant_param = Some(Arc::new(param));
+        self
+    }
+
+    pub fn tenant_param<P: Any>(&self) -> Option<&P> {
+        self.tenant_param.as_deref()?.downcast_ref()
+    }
+
+    /// Applies the faults scripted on the factories registered under the provider name of `E`
+    /// to an operation on `E`.
+    #[cfg(feature = "fault-injection")]
+    pub(crate) async fn inject_faults<E: CtxTypeInfo + 'static>(&self, op: FaultOp) -> Result<()> {
+        for rec in self.records.iter().filter(|r| &*r.name == E::PROVIDER) {
+            if let Some(fault) = rec.factory.inject_fault(op, TypeId::of::<E>()) {
+                fault.await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Locks the loading of a table, identified by its entity type, without blocking the
+    /// loading of the other tables.
+    pub(crate) async fn table_gate(&self, type_id: TypeId) -> OwnedMutexGuard<()> {
+        let gate = Arc::clone(self.table_gates.lock().entry(type_id).or_default());
+        gate.lock_owned().await
     }
 
     /// A method to garbage collect all unused provider. This is intended to close database
-    /// connections and release resources.
+    /// connections and release resources. The providers still in use are asked to release
+    /// their idle resources.
     pub fn gc(&mut self) {
         let last_gc = self.last_gc;
         let new_gc = *self.lru.get_mut();
+        let collect = last_gc != new_gc;
 
-        if last_gc != new_gc {
-            for r in &mut self.records {
-                if r.provider
-                    .get_mut()
-                    .map_or(false, |r| *r.lru.get_mut() <= last_gc)
-                {
-                    r.provider.take();
-                }
-            }
+        for r in &mut self.records {
+            let Some(provider_rec) = r.provider.get_mut() else {
+                continue;
+            };
 
-            self.last_gc = new_gc;
+            if collect && *provider_rec.lru.get_mut() <= last_gc {
+                r.provider.take();
+            } else {
+                provider_rec.cast_provider.provider().gc();
+            }
         }
+
+        self.last_gc = new_gc;
     }
 
     /// Gets or creates a database provider that have been previously registered with
@@ -120,6 +184,9 @@ impl ProviderContainer {
             factory: Box::new(Factory::new(factory)),
             name: name.into(),
             provider: AsyncOnceCell::new(),
+            #[cfg(feature = "fault-injection")]
+            type_id: F::provided_type(),
+            #[cfg(not(feature = "fault-injection"))]
             type_id: TypeId::of::<F::Provider>(),
         };
 
@@ -131,7 +198,7 @@ impl ProviderContainer {
     }
 
     pub fn transaction(&self) -> TransactionProvider<'_> {
-        TransactionProvider(self)
+        TransactionProvider(self, None)
     }
 }
 
@@ -139,9 +206,11 @@ impl Default for ProviderContainer {
     fn default() -> Self {
         Self {
             last_gc: 0,
-            lock: Mutex::new(()),
             lru: AtomicU64::new(1), // starting at 1 because the garbage collector start at 0.
             records: Vec::new(),
+            table_gates: Default::default(),
+            tenant: None,
+            tenant_param: None,
         }
     }
 }
@@ -1,8 +1,31 @@
 use super::Provider;
 use crate::{BoxFuture, Result};
+#[cfg(feature = "fault-injection")]
+use {super::FaultOp, std::any::TypeId};
 
 pub trait ProviderFactory: Send + Sync + 'static {
     type Provider: Provider;
 
     fn create_provider(&self) -> BoxFuture<'_, Result<Self::Provider>>;
+
+    /// Called before the operations done through a `Ctx` on the entity `entity` of the
+    /// provider, used by [FaultFactory](super::FaultFactory) to fail or delay them.
+    #[cfg(feature = "fault-injection")]
+    fn inject_fault(
+        &self,
+        _op: FaultOp,
+        _entity: TypeId,
+    ) -> Option<BoxFuture<'static, Result<()>>> {
+        None
+    }
+
+    /// The type of the provider for [ProviderContainer::provide](super::ProviderContainer::provide),
+    /// a decorating factory returns the type of the provider it wraps.
+    #[cfg(feature = "fault-injection")]
+    fn provided_type() -> TypeId
+    where
+        Self: Sized,
+    {
+        TypeId::of::<Self::Provider>()
+    }
 }
@@ -1,8 +1,9 @@
-use super::{LoadAll, LoadArgs, ProviderContainer};
-use crate::{BoxFuture, Entity, Result};
-use std::ops::Deref;
+use super::{LoadAll, LoadArgs, LoadOne, ProviderContainer};
+use crate::{with_deadline, BoxFuture, Entity, Result};
+use std::{ops::Deref, time::Instant};
 
-pub struct TransactionProvider<'a>(pub(super) &'a ProviderContainer);
+/// The providers of a transaction, with the deadline bounding the loads done through it.
+pub struct TransactionProvider<'a>(pub(super) &'a ProviderContainer, pub(super) Option<Instant>);
 
 impl<'a> TransactionProvider<'a> {
     pub fn commit(&self) -> BoxFuture<'_, Result<()>> {
@@ -29,6 +30,15 @@ impl<'a> TransactionProvider<'a> {
     pub fn container(&self) -> &'a ProviderContainer {
         self.0
     }
+
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.1
+    }
+
+    pub(crate) fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.1 = deadline;
+    }
 }
 
 impl Deref for TransactionProvider<'_> {
@@ -59,6 +69,28 @@ where
         filter: &'a FILTER,
         args: LoadArgs,
     ) -> BoxFuture<'a, Result<C>> {
-        self.0.load_all_with_args(filter, args)
+        Box::pin(with_deadline(
+            self.0.load_all_with_args(filter, args),
+            self.1,
+            None,
+        ))
+    }
+}
+
+impl<E> LoadOne<E> for TransactionProvider<'_>
+where
+    E: Entity,
+    ProviderContainer: LoadOne<E>,
+{
+    fn load_one_with_args<'a>(
+        &'a self,
+        k: &'a E::Key,
+        args: LoadArgs,
+    ) -> BoxFuture<'a, Result<Option<E>>> {
+        Box::pin(with_deadline(
+            self.0.load_one_with_args(k, args),
+            self.1,
+            None,
+        ))
     }
 }
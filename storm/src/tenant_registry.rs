@@ -0,0 +1,145 @@
+use crate::{Ctx, ProviderContainer, QueueRwLock, Result};
+use fxhash::FxHashMap;
+use std::{
+    any::Any,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering::Relaxed},
+        Arc,
+    },
+};
+
+type ContainerFactory<T> = Box<dyn Fn(&T) -> ProviderContainer + Send + Sync>;
+
+/// Manages one `Ctx` per tenant, created on first use from a `ProviderContainer` scoped to
+/// the tenant.
+///
+/// ```ignore
+/// let registry = TenantRegistry::new(|_tenant: &i32| {
+///     let mut provider = ProviderContainer::new();
+///     provider.register("", MssqlFactory(config.clone()));
+///     provider.with_mssql_tenant_type::<i32>()
+/// });
+///
+/// let ctx = registry.ctx(&tenant_id);
+/// let ctx = ctx.read().await?;
+/// ```
+pub struct TenantRegistry<T> {
+    factory: ContainerFactory<T>,
+    last_gc: AtomicU64,
+    lru: AtomicU64,
+    tenants: parking_lot::Mutex<FxHashMap<T, TenantRec>>,
+}
+
+impl<T> TenantRegistry<T>
+where
+    T: Any + Clone + Eq + Hash + Send + Sync,
+{
+    /// The factory creates the provider container of a tenant, the tenant is set by
+    /// the registry.
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: Fn(&T) -> ProviderContainer + Send + Sync + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            last_gc: AtomicU64::new(0),
+            lru: AtomicU64::new(1), // starting at 1 because the garbage collector start at 0.
+            tenants: Default::default(),
+        }
+    }
+
+    /// Gets or creates the context of the tenant.
+    pub fn ctx(&self, tenant: &T) -> Arc<QueueRwLock<Ctx>> {
+        let lru = self.lru.fetch_add(1, Relaxed);
+        let mut tenants = self.tenants.lock();
+
+        let rec = tenants.entry(tenant.clone()).or_insert_with(|| {
+            let provider = (self.factory)(tenant).with_tenant(tenant.clone());
+
+            TenantRec {
+                ctx: Arc::new(QueueRwLock::new(Ctx::new(provider))),
+                lru,
+            }
+        });
+
+        rec.lru = lru;
+        Arc::clone(&rec.ctx)
+    }
+
+    /// The context of the tenant, without creating it.
+    pub fn get(&self, tenant: &T) -> Option<Arc<QueueRwLock<Ctx>>> {
+        let lru = self.lru.fetch_add(1, Relaxed);
+        let mut tenants = self.tenants.lock();
+        let rec = tenants.get_mut(tenant)?;
+
+        rec.lru = lru;
+        Some(Arc::clone(&rec.ctx))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.lock().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tenants.lock().len()
+    }
+
+    /// Removes the context of the tenant, the next call to [ctx](Self::ctx) creates a new one.
+    pub fn remove(&self, tenant: &T) -> Option<Arc<QueueRwLock<Ctx>>> {
+        self.tenants.lock().remove(tenant).map(|r| r.ctx)
+    }
+
+    pub fn tenants(&self) -> Vec<T> {
+        self.tenants.lock().keys().cloned().collect()
+    }
+
+    /// Drops the contexts not used since the previous garbage collection and not borrowed
+    /// anymore, then garbage collects the remaining ones.
+    pub async fn gc(&self) -> Result<()> {
+        let new_gc = self.lru.load(Relaxed);
+        let last_gc = self.last_gc.swap(new_gc, Relaxed);
+
+        let ctxs = {
+            let mut tenants = self.tenants.lock();
+            tenants.retain(|_, r| r.lru >= last_gc || Arc::strong_count(&r.ctx) > 1);
+            tenants
+                .values()
+                .map(|r| Arc::clone(&r.ctx))
+                .collect::<Vec<_>>()
+        };
+
+        for ctx in ctxs {
+            ctx.queue().await?.write().await?.gc();
+        }
+
+        Ok(())
+    }
+}
+
+struct TenantRec {
+    ctx: Arc<QueueRwLock<Ctx>>,
+    lru: u64,
+}
+
+#[test]
+fn tenant_contexts() {
+    let registry = TenantRegistry::new(|_: &i32| ProviderContainer::new());
+
+    let ctx = registry.ctx(&1);
+    assert!(Arc::ptr_eq(&ctx, &registry.ctx(&1)));
+    assert!(registry.get(&2).is_none());
+
+    registry.ctx(&2);
+
+    let mut tenants = registry.tenants();
+    tenants.sort_unstable();
+
+    assert_eq!(tenants, vec![1, 2]);
+    assert!(registry.remove(&2).is_some());
+    assert_eq!(registry.len(), 1);
+
+    let provider = ProviderContainer::new().with_tenant(1);
+    assert_eq!(provider.tenant::<i32>(), Some(&1));
+    assert_eq!(provider.tenant::<i64>(), None);
+}
@@ -0,0 +1,70 @@
+use crate::{Error, Result};
+use futures::future::{select, Either};
+use std::{
+    future::Future,
+    pin::pin,
+    time::{Duration, Instant},
+};
+use tokio_util::sync::CancellationToken;
+
+/// Runs the future until it completes, the deadline is reached or the token is cancelled.
+/// Returns `Error::Timeout` or `Error::Cancelled` in the last two cases.
+///
+/// The future is dropped when it does not complete, the providers are responsible to
+/// leave their connections in a consistent state when this happens.
+pub async fn with_deadline<F, T>(
+    fut: F,
+    deadline: Option<Instant>,
+    cancel: Option<&CancellationToken>,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let fut = async move {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), fut)
+                .await
+                .map_err(|_| Error::Timeout)?,
+            None => fut.await,
+        }
+    };
+
+    match cancel {
+        Some(token) => match select(pin!(fut), pin!(token.cancelled())).await {
+            Either::Left((r, _)) => r,
+            Either::Right(_) => Err(Error::Cancelled),
+        },
+        None => fut.await,
+    }
+}
+
+/// Same as [with_deadline] with a deadline relative to now.
+pub async fn with_timeout<F, T>(
+    fut: F,
+    timeout: Option<Duration>,
+    cancel: Option<&CancellationToken>,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+    with_deadline(fut, deadline, cancel).await
+}
+
+#[tokio::test]
+async fn check_with_timeout() {
+    let never = std::future::pending::<Result<()>>;
+
+    let r = with_timeout(never(), Some(Duration::from_millis(10)), None).await;
+    assert!(matches!(r, Err(Error::Timeout)));
+
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let r = with_timeout(never(), None, Some(&token)).await;
+    assert!(matches!(r, Err(Error::Cancelled)));
+
+    // a completed future wins over the cancellation.
+    let r = with_timeout(async { Ok(1) }, Some(Duration::from_secs(10)), Some(&token)).await;
+    assert!(matches!(r, Ok(1)));
+}
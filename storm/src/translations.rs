@@ -0,0 +1,264 @@
+use crate::{Gc, Len};
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{borrow::Cow, fmt, marker::PhantomData};
+
+/// The values of a field in a dynamic set of cultures.
+///
+/// The MSSQL derives load and save it in the translated values table of the entity, one row by
+/// culture. Saving an entity deletes the rows of the cultures no longer present, a culture
+/// missing from one of the fields is saved as `NULL` and the column must be nullable.
+///
+/// [Len] is the length of the longest value, a `max_length` applies to every culture. The
+/// equality ignores the order in which the cultures were set, they are serialized in order.
+#[derive(Clone, Debug)]
+pub struct Translations<C> {
+    values: Vec<(C, Box<str>)>,
+}
+
+impl<C: PartialEq> Translations<C> {
+    pub const fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    pub fn contains(&self, culture: &C) -> bool {
+        self.get(culture).is_some()
+    }
+
+    pub fn cultures(&self) -> impl Iterator<Item = &C> + '_ {
+        self.values.iter().map(|(c, _)| c)
+    }
+
+    /// The value of the culture, without fallback.
+    pub fn get(&self, culture: &C) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(c, _)| c == culture)
+            .map(|(_, v)| &**v)
+    }
+
+    /// The value of the first culture of the fallback chain having a value.
+    pub fn get_or_fallback(&self, culture: &C, fallback: &FallbackChain<C>) -> Option<&str> {
+        fallback.chain(culture).find_map(|c| self.get(c))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&C, &str)> + '_ {
+        self.values.iter().map(|(c, v)| (c, &**v))
+    }
+
+    pub fn remove(&mut self, culture: &C) -> Option<Box<str>> {
+        let index = self.values.iter().position(|(c, _)| c == culture)?;
+        Some(self.values.remove(index).1)
+    }
+
+    pub fn set<'a>(&mut self, culture: C, value: impl Into<Cow<'a, str>>) {
+        let value = Box::from(value.into());
+
+        match self.values.iter_mut().find(|(c, _)| *c == culture) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((culture, value)),
+        }
+    }
+
+    pub fn with<'a>(mut self, culture: C, value: impl Into<Cow<'a, str>>) -> Self {
+        self.set(culture, value);
+        self
+    }
+
+    /// Adds the cultures of the translations missing from `cultures`.
+    #[doc(hidden)]
+    pub fn collect_cultures<'a>(&'a self, cultures: &mut Vec<&'a C>) {
+        for (c, _) in &self.values {
+            if !cultures.contains(&c) {
+                cultures.push(c);
+            }
+        }
+    }
+}
+
+impl<C: PartialEq> Default for Translations<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C: PartialEq, S: Into<Cow<'a, str>>> FromIterator<(C, S)> for Translations<C> {
+    fn from_iter<T: IntoIterator<Item = (C, S)>>(iter: T) -> Self {
+        let mut t = Self::new();
+
+        for (c, v) in iter {
+            t.set(c, v);
+        }
+
+        t
+    }
+}
+
+impl<C> Gc for Translations<C> {}
+
+impl<C: PartialEq> PartialEq for Translations<C> {
+    fn eq(&self, other: &Self) -> bool {
+        // the cultures are unique, the same count and values is the same set.
+        self.values.len() == other.values.len() && self.iter().all(|(c, v)| other.get(c) == Some(v))
+    }
+}
+
+impl<C: Eq> Eq for Translations<C> {}
+
+impl<C> Len for Translations<C> {
+    fn is_empty(&self) -> bool {
+        self.values.iter().all(|(_, v)| v.is_empty())
+    }
+
+    fn len(&self) -> usize {
+        self.values
+            .iter()
+            .map(|(_, v)| Len::len(&**v))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<C: Ord + Serialize> Serialize for Translations<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = self.iter().collect::<Vec<_>>();
+        values.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        serializer.collect_map(values)
+    }
+}
+
+impl<'de, C: Deserialize<'de> + PartialEq> Deserialize<'de> for Translations<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TranslationsVisitor<C>(PhantomData<C>);
+
+        impl<'de, C: Deserialize<'de> + PartialEq> Visitor<'de> for TranslationsVisitor<C> {
+            type Value = Translations<C>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of cultures to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut t = Translations::new();
+
+                while let Some((c, v)) = map.next_entry::<C, String>()? {
+                    t.set(c, v);
+                }
+
+                Ok(t)
+            }
+        }
+
+        deserializer.deserialize_map(TranslationsVisitor(PhantomData))
+    }
+}
+
+/// The cultures tried in order by [Translations::get_or_fallback] when the value of a culture
+/// is missing: the culture itself, its own fallbacks, then the default ones.
+///
+/// ```ignore
+/// let chain = FallbackChain::new()
+///     .with_fallback(Culture::FrCa, [Culture::Fr])
+///     .with_default([Culture::En]);
+///
+/// assert_eq!(label.get_or_fallback(&Culture::FrCa, &chain), Some("english"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FallbackChain<C> {
+    cultures: Vec<(C, Vec<C>)>,
+    default: Vec<C>,
+}
+
+impl<C: PartialEq> FallbackChain<C> {
+    pub const fn new() -> Self {
+        Self {
+            cultures: Vec::new(),
+            default: Vec::new(),
+        }
+    }
+
+    /// All the cultures to try for `culture`, in order.
+    pub fn chain<'a>(&'a self, culture: &'a C) -> impl Iterator<Item = &'a C> + 'a {
+        let own = self
+            .cultures
+            .iter()
+            .find(|(c, _)| c == culture)
+            .map_or(&[][..], |(_, f)| &f[..]);
+
+        std::iter::once(culture)
+            .chain(own.iter())
+            .chain(self.default.iter())
+    }
+
+    /// The cultures tried for every culture after its own fallbacks.
+    pub fn with_default(mut self, cultures: impl IntoIterator<Item = C>) -> Self {
+        self.default = cultures.into_iter().collect();
+        self
+    }
+
+    pub fn with_fallback(mut self, culture: C, fallbacks: impl IntoIterator<Item = C>) -> Self {
+        let fallbacks = fallbacks.into_iter().collect();
+
+        match self.cultures.iter_mut().find(|(c, _)| *c == culture) {
+            Some((_, f)) => *f = fallbacks,
+            None => self.cultures.push((culture, fallbacks)),
+        }
+
+        self
+    }
+}
+
+impl<C: PartialEq> Default for FallbackChain<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn translations_fallback() {
+    let t = Translations::new()
+        .with("en", "english")
+        .with("fr", "french");
+
+    let chain = FallbackChain::new()
+        .with_fallback("fr-CA", ["fr"])
+        .with_default(["en"]);
+
+    assert_eq!(t.get(&"fr-CA"), None);
+    assert_eq!(t.get_or_fallback(&"fr-CA", &chain), Some("french"));
+    assert_eq!(t.get_or_fallback(&"es", &chain), Some("english"));
+    assert_eq!(t.get_or_fallback(&"es", &FallbackChain::new()), None);
+    assert_eq!(Len::len(&t), 7);
+
+    let mut cultures = vec![&"fr"];
+    t.collect_cultures(&mut cultures);
+    assert_eq!(cultures, vec![&"fr", &"en"]);
+
+    let mut t = t;
+    t.set("en", "other");
+    assert_eq!(t.remove(&"fr").as_deref(), Some("french"));
+    assert_eq!(t.iter().collect::<Vec<_>>(), vec![(&"en", "other")]);
+
+    let json = serde_json::to_value(&t).ok();
+    assert_eq!(json, Some(serde_json::json!({ "en": "other" })));
+}
+
+#[test]
+fn translations_order() {
+    let a = Translations::new()
+        .with("fr", "french")
+        .with("en", "english");
+    let b = Translations::new()
+        .with("en", "english")
+        .with("fr", "french");
+
+    assert_eq!(a, b);
+    assert_ne!(a, Translations::new().with("en", "english"));
+    assert_ne!(a, b.clone().with("fr", "other"));
+
+    let json = serde_json::to_string(&a).ok();
+    assert_eq!(json, serde_json::to_string(&b).ok());
+    assert_eq!(json.as_deref(), Some(r#"{"en":"english","fr":"french"}"#));
+}
@@ -0,0 +1,297 @@
+use crate::{Error, Fields, Len};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+    sync::Arc,
+};
+
+/// The declarative rule that failed on a field, reported by `Error::Validation`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationRule {
+    /// A custom rule from `validate_with` failed with the provided message.
+    Custom(Cow<'static, str>),
+    MinLength {
+        len: usize,
+        min: usize,
+    },
+    NotEmpty,
+    OneOf(&'static [&'static str]),
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+    Regex(&'static str),
+}
+
+impl Display for ValidationRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom(msg) => f.write_str(msg),
+            Self::MinLength { len, min } => write!(f, "too short, len: {len}, min {min}"),
+            Self::NotEmpty => f.write_str("must not be empty"),
+            Self::OneOf(values) => write!(f, "must be one of `{}`", values.join(", ")),
+            Self::Range { min, max } => match (min, max) {
+                (Some(min), Some(max)) => write!(f, "must be between {min} and {max}"),
+                (Some(min), None) => write!(f, "must be greater or equal to {min}"),
+                (None, Some(max)) => write!(f, "must be less or equal to {max}"),
+                (None, None) => f.write_str("out of range"),
+            },
+            Self::Regex(pattern) => write!(f, "must match `{pattern}`"),
+        }
+    }
+}
+
+/// Gives access to the value checked by the `range`, `one_of` and `regex` rules.
+///
+/// Returns `None` when there is nothing to validate, for example an `Option` set to `None`.
+pub trait ValidateValue {
+    type Value: ?Sized;
+
+    fn validate_value(&self) -> Option<&Self::Value>;
+}
+
+impl<T: ValidateValue> ValidateValue for Option<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&Self::Value> {
+        self.as_ref().and_then(ValidateValue::validate_value)
+    }
+}
+
+impl<T: ValidateValue + ?Sized> ValidateValue for Arc<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&Self::Value> {
+        (**self).validate_value()
+    }
+}
+
+impl<T: ValidateValue + ?Sized> ValidateValue for Box<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&Self::Value> {
+        (**self).validate_value()
+    }
+}
+
+impl<T: ValidateValue + ?Sized> ValidateValue for Rc<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&Self::Value> {
+        (**self).validate_value()
+    }
+}
+
+impl<T> ValidateValue for Cow<'_, T>
+where
+    T: ValidateValue + ToOwned + ?Sized,
+{
+    type Value = T::Value;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&Self::Value> {
+        (**self).validate_value()
+    }
+}
+
+impl ValidateValue for str {
+    type Value = str;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl ValidateValue for String {
+    type Value = str;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "str_utils")]
+impl<F> ValidateValue for str_utils::form_str::FormStr<F> {
+    type Value = str;
+
+    #[inline]
+    fn validate_value(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+macro_rules! validate_value {
+    ($t:ty) => {
+        impl ValidateValue for $t {
+            type Value = Self;
+
+            #[inline]
+            fn validate_value(&self) -> Option<&Self> {
+                Some(self)
+            }
+        }
+    };
+}
+
+validate_value!(bool);
+validate_value!(char);
+validate_value!(f32);
+validate_value!(f64);
+validate_value!(i8);
+validate_value!(i16);
+validate_value!(i32);
+validate_value!(i64);
+validate_value!(i128);
+validate_value!(isize);
+validate_value!(u8);
+validate_value!(u16);
+validate_value!(u32);
+validate_value!(u64);
+validate_value!(u128);
+validate_value!(usize);
+
+#[cfg(feature = "chrono")]
+validate_value!(chrono::NaiveDate);
+
+#[cfg(feature = "chrono")]
+validate_value!(chrono::NaiveDateTime);
+
+#[cfg(feature = "chrono")]
+validate_value!(chrono::NaiveTime);
+
+#[cfg(feature = "chrono")]
+validate_value!(chrono::DateTime<chrono::Local>);
+
+#[cfg(feature = "chrono")]
+validate_value!(chrono::DateTime<chrono::Utc>);
+
+#[cfg(feature = "dec19x5")]
+validate_value!(dec19x5::Decimal);
+
+#[cfg(feature = "rust_decimal")]
+validate_value!(rust_decimal::Decimal);
+
+#[doc(hidden)]
+pub fn macro_check_custom<T: ?Sized>(
+    value: &T,
+    f: fn(&T) -> Result<(), Cow<'static, str>>,
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) {
+    if let Err(msg) = f(value) {
+        push(error, field, ValidationRule::Custom(msg));
+    }
+}
+
+#[doc(hidden)]
+pub fn macro_check_min_len(
+    len: usize,
+    min: usize,
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) {
+    if len < min {
+        push(error, field, ValidationRule::MinLength { len, min });
+    }
+}
+
+#[doc(hidden)]
+pub fn macro_check_not_empty<T: Len + ?Sized>(
+    value: &T,
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) {
+    if value.is_empty() {
+        push(error, field, ValidationRule::NotEmpty);
+    }
+}
+
+#[doc(hidden)]
+pub fn macro_check_one_of<T>(
+    value: &T,
+    values: &'static [&'static str],
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) where
+    T: ValidateValue + ?Sized,
+    T::Value: AsRef<str>,
+{
+    if let Some(v) = value.validate_value() {
+        let v = v.as_ref();
+
+        if !values.contains(&v) {
+            push(error, field, ValidationRule::OneOf(values));
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn macro_check_range<T>(
+    value: &T,
+    min: Option<T::Value>,
+    max: Option<T::Value>,
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) where
+    T: ValidateValue + ?Sized,
+    T::Value: Display + PartialOrd + Sized,
+{
+    if let Some(v) = value.validate_value() {
+        let too_low = min.as_ref().is_some_and(|min| v < min);
+        let too_high = max.as_ref().is_some_and(|max| v > max);
+
+        if too_low || too_high {
+            let rule = ValidationRule::Range {
+                min: min.map(|v| v.to_string()),
+                max: max.map(|v| v.to_string()),
+            };
+
+            push(error, field, rule);
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+pub fn macro_check_regex<T>(
+    value: &T,
+    regex: &crate::OnceCell<regex::Regex>,
+    pattern: &'static str,
+    field: impl Fields + 'static,
+    error: &mut Option<Error>,
+) where
+    T: ValidateValue + ?Sized,
+    T::Value: AsRef<str>,
+{
+    let Some(v) = value.validate_value() else {
+        return;
+    };
+
+    match regex.get_or_try_init(|| regex::Regex::new(pattern)) {
+        Ok(regex) => {
+            if !regex.is_match(v.as_ref()) {
+                push(error, field, ValidationRule::Regex(pattern));
+            }
+        }
+        Err(e) => Error::extend_one_opt(error, Error::std(e)),
+    }
+}
+
+fn push(error: &mut Option<Error>, field: impl Fields + 'static, rule: ValidationRule) {
+    Error::extend_one_opt(
+        error,
+        Error::Validation {
+            field: Box::new(field),
+            rule,
+        },
+    );
+}
@@ -1,6 +1,6 @@
 use crate::{
-    on_changed::Changed, provider::LoadAll, Accessor, ApplyLog, BoxFuture, CtxTypeInfo, Deps,
-    Entity, EntityAccessor, EntityOf, Gc, GcCtx, Get, GetMut, Init, Log, LogState, NotifyTag,
+    on_changed::Changed, provider::LoadAll, Accessor, ApplyLog, Authorized, BoxFuture, CtxTypeInfo,
+    Deps, Entity, EntityAccessor, EntityOf, Gc, GcCtx, Get, GetMut, Init, Log, LogState, NotifyTag,
     Result, Tag, TblVar,
 };
 use rayon::iter::IntoParallelIterator;
@@ -21,6 +21,14 @@ impl<E: Entity> VecTable<E> {
         }
     }
 
+    /// The entities the caller can read according to the policies of the entity.
+    pub fn authorized<'a>(&'a self, caller: &'a E::TrackCtx) -> Authorized<'a, E, Self>
+    where
+        E: EntityAccessor,
+    {
+        Authorized::new(self, caller, E::policy())
+    }
+
     #[inline]
     pub fn iter(&self) -> Iter<E::Key, E> {
         self.map.iter()
@@ -70,6 +70,31 @@ async fn transaction() -> Result<()> {
     .await
 }
 
+#[tokio::test]
+async fn preload() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let ctx = create_ctx();
+        let ctx = ctx.read().await?;
+
+        let report = ctx
+            .preload()
+            .tbl::<Entity1>()
+            .tbl::<Entity2>()
+            .ref_as::<Entity3s>()
+            .parallelism(2)
+            .run()
+            .await?;
+
+        assert_eq!(report.timings.len(), 3);
+        assert!(ctx.tbl_of_opt::<Entity1>().is_some());
+
+        let _ = PreloadedLocks::from_ctx(&ctx).await?;
+
+        Ok(())
+    })
+    .await
+}
+
 #[derive(storm::LocksAwait)]
 struct Locks<'a> {
     e1: &'a Entity1s,
@@ -79,6 +104,14 @@ struct Locks<'a> {
     e5: &'a Entity5s,
 }
 
+#[derive(storm::LocksAwait)]
+#[storm(preload, parallelism = 2)]
+struct PreloadedLocks<'a> {
+    e1: &'a Entity1s,
+    e2: &'a Entity2s,
+    e3: &'a Entity3s,
+}
+
 macro_rules! entity {
     ($n:ident) => {
         #[derive(Ctx, Default, NoopDelete, NoopLoad, NoopSave)]
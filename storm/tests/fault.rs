@@ -0,0 +1,181 @@
+use std::time::{Duration, Instant};
+use storm::{
+    prelude::*,
+    provider::{FaultFactory, FaultOp, Faults, Provider, ProviderFactory},
+    BoxFuture, Error, NoopDelete, NoopLoad, NoopSave, Result,
+};
+
+fn create_ctx() -> (QueueRwLock<Ctx>, Faults) {
+    let factory = FaultFactory::new(NullFactory);
+    let faults = factory.faults();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", factory);
+
+    (QueueRwLock::new(provider.into()), faults)
+}
+
+#[tokio::test]
+async fn fail_nth_upsert_poisons_transaction() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let (ctx, faults) = create_ctx();
+        faults.fail::<Entity1>(FaultOp::Upsert, 2);
+
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+
+        // the provider is in use so its transaction is cancelled on drop.
+        trx.provider().provide::<NullProvider>("").await?;
+
+        trx.insert(1, Entity1::default(), &()).await?;
+
+        let r = trx.insert(2, Entity1::default(), &()).await;
+        assert!(
+            matches!(r.map_err(Error::into_root), Err(Error::String(s)) if s == "Injected upsert fault #2.")
+        );
+
+        // the transaction is in error and cannot be committed.
+        assert!(matches!(trx.commit().await, Err(Error::TransactionError)));
+        assert_eq!(faults.calls::<Entity1>(FaultOp::Upsert), 2);
+        assert_eq!(faults.commits(), 0);
+        assert_eq!(faults.cancels(), 1);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn fail_commit_and_load() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let (ctx, faults) = create_ctx();
+        faults.fail::<Entity1>(FaultOp::Load, 1);
+        faults.fail_commit(1);
+
+        {
+            let ctx = ctx.read().await?;
+            assert!(ctx.tbl_of::<Entity1>().await.is_err());
+
+            // the failed load is retried.
+            ctx.tbl_of::<Entity1>().await?;
+        }
+
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+
+        trx.provider().provide::<NullProvider>("").await?;
+        trx.remove::<Entity1>(1, &()).await?;
+
+        assert!(trx.commit().await.is_err());
+        assert_eq!(faults.calls::<Entity1>(FaultOp::Load), 2);
+        assert_eq!(faults.calls::<Entity1>(FaultOp::Delete), 1);
+        assert_eq!(faults.commits(), 1);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn connection_drop_and_latency() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let (ctx, faults) = create_ctx();
+        faults.latency::<Entity1>(FaultOp::Upsert, Duration::from_millis(20));
+        faults.drop_connection::<Entity1>(FaultOp::Delete, 1);
+
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+
+        let start = Instant::now();
+        trx.insert(1, Entity1::default(), &()).await?;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        let r = trx.remove::<Entity1>(1, &()).await;
+        assert!(matches!(
+            r.map_err(Error::into_root),
+            Err(Error::ClientInError)
+        ));
+
+        // every later operation fails until the connection is back.
+        let r = trx.insert(2, Entity1::default(), &()).await;
+        assert!(matches!(
+            r.map_err(Error::into_root),
+            Err(Error::TransactionError)
+        ));
+
+        drop(trx);
+
+        let mut trx = ctx.transaction();
+        let r = trx.insert(2, Entity1::default(), &()).await;
+        assert!(matches!(
+            r.map_err(Error::into_root),
+            Err(Error::ClientInError)
+        ));
+
+        faults.reconnect();
+
+        let mut trx = ctx.transaction();
+        trx.insert(2, Entity1::default(), &()).await?;
+        trx.commit().await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn faults_apply_to_the_entities_of_the_provider() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let factory = FaultFactory::new(NullFactory);
+        let faults = factory.faults();
+        faults.fail::<Entity1>(FaultOp::Upsert, 1);
+
+        let mut provider = ProviderContainer::new();
+        provider.register("", NullFactory);
+        provider.register("other", factory);
+
+        let ctx = QueueRwLock::new(Ctx::from(provider));
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+
+        // `Entity1` is saved through the provider "", the faults of "other" are not applied.
+        trx.insert(1, Entity1::default(), &()).await?;
+        trx.commit().await?;
+
+        assert_eq!(faults.calls::<Entity1>(FaultOp::Upsert), 0);
+
+        Ok(())
+    })
+    .await
+}
+
+/// A provider without database for the entities saved with the noop derives.
+struct NullProvider;
+
+impl Provider for NullProvider {
+    fn cancel(&self) {}
+
+    fn commit(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+struct NullFactory;
+
+impl ProviderFactory for NullFactory {
+    type Provider = NullProvider;
+
+    fn create_provider(&self) -> BoxFuture<'_, Result<Self::Provider>> {
+        Box::pin(async { Ok(NullProvider) })
+    }
+}
+
+#[derive(Ctx, Default, NoopDelete, NoopLoad, NoopSave)]
+struct Entity1 {
+    name: String,
+}
+
+impl Entity for Entity1 {
+    type Key = usize;
+    type TrackCtx = ();
+}
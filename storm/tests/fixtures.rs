@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use storm::{prelude::*, EntityAccessor, Fixtures, NoopDelete, NoopLoad, NoopSave, Result};
+
+const FIXTURES: &str = r#"{
+  "User": [
+    { "key": 2, "entity": { "name": "Bob" } },
+    { "key": 1, "entity": { "name": "Alice" } }
+  ]
+}"#;
+
+#[tokio::test]
+async fn load_fixtures() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let fixtures = Fixtures::from_json(FIXTURES)?;
+        let mut ctx = Ctx::default();
+
+        assert_eq!(fixtures.load::<User>(&mut ctx)?, 2);
+
+        let users = ctx.tbl_of::<User>().await?;
+        assert_eq!(users.get(&1).map(|u| &u.name[..]), Some("Alice"));
+
+        // dumped back ordered by key.
+        let mut dumped = Fixtures::new();
+        dumped.dump::<User>(&ctx)?;
+
+        let yaml = dumped.to_yaml()?;
+        assert_eq!(
+            yaml,
+            "User:\n- entity:\n    name: Alice\n  key: 1\n- entity:\n    name: Bob\n  key: 2\n"
+        );
+        assert_eq!(Fixtures::from_yaml(&yaml)?, Fixtures::from_json(FIXTURES)?);
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn insert_fixtures() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        User::on_change().register_fn(|_trx, _key, user, _track| {
+            user.name = user.name.to_uppercase();
+            Box::pin(async { Ok(()) })
+        });
+
+        let fixtures = Fixtures::from_json(FIXTURES)?;
+        let ctx = QueueRwLock::<Ctx>::new(Default::default());
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+
+        // the change handlers run on the inserted rows.
+        assert_eq!(fixtures.insert::<User>(&mut trx, &()).await?, 2);
+
+        let log = trx.commit().await?;
+        let mut ctx = ctx.write().await?;
+        ctx.apply_log(log);
+
+        let ctx = ctx.read().await?;
+        let users = ctx.tbl_of::<User>().await?;
+
+        assert_eq!(users.get(&2).map(|u| &u.name[..]), Some("BOB"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Ctx, Debug, Default, Deserialize, NoopDelete, NoopLoad, NoopSave, Serialize)]
+struct User {
+    name: String,
+}
+
+impl Entity for User {
+    type Key = usize;
+    type TrackCtx = ();
+}
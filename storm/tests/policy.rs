@@ -0,0 +1,77 @@
+use storm::{prelude::*, EntityAccessor, Error, NoopDelete, NoopLoad, NoopSave, Policy, Result};
+
+#[tokio::test]
+async fn policy() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        Doc::policy().register(OwnerPolicy);
+
+        let ctx = QueueRwLock::<Ctx>::new(Default::default());
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+        let mut docs = trx.tbl_of::<Doc>().await?;
+
+        docs.insert(1, Doc { owner: 10 }, &10).await?;
+        docs.insert(2, Doc { owner: 20 }, &20).await?;
+
+        // only the owner can write its documents.
+        let r = docs.insert(3, Doc { owner: 20 }, &10).await;
+        assert!(matches!(
+            r.map_err(Error::into_root),
+            Err(Error::Unauthorized)
+        ));
+
+        let r = docs.remove(2, &10).await;
+        assert!(matches!(
+            r.map_err(Error::into_root),
+            Err(Error::Unauthorized)
+        ));
+
+        // a denied operation does not put the transaction in error.
+        docs.remove(1, &10).await?;
+
+        docs.insert(1, Doc { owner: 10 }, &10).await?;
+
+        let view = docs.authorized(&10);
+        assert_eq!(view.keys().copied().collect::<Vec<_>>(), vec![1]);
+        assert!(view.get(&2).is_none());
+
+        let log = trx.commit().await?;
+        let mut ctx = ctx.write().await?;
+        ctx.apply_log(log);
+
+        let ctx = ctx.read().await?;
+        let docs = ctx.tbl_of::<Doc>().await?;
+
+        assert_eq!(docs.authorized(&20).values().count(), 1);
+        assert_eq!(docs.iter().count(), 2);
+
+        Ok(())
+    })
+    .await
+}
+
+struct OwnerPolicy;
+
+impl Policy<Doc> for OwnerPolicy {
+    fn can_read(&self, caller: &u32, _key: &usize, entity: &Doc) -> bool {
+        entity.owner == *caller
+    }
+
+    fn can_insert(&self, caller: &u32, _key: &usize, new: &Doc, old: Option<&Doc>) -> bool {
+        new.owner == *caller && old.is_none_or(|d| d.owner == *caller)
+    }
+
+    fn can_remove(&self, caller: &u32, _key: &usize, old: Option<&Doc>) -> bool {
+        old.is_none_or(|d| d.owner == *caller)
+    }
+}
+
+#[derive(Ctx, Default, NoopDelete, NoopLoad, NoopSave)]
+struct Doc {
+    owner: u32,
+}
+
+impl Entity for Doc {
+    type Key = usize;
+    type TrackCtx = u32;
+}
@@ -0,0 +1,116 @@
+#![allow(clippy::unwrap_used)]
+
+use std::borrow::Cow;
+use storm::{EntityValidate, Error, ValidationRule};
+
+#[derive(EntityValidate)]
+struct Person {
+    #[storm(min_length = 2, max_length = 10, column = "Nom")]
+    name: String,
+
+    #[storm(not_empty, one_of = "A, B")]
+    code: Option<String>,
+
+    #[storm(range(min = 0, max = 120), validate_with = "even")]
+    age: i32,
+
+    #[storm(range(min = 1.5))]
+    score: Option<f64>,
+}
+
+fn even(v: &i32) -> Result<(), Cow<'static, str>> {
+    if v % 2 == 0 {
+        Ok(())
+    } else {
+        Err("must be even".into())
+    }
+}
+
+fn validate(p: &Person) -> Vec<(PersonFields, ValidationRule)> {
+    let mut error = None;
+    p.entity_validate(&mut error);
+
+    let errors = match error {
+        Some(Error::Multiple(vec)) => vec,
+        Some(e) => vec![e],
+        None => Vec::new(),
+    };
+
+    errors
+        .into_iter()
+        .map(|e| match e {
+            Error::Validation { field, rule } => {
+                (*field.downcast_ref::<PersonFields>().unwrap(), rule)
+            }
+            e => panic!("unexpected error {e}"),
+        })
+        .collect()
+}
+
+#[test]
+fn valid_entity() {
+    let p = Person {
+        name: "John".into(),
+        code: Some("B".into()),
+        age: 40,
+        score: None,
+    };
+
+    assert!(validate(&p).is_empty());
+}
+
+#[test]
+fn invalid_entity() {
+    let p = Person {
+        name: "J".into(),
+        code: None,
+        age: 121,
+        score: Some(1.0),
+    };
+
+    let errors = validate(&p);
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                PersonFields::Name,
+                ValidationRule::MinLength { len: 1, min: 2 }
+            ),
+            (PersonFields::Code, ValidationRule::NotEmpty),
+            (
+                PersonFields::Age,
+                ValidationRule::Range {
+                    min: Some("0".into()),
+                    max: Some("120".into())
+                }
+            ),
+            (
+                PersonFields::Age,
+                ValidationRule::Custom("must be even".into())
+            ),
+            (
+                PersonFields::Score,
+                ValidationRule::Range {
+                    min: Some("1.5".into()),
+                    max: None
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn one_of() {
+    let p = Person {
+        name: "John".into(),
+        code: Some("C".into()),
+        age: 40,
+        score: None,
+    };
+
+    assert_eq!(
+        validate(&p),
+        vec![(PersonFields::Code, ValidationRule::OneOf(&["A", "B"]))]
+    );
+}
@@ -3,7 +3,7 @@ use darling::{FromDeriveInput, FromMeta};
 use inflector::Inflector;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Ident, LitStr};
+use syn::{DeriveInput, Ident};
 
 pub fn generate(input: &DeriveInput) -> TokenStream {
     let implement = try_ts!(implement(input));
@@ -20,10 +20,11 @@ fn implement(input: &DeriveInput) -> Result<TokenStream, TokenStream> {
 
     let entity_name = entity.to_string();
     let table_name = entity_name.to_plural();
-    let table_name_lit = LitStr::new(&entity_name, entity.span());
+    let table_name_lit = input.entity_name();
     let table_alias = Ident::new(&table_name, entity.span());
 
     let coll_ty = args.collection.ty(entity);
+    let provider = &args.provider;
     let (gc, gc_collect) = gc(input)?;
 
     Ok(quote! {
@@ -72,6 +73,13 @@ fn implement(input: &DeriveInput) -> Result<TokenStream, TokenStream> {
                 static E: storm::OnRemove<#entity> = Default::default();
                 &E
             }
+
+            #[inline]
+            fn policy() -> &'static storm::EntityPolicy<Self> {
+                #[static_init::dynamic]
+                static E: storm::EntityPolicy<#entity> = Default::default();
+                &E
+            }
         }
 
         impl storm::LogAccessor for #entity {
@@ -88,6 +96,7 @@ fn implement(input: &DeriveInput) -> Result<TokenStream, TokenStream> {
 
         impl storm::CtxTypeInfo for #entity {
             const NAME: &'static str = #table_name_lit;
+            const PROVIDER: &'static str = #provider;
         }
 
         #gc
@@ -145,4 +154,7 @@ impl Default for Collection {
 struct TypeArgs {
     #[darling(default)]
     collection: Collection,
+
+    #[darling(default)]
+    provider: String,
 }
@@ -1,10 +1,16 @@
 //use crate::AttrsExt;
 use proc_macro2::TokenStream;
-use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields};
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, LitStr};
 
 pub trait DeriveInputExt {
     fn input(&self) -> &DeriveInput;
 
+    /// The name of the entity in the error frames, `CtxTypeInfo::NAME`.
+    fn entity_name(&self) -> LitStr {
+        let ident = &self.input().ident;
+        LitStr::new(&ident.to_string(), ident.span())
+    }
+
     fn fields(&self) -> Result<&Fields, TokenStream> {
         let input = self.input();
         match &input.data {
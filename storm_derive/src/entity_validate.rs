@@ -0,0 +1,233 @@
+use crate::{entity_fields::enum_fields_impl, DeriveInputExt, Errors, FieldExt};
+use darling::{util::SpannedValue, FromField, FromMeta};
+use inflector::Inflector;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{DeriveInput, Error, Expr, Ident, LitInt, LitStr};
+
+/// Declares the rules once, for the strict `ValidateRules` of `MssqlSave` and the lenient
+/// `LenientRules` of the standalone derive.
+macro_rules! validate_rules {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty,)*) => {
+        /// Declarative validation rules of a field, shared by the `EntityValidate`
+        /// and `MssqlSave` derives.
+        #[derive(Debug, Default, FromMeta)]
+        pub(crate) struct ValidateRules {
+            $(
+                $(#[$doc])*
+                #[darling(default)]
+                $field: $ty,
+            )*
+        }
+
+        /// The rules of the standalone derive, ignoring the attributes of the other derives
+        /// of the entity (`column`, `skip`, ...).
+        #[derive(Debug, FromMeta)]
+        #[darling(allow_unknown_fields)]
+        struct LenientRules {
+            $(
+                #[darling(default)]
+                $field: $ty,
+            )*
+        }
+
+        impl From<LenientRules> for ValidateRules {
+            fn from(v: LenientRules) -> Self {
+                Self {
+                    $($field: v.$field,)*
+                }
+            }
+        }
+    };
+}
+
+validate_rules! {
+    min_length: usize,
+    not_empty: bool,
+
+    /// comma separated list of the allowed values.
+    one_of: SpannedValue<Option<String>>,
+    range: Option<Range>,
+
+    /// requires the `regex` feature of storm.
+    regex: SpannedValue<Option<String>>,
+
+    /// `fn(&T) -> Result<(), Cow<'static, str>>` where `T` is the field type.
+    validate_with: SpannedValue<Option<Ident>>,
+}
+
+#[derive(Debug, FromMeta)]
+struct Range {
+    #[darling(default)]
+    min: Option<Expr>,
+
+    #[darling(default)]
+    max: Option<Expr>,
+}
+
+impl ValidateRules {
+    pub fn is_empty(&self) -> bool {
+        self.min_length == 0
+            && !self.not_empty
+            && self.one_of.is_none()
+            && self.range.is_none()
+            && self.regex.is_none()
+            && self.validate_with.is_none()
+    }
+
+    /// Error reported when rules are placed on a field that cannot be validated.
+    pub fn unsupported(&self, span: Span, errors: &mut Vec<TokenStream>) {
+        if !self.is_empty() {
+            errors.push(
+                Error::new(span, "Validation rules are not supported on this field.")
+                    .to_compile_error(),
+            );
+        }
+    }
+
+    pub fn validations(
+        &self,
+        field: &Ident,
+        variant: TokenStream,
+        validations: &mut Vec<TokenStream>,
+        errors: &mut Vec<TokenStream>,
+    ) {
+        if self.not_empty {
+            validations.push(quote!(storm::macro_check_not_empty(&self.#field, #variant, error);));
+        }
+
+        if self.min_length > 0 {
+            let min = LitInt::new(&self.min_length.to_string(), Span::call_site());
+
+            validations.push(quote!(
+                storm::macro_check_min_len(storm::Len::len(&self.#field), #min, #variant, error);
+            ));
+        }
+
+        if let Some(range) = &self.range {
+            if range.min.is_none() && range.max.is_none() {
+                errors.push(
+                    Error::new(field.span(), "range expects a `min` or a `max`.")
+                        .to_compile_error(),
+                );
+            }
+
+            let min = opt_expr(&range.min);
+            let max = opt_expr(&range.max);
+
+            validations
+                .push(quote!(storm::macro_check_range(&self.#field, #min, #max, #variant, error);));
+        }
+
+        if let Some(one_of) = self.one_of.as_ref() {
+            let values = one_of
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| LitStr::new(s, self.one_of.span()))
+                .collect::<Vec<_>>();
+
+            if values.is_empty() {
+                errors.push(Error::new(self.one_of.span(), "Expected a value.").to_compile_error());
+            }
+
+            validations.push(
+                quote!(storm::macro_check_one_of(&self.#field, &[#(#values),*], #variant, error);),
+            );
+        }
+
+        if let Some(regex) = self.regex.as_ref() {
+            let pattern = LitStr::new(regex, self.regex.span());
+
+            validations.push(quote! {
+                {
+                    static REGEX: storm::OnceCell<storm::regex::Regex> = storm::OnceCell::new();
+                    storm::macro_check_regex(&self.#field, &REGEX, #pattern, #variant, error);
+                }
+            });
+        }
+
+        if let Some(f) = self.validate_with.as_ref() {
+            validations.push(quote!(storm::macro_check_custom(&self.#field, #f, #variant, error);));
+        }
+    }
+}
+
+fn opt_expr(e: &Option<Expr>) -> TokenStream {
+    match e {
+        Some(e) => quote!(Some(#e)),
+        None => quote!(None),
+    }
+}
+
+/// Field attributes of the standalone derive.
+#[derive(Debug, FromField)]
+#[darling(attributes(storm))]
+struct FieldAttrs {
+    #[darling(default)]
+    max_length: usize,
+
+    #[darling(flatten)]
+    rules: LenientRules,
+}
+
+pub(crate) fn generate(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let enum_fields_ident = Ident::new(&format!("{ident}Fields"), ident.span());
+    let mut enum_fields = Vec::new();
+    let mut errors = Vec::new();
+    let mut validations = Vec::new();
+
+    for field in try_ts!(input.fields()) {
+        let attrs: FieldAttrs = continue_ts!(
+            FieldAttrs::from_field(field).map_err(|e| e.write_errors()),
+            errors
+        );
+
+        let field_ident = continue_ts!(field.ident(), errors);
+        let field_pascal_ident = Ident::new(
+            &field_ident.to_string().to_pascal_case(),
+            field_ident.span(),
+        );
+
+        let variant = quote!(#enum_fields_ident::#field_pascal_ident);
+
+        if attrs.max_length > 0 {
+            let max = LitInt::new(&attrs.max_length.to_string(), Span::call_site());
+
+            validations.push(quote!(
+                storm::macro_check_max_len(storm::Len::len(&self.#field_ident), #max, #variant, error);
+            ));
+        }
+
+        ValidateRules::from(attrs.rules).validations(
+            field_ident,
+            variant,
+            &mut validations,
+            &mut errors,
+        );
+
+        enum_fields.push(field_pascal_ident);
+    }
+
+    try_ts!(errors.result());
+
+    let entity_validate = entity_validate_impl(validations, ident);
+    let enum_fields = enum_fields_impl(&input.vis, ident, enum_fields, &enum_fields_ident);
+
+    quote! {
+        #entity_validate
+        #enum_fields
+    }
+}
+
+pub(crate) fn entity_validate_impl(validations: Vec<TokenStream>, ident: &Ident) -> TokenStream {
+    quote! {
+        impl storm::EntityValidate for #ident {
+            #[allow(unused)]
+            fn entity_validate(&self, error: &mut Option<storm::Error>) {
+                #(#validations)*
+            }
+        }
+    }
+}
@@ -7,7 +7,8 @@ mod macros;
 
 mod ctx;
 mod derive_input_ext;
-#[cfg(feature = "mssql")]
+mod entity_fields;
+mod entity_validate;
 mod errors;
 mod field_ext;
 mod indexing;
@@ -16,15 +17,14 @@ mod locks_await;
 mod mssql;
 mod noop;
 mod rename_all;
+mod storm_key;
 #[cfg(feature = "mssql")]
 mod string_ext;
 mod token_stream_ext;
 mod type_ext;
 
 use derive_input_ext::DeriveInputExt;
-#[cfg(feature = "mssql")]
 use errors::Errors;
-#[cfg(feature = "mssql")]
 use field_ext::FieldExt;
 use proc_macro::TokenStream;
 #[cfg(feature = "mssql")]
@@ -40,18 +40,62 @@ pub fn ctx(input: TokenStream) -> TokenStream {
     ctx::generate(&input).into()
 }
 
+/// Implements `storm::EntityValidate` from the declarative rules placed on the fields
+/// and generates the `{Entity}Fields` enum used to report the failing field.
+///
+/// Supported rules: `max_length = N`, `min_length = N`, `not_empty`, `one_of = "A,B"`,
+/// `range(min = 0, max = 10)`, `regex = "..."` (requires the `regex` feature) and
+/// `validate_with = "fn_name"`.
+///
+/// `MssqlSave` already implements `EntityValidate` and honors the same rules, this derive
+/// is meant for entities that are not saved through it.
+#[proc_macro_derive(EntityValidate, attributes(storm))]
+pub fn entity_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    entity_validate::generate(&input).into()
+}
+
 #[proc_macro_attribute]
 pub fn indexing(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as Item);
     indexing::indexing(item).into()
 }
 
+/// Implements the async loading of a struct of table and index references from a `Ctx`.
+///
+/// With `#[storm(preload)]`, all the fields are loaded concurrently through `Ctx::preload`,
+/// at most `#[storm(parallelism = N)]` at the same time.
 #[proc_macro_derive(LocksAwait, attributes(storm))]
 pub fn locks_await(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     locks_await::locks_await(&input).into()
 }
 
+/// Embeds the migration scripts of a directory, relative to the crate manifest, as a
+/// `&'static [storm_mssql::Migration]` sorted by version.
+///
+/// Scripts are named `{version}_{name}.sql`, e.g. `0001_create_users.sql`, and may contain
+/// `GO` batch separators.
+///
+/// The changes of the embedded scripts rebuild the crate, but a script added to the directory
+/// is only embedded on the next rebuild. The crate must have a `build.rs` watching the
+/// directory, otherwise the new migration is silently skipped:
+///
+/// ```ignore
+/// fn main() {
+///     println!("cargo:rerun-if-changed=migrations");
+/// }
+/// ```
+#[cfg(feature = "mssql")]
+#[proc_macro]
+pub fn embed_migrations(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as syn::LitStr);
+    mssql::embed_migrations(&dir).into()
+}
+
+/// With `#[storm(delete_proc = "dbo.DeleteUser")]`, the row is deleted by calling the stored
+/// procedure with the keys, and the tenant, passed to the parameters named after their columns.
+/// The translations are still deleted by statement.
 #[cfg(feature = "mssql")]
 #[proc_macro_derive(MssqlDelete, attributes(storm))]
 pub fn mssql_delete(input: TokenStream) -> TokenStream {
@@ -59,6 +103,26 @@ pub fn mssql_delete(input: TokenStream) -> TokenStream {
     mssql::delete(&input).into()
 }
 
+/// Implements `Flatten` for a value object embedded in entities with
+/// `#[storm(flatten, prefix = "Addr")]`, its fields stored in the columns of the entity named
+/// after the columns of the struct with the prefix.
+///
+/// Also implements `Gc`, `EntityValidate` and the `{Struct}Fields` enum from the rules of the
+/// fields. With `#[storm(diff)]`, the struct is diffed as a whole by the entities and must
+/// implement `PartialEq`, `Serialize` and `Deserialize`.
+#[cfg(feature = "mssql")]
+#[proc_macro_derive(MssqlFlatten, attributes(storm))]
+pub fn mssql_flatten(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    mssql::flatten(&input).into()
+}
+
+/// With `#[storm(read_only, view = "dbo.ActiveUsers")]` or
+/// `#[storm(read_only, load_sql = "SELECT ...")]`, the entity is loaded from a view or a custom
+/// select instead of a table. The select is wrapped as a subquery aliased `t`, so the filters,
+/// the `where_clause` and the tenant apply to its columns. A read-only entity cannot derive
+/// `MssqlSave` nor `MssqlDelete`, its inserts and removes do not compile. The schema of a view
+/// only verifies its columns, a custom select has no schema.
 #[cfg(feature = "mssql")]
 #[proc_macro_derive(MssqlLoad, attributes(storm))]
 pub fn mssql_load(input: TokenStream) -> TokenStream {
@@ -66,6 +130,10 @@ pub fn mssql_load(input: TokenStream) -> TokenStream {
     mssql::load(&input).into()
 }
 
+/// With `#[storm(save_proc = "dbo.SaveUser")]`, the upsert calls the stored procedure with
+/// each column passed to the parameter of the same name. An identity key is an `OUTPUT`
+/// parameter set by the procedure on insert, and the computed columns are read back with
+/// `reload_on_upsert`. The translations are still saved by statement.
 #[cfg(feature = "mssql")]
 #[proc_macro_derive(MssqlSave, attributes(storm))]
 pub fn mssql_save(input: TokenStream) -> TokenStream {
@@ -73,6 +141,48 @@ pub fn mssql_save(input: TokenStream) -> TokenStream {
     mssql::save(&input).into()
 }
 
+/// Implements `FromRow`, reading the fields from the columns of a row by name for the ad-hoc
+/// queries of `QueryRows`.
+///
+/// The columns are named like the entity fields, with `#[storm(rename_all = "...")]` and
+/// `#[storm(column = "...")]`. `#[from_row(flatten)]` reads a nested `FromRow` struct from the
+/// same row. A missing, null or mistyped column fails with `Error::ConvertFailed` naming it.
+#[cfg(feature = "mssql")]
+#[proc_macro_derive(FromRow, attributes(from_row, storm))]
+pub fn from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    mssql::from_row(&input).into()
+}
+
+/// Maps a unit enum to a column, implementing `ToSql`, `FromSql`, `SqlType`, the field diff
+/// traits, `Gc`, and serde's `Serialize` and `Deserialize` as the stored value.
+///
+/// `#[sql(repr = "i32")]` stores the discriminants (or `#[sql(value = 1)]`), `u8`, `i16` and
+/// `i64` are also supported. `#[sql(repr = "string")]` stores the variant names
+/// (or `#[sql(value = "A")]`). Unknown database values fail with `Error::ConvertFailed`.
+///
+/// The discriminants must be integer literals. `#[sql(no_serde)]` skips the serde impls, for
+/// the enums deriving them.
+#[cfg(feature = "mssql")]
+#[proc_macro_derive(SqlEnum, attributes(sql))]
+pub fn sql_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    mssql::sql_enum(&input).into()
+}
+
+/// Implements the traits of a key newtype from its inner integer or uuid:
+/// `Clone`, `Copy`, `Eq`, `Hash`, `PartialEq`, serde, `Gc`, `IsDefined`, the `usize`
+/// conversions of `VecTable` for the integer keys and, with `mssql`, `ToSql`, `ToSqlNull`,
+/// `FromSql` and `SqlType`.
+///
+/// The integer is `i8` to `i64` or `u8` to `u64`. The `usize` conversions panic on a key that
+/// is not a valid index, `#[storm(vec_table = false)]` skips them.
+#[proc_macro_derive(StormKey, attributes(storm))]
+pub fn storm_key(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    storm_key::generate(&input).into()
+}
+
 #[proc_macro_derive(NoopDelete)]
 pub fn noop_delete(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
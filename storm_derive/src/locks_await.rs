@@ -1,11 +1,34 @@
 use crate::DeriveInputExt;
+use darling::{util::SpannedValue, FromDeriveInput};
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Type};
+use syn::{DeriveInput, Error, Type};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(storm))]
+struct TypeAttrs {
+    /// loads all the fields concurrently with `Ctx::preload` before taking the references.
+    #[darling(default)]
+    preload: bool,
+
+    /// maximum number of fields loaded at the same time in preload mode.
+    #[darling(default)]
+    parallelism: SpannedValue<Option<usize>>,
+}
 
 #[allow(clippy::expect_used)]
 pub(crate) fn locks_await(input: &DeriveInput) -> TokenStream {
+    let attrs = match TypeAttrs::from_derive_input(input) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.write_errors(),
+    };
+
+    if !attrs.preload && attrs.parallelism.is_some() {
+        return Error::new(attrs.parallelism.span(), "Requires `preload`.").to_compile_error();
+    }
+
     let type_ident = &input.ident;
+    let mut preloads = Vec::new();
     let mut init_fields = Vec::new();
     let mut as_refs = Vec::new();
     let mut tags = Vec::new();
@@ -14,6 +37,8 @@ pub(crate) fn locks_await(input: &DeriveInput) -> TokenStream {
         let f_ident = &field.ident;
         let ty = unref(&field.ty);
 
+        preloads.push(quote!(.ref_as::<#ty>()));
+
         init_fields
             .push(quote!(#f_ident: storm::tri!(storm::AsRefAsync::as_ref_async(ctx).await),));
 
@@ -32,10 +57,22 @@ pub(crate) fn locks_await(input: &DeriveInput) -> TokenStream {
     let as_refs = quote!(#(#as_refs)*);
     let init_fields = quote!(#(#init_fields)*);
 
+    let preload = if attrs.preload {
+        let parallelism = attrs.parallelism.as_ref().map(|n| quote!(.parallelism(#n)));
+
+        quote! {
+            storm::tri!(ctx.preload() #(#preloads)* #parallelism .run().await);
+        }
+    } else {
+        quote!()
+    };
+
     quote! {
         impl<'a> storm::AsyncTryFrom<'a, &'a storm::Ctx> for #type_ident<'a> {
             fn async_try_from(ctx: &'a storm::Ctx) -> storm::BoxFuture<'a, storm::Result<#type_ident<'a>>> {
                 Box::pin(async move {
+                    #preload
+
                     Ok(#type_ident {
                         #init_fields
                     })
@@ -1,4 +1,3 @@
-#[cfg(feature = "mssql")]
 macro_rules! continue_ts {
     ($v:expr, $errors:ident) => {
         match $v {
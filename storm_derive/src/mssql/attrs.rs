@@ -1,4 +1,4 @@
-use crate::rename_all::RenameAll;
+use crate::{entity_validate::ValidateRules, rename_all::RenameAll};
 use darling::{util::SpannedValue, FromDeriveInput, FromField};
 use proc_macro2::{Span, TokenStream};
 use syn::{Error, Ident, LitStr};
@@ -9,6 +9,11 @@ pub(super) struct FieldAttrs {
     #[darling(default)]
     pub column: Option<String>,
 
+    /// The fields of the struct are stored in prefixed columns of the entity, see
+    /// `storm_mssql::Flatten`.
+    #[darling(default)]
+    pub flatten: bool,
+
     #[darling(default)]
     pub load_with: SpannedValue<Option<Ident>>,
 
@@ -18,6 +23,13 @@ pub(super) struct FieldAttrs {
     #[darling(default)]
     pub part: bool,
 
+    /// The prefix of the columns of a flattened field.
+    #[darling(default)]
+    prefix: SpannedValue<Option<String>>,
+
+    #[darling(flatten)]
+    pub rules: ValidateRules,
+
     #[darling(default)]
     pub save_with: SpannedValue<Option<Ident>>,
 
@@ -32,9 +44,29 @@ pub(super) struct FieldAttrs {
 
     #[darling(default)]
     skip_diff: bool,
+
+    /// Overrides the sql type inferred from the field type in the schema.
+    #[darling(default)]
+    pub sql_type: Option<String>,
 }
 
 impl FieldAttrs {
+    /// The attributes without meaning on the fields of a flattened struct.
+    pub fn is_flatten_unsupported(&self) -> bool {
+        self.flatten
+            || self.part
+            || self.prefix.is_some()
+            || self.load_with.is_some()
+            || self.save_with.is_some()
+            || self.skip.is_some()
+            || self.skip_load.is_some()
+            || self.skip_save.is_some()
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or_default()
+    }
+
     pub fn skip_diff(&self) -> bool {
         self.skip_diff
     }
@@ -51,6 +83,16 @@ impl FieldAttrs {
         if let (Some(true), Some(false)) = (*self.skip, *self.skip_load) {
             errors.push(Error::new(self.skip_load.span(), SKIP_IS_INCOMPATIBLE).to_compile_error());
         }
+
+        if !self.flatten && self.prefix.is_some() {
+            errors.push(Error::new(self.prefix.span(), "Expected `flatten`.").to_compile_error());
+        }
+
+        if self.flatten && self.load_with.is_some() {
+            errors.push(
+                Error::new(self.load_with.span(), "Ignored on flatten field.").to_compile_error(),
+            );
+        }
     }
 
     pub fn validate_save(&self, errors: &mut Vec<TokenStream>) {
@@ -67,15 +109,35 @@ impl FieldAttrs {
                 Error::new(self.save_with.span(), "Ignored on part field.").to_compile_error(),
             );
         }
+
+        if self.flatten && self.save_with.is_some() {
+            errors.push(
+                Error::new(self.save_with.span(), "Ignored on flatten field.").to_compile_error(),
+            );
+        }
     }
 }
 
 #[derive(Debug, FromDeriveInput)]
 #[darling(attributes(storm))]
 pub(super) struct TypeAttrs {
+    #[darling(default)]
     pub table: SpannedValue<String>,
     pub keys: SpannedValue<String>,
 
+    /// The entity is only loaded, `MssqlSave` and `MssqlDelete` are rejected.
+    #[darling(default)]
+    pub read_only: SpannedValue<bool>,
+
+    /// The view loaded instead of a table, requires `read_only`.
+    #[darling(default)]
+    pub view: SpannedValue<String>,
+
+    /// The select loaded instead of a table, requires `read_only`. It is wrapped as a subquery
+    /// aliased `t`, the filters, the `where_clause` and the tenant apply to its columns.
+    #[darling(default)]
+    pub load_sql: SpannedValue<String>,
+
     #[darling(default)]
     pub no_test: bool,
 
@@ -92,9 +154,41 @@ pub(super) struct TypeAttrs {
     #[darling(default)]
     reload_on_upsert: bool,
 
+    /// The stored procedure called by the upsert instead of the generated statement.
+    #[darling(default)]
+    pub save_proc: SpannedValue<String>,
+
+    /// The stored procedure called by the delete instead of the generated statement.
+    #[darling(default)]
+    pub delete_proc: SpannedValue<String>,
+
+    /// A bit column set to 1 instead of deleting the row.
+    #[darling(default)]
+    soft_delete: SpannedValue<String>,
+
+    /// A date time column set to the deletion time instead of deleting the row.
+    #[darling(default)]
+    soft_delete_at: SpannedValue<String>,
+
     #[darling(default)]
     pub rename_all: Option<RenameAll>,
 
+    /// The column holding the tenant, the loads, saves and deletes are scoped to the tenant
+    /// of the provider container.
+    #[darling(default)]
+    pub tenant: String,
+
+    /// The table is system-versioned, generates the loads as of a point in time and the history.
+    #[darling(default)]
+    pub temporal: bool,
+
+    /// The period columns of the temporal table, `ValidFrom` and `ValidTo` by default.
+    #[darling(default)]
+    pub period_start: SpannedValue<String>,
+
+    #[darling(default)]
+    pub period_end: SpannedValue<String>,
+
     #[darling(default)]
     pub translate_table: SpannedValue<String>,
 
@@ -140,6 +234,77 @@ impl TypeAttrs {
         vec
     }
 
+    /// What the loads select from: the table, the view or the custom select as a subquery.
+    pub fn load_source(&self, errors: &mut Vec<TokenStream>) -> String {
+        let sources = [&self.table, &self.view, &self.load_sql];
+
+        match sources.iter().filter(|s| !s.is_empty()).count() {
+            0 => errors.push(
+                Error::new(
+                    self.table.span(),
+                    "Expected a `table`, a `view` or a `load_sql`.",
+                )
+                .to_compile_error(),
+            ),
+            1 => {}
+            _ => errors.push(
+                Error::new(
+                    self.table.span(),
+                    "`table`, `view` and `load_sql` are incompatible.",
+                )
+                .to_compile_error(),
+            ),
+        }
+
+        for source in [&self.view, &self.load_sql] {
+            if !source.is_empty() && !*self.read_only {
+                errors.push(Error::new(source.span(), "Expected `read_only`.").to_compile_error());
+            }
+        }
+
+        if !self.load_sql.is_empty() && self.temporal {
+            errors.push(
+                Error::new(self.load_sql.span(), "Incompatible with `temporal`.")
+                    .to_compile_error(),
+            );
+        }
+
+        if *self.read_only && self.diff {
+            errors.push(
+                Error::new(self.read_only.span(), "Incompatible with `diff`.").to_compile_error(),
+            );
+        }
+
+        if !self.load_sql.is_empty() {
+            format!("({})", &*self.load_sql)
+        } else {
+            non_empty_or(&self.view, &self.table).to_string()
+        }
+    }
+
+    /// The name of the table or the view, empty for a custom select.
+    pub fn table_name(&self) -> &str {
+        non_empty_or(&self.view, &self.table)
+    }
+
+    /// Reports the errors of the derives writing to the table.
+    pub fn check_writable(&self, errors: &mut Vec<TokenStream>) {
+        if *self.read_only {
+            errors.push(
+                Error::new(
+                    self.read_only.span(),
+                    "A read-only entity cannot be saved nor deleted.",
+                )
+                .to_compile_error(),
+            );
+            return;
+        }
+
+        check_empty(&self.view, errors);
+        check_empty(&self.load_sql, errors);
+        check_required(&self.table, errors);
+    }
+
     pub fn is_identity_key(&self) -> bool {
         !self.identity.is_empty()
             && self
@@ -164,6 +329,32 @@ impl TypeAttrs {
         self.reload_on_upsert || !self.identity.is_empty()
     }
 
+    pub fn soft_delete(&self, errors: &mut Vec<TokenStream>) -> Option<SoftDelete<'_>> {
+        match (self.soft_delete.is_empty(), self.soft_delete_at.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(SoftDelete::Flag(&self.soft_delete)),
+            (true, false) => Some(SoftDelete::Timestamp(&self.soft_delete_at)),
+            (false, false) => {
+                errors.push(
+                    Error::new(
+                        self.soft_delete_at.span(),
+                        "`soft_delete` and `soft_delete_at` are incompatible.",
+                    )
+                    .to_compile_error(),
+                );
+                None
+            }
+        }
+    }
+
+    /// The start and end columns of the period of a temporal table.
+    pub fn period(&self) -> (&str, &str) {
+        (
+            non_empty_or(&self.period_start, "ValidFrom"),
+            non_empty_or(&self.period_end, "ValidTo"),
+        )
+    }
+
     pub fn translate_keys(&self, errors: &mut Vec<TokenStream>) -> Vec<&str> {
         if self.translate_table.is_empty() {
             return Vec::new();
@@ -198,6 +389,38 @@ impl TypeAttrs {
     }
 }
 
+/// The column marking a row as deleted instead of removing it from the table.
+#[derive(Clone, Copy)]
+pub(super) enum SoftDelete<'a> {
+    Flag(&'a str),
+    Timestamp(&'a str),
+}
+
+impl SoftDelete<'_> {
+    /// The condition excluding the deleted rows.
+    pub fn not_deleted(&self, alias: &str) -> String {
+        match self {
+            Self::Flag(c) => format!("{alias}.[{c}]=0"),
+            Self::Timestamp(c) => format!("{alias}.[{c}] IS NULL"),
+        }
+    }
+
+    /// The assignment marking a row as deleted.
+    pub fn set_deleted(&self) -> String {
+        match self {
+            Self::Flag(c) => format!("[{c}]=1"),
+            Self::Timestamp(c) => format!("[{c}]=SYSUTCDATETIME()"),
+        }
+    }
+}
+
+fn non_empty_or<'a>(v: &'a str, default: &'a str) -> &'a str {
+    match v.is_empty() {
+        true => default,
+        false => v,
+    }
+}
+
 const SKIP_IS_INCOMPATIBLE: &str = "`skip` is incompatible.";
 
 pub(super) fn check_empty<'a, T: IsEmpty>(
@@ -19,6 +19,18 @@ impl DeleteBuilder {
             .add(')');
     }
 
+    pub fn wheres(&self) -> &str {
+        &self.wheres
+    }
+
+    pub fn add_condition(&mut self, condition: &str) {
+        self.wheres
+            .add_sep_str("AND")
+            .add('(')
+            .add_str(condition)
+            .add(')');
+    }
+
     pub fn to_sql(&self, table: &str) -> String {
         format!("DELETE FROM {} WHERE {}", table, self.wheres)
     }
@@ -26,6 +38,14 @@ impl DeleteBuilder {
     pub fn to_sql_lit(&self, table: &str) -> LitStr {
         LitStr::new(&self.to_sql(table), Span::call_site())
     }
+
+    /// Marks the rows as deleted instead of deleting them.
+    pub fn to_soft_sql_lit(&self, table: &str, set_deleted: &str) -> LitStr {
+        LitStr::new(
+            &format!("UPDATE {} SET {} WHERE {}", table, set_deleted, self.wheres),
+            Span::call_site(),
+        )
+    }
 }
 
 #[derive(Clone, Default)]
@@ -91,6 +111,28 @@ impl JoinBuilder {
         }
     }
 
+    /// Joins a temporal table as of the date in the first parameter.
+    pub fn inner_join_as_of<'a>(
+        &'a mut self,
+        table: &str,
+        alias: Option<&str>,
+    ) -> JoinConditions<'a> {
+        self.0
+            .add_str(" INNER JOIN ")
+            .add_str(table)
+            .add(' ')
+            .add_str(AS_OF);
+
+        if let Some(alias) = alias {
+            self.0.add(' ').add_str(alias);
+        }
+
+        JoinConditions {
+            s: &mut self.0,
+            is_first: true,
+        }
+    }
+
     pub fn to_sql(&self) -> &str {
         &self.0
     }
@@ -147,7 +189,19 @@ impl SelectBuilder {
         self.select.is_empty()
     }
 
+    /// The number of selected fields.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
     fn to_sql(&self, table: &str, where_clause: &str) -> String {
+        let (select, from) = self.to_sql_parts(table, where_clause);
+        select + &from
+    }
+
+    /// The select and the rest of the statement from the `FROM`, the columns of the flattened
+    /// fields are added between them.
+    pub fn to_sql_parts(&self, table: &str, where_clause: &str) -> (String, String) {
         let wc;
 
         let where_clause = if where_clause.is_empty() {
@@ -157,20 +211,30 @@ impl SelectBuilder {
             &wc
         };
 
-        format!(
-            "SELECT {} FROM {} {} {}",
-            self.select,
-            table,
-            self.alias.unwrap_or(""),
-            where_clause
+        (
+            format!("SELECT {}", self.select),
+            format!(
+                " FROM {} {} {}",
+                table,
+                self.alias.unwrap_or(""),
+                where_clause
+            ),
         )
     }
 
     pub fn to_sql_lit(&self, table: &str, where_clause: &str) -> LitStr {
         LitStr::new(&self.to_sql(table, where_clause), Span::call_site())
     }
+
+    /// The table of the select of a temporal table as of the date in the first parameter.
+    pub fn as_of(table: &str) -> String {
+        format!("{table} {AS_OF}")
+    }
 }
 
+/// The period of a temporal table, as of the date in the first parameter.
+const AS_OF: &str = "FOR SYSTEM_TIME AS OF @p1";
+
 #[derive(Clone, Default)]
 pub(super) struct UpdateBuilder {
     fields: String,
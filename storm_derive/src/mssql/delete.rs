@@ -6,7 +6,7 @@ use darling::util::SpannedValue;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt as _};
 use std::marker::PhantomData;
-use syn::LitInt;
+use syn::{LitInt, LitStr};
 
 pub(super) struct Delete<'a, S> {
     attrs: &'a TypeAttrs,
@@ -37,14 +37,72 @@ where
         let mut params = ParamsBuilder::default();
         let mut delete = DeleteBuilder::default();
 
+        let soft_delete = match S::TRANSLATED {
+            // the translations are kept with the soft deleted row.
+            true if self.attrs.soft_delete(&mut Vec::new()).is_some() => return,
+            true => None,
+            false => self.attrs.soft_delete(&mut errors),
+        };
+
         let keys = S::keys(self.attrs, &mut errors);
+        let mut tenant_probe = None;
 
         add_keys(&keys, &mut params, &mut delete);
 
-        let sql = delete.to_sql_lit(table);
+        if !self.attrs.tenant.is_empty() {
+            let index = params.add_ts(quote!(tenant)).to_string();
+
+            match S::TRANSLATED {
+                // the translated table has no tenant column, the row of the main table is checked.
+                true => {
+                    let mut main = DeleteBuilder::default();
+
+                    for (i, key) in self.attrs.keys_internal().iter().enumerate() {
+                        main.add_key(key, &(i + 1).to_string());
+                    }
+
+                    main.add_key(&self.attrs.tenant, &index);
+
+                    delete.add_condition(&format!(
+                        "EXISTS (SELECT 1 FROM {} WHERE {})",
+                        &*self.attrs.table,
+                        main.wheres()
+                    ));
+                }
+                false => {
+                    // the row of another tenant, when nothing is deleted.
+                    let mut probe = DeleteBuilder::default();
+                    add_keys(&keys, &mut ParamsBuilder::default(), &mut probe);
+                    probe.add_condition(&format!("NOT [{}]=@p{index}", &*self.attrs.tenant));
+
+                    tenant_probe = Some(LitStr::new(
+                        &format!("SELECT 1 FROM {} WHERE {}", &**table, probe.wheres()),
+                        Span::call_site(),
+                    ));
+
+                    delete.add_key(&self.attrs.tenant, &index);
+                }
+            }
+        }
+
+        let sql = match soft_delete {
+            Some(soft_delete) => delete.to_soft_sql_lit(table, &soft_delete.set_deleted()),
+            None => delete.to_sql_lit(table),
+        };
+
+        let execute = match tenant_probe {
+            Some(probe) => quote! {
+                if storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await) == 0 {
+                    storm::tri!(storm_mssql::check_tenant_row(provider, #probe, #params).await);
+                }
+            },
+            None => {
+                quote!(storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await);)
+            }
+        };
 
         tokens.append_all(quote! {
-            storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await);
+            #execute
             #(#errors)*
         });
     }
@@ -68,7 +126,7 @@ fn add_key_single(
     builder.add_key(column, &i.to_string());
 }
 
-fn add_keys(keys: &[&str], params: &mut ParamsBuilder, builder: &mut DeleteBuilder) {
+pub(super) fn add_keys(keys: &[&str], params: &mut ParamsBuilder, builder: &mut DeleteBuilder) {
     match keys {
         [k] => add_key_single(k, quote!(k as _), params, builder),
         _ => add_key_many(keys, params, builder),
@@ -76,13 +134,17 @@ fn add_keys(keys: &[&str], params: &mut ParamsBuilder, builder: &mut DeleteBuild
 }
 
 pub(super) trait AttrsSelector {
+    const TRANSLATED: bool;
+
     fn keys<'a>(attrs: &'a TypeAttrs, errors: &mut Vec<TokenStream>) -> Vec<&'a str>;
     fn table(attrs: &TypeAttrs) -> &SpannedValue<String>;
 }
 
 macro_rules! selector {
-    ($t:ty, $keys:ident, $table:ident) => {
+    ($t:ty, $keys:ident, $table:ident, $translated:literal) => {
         impl AttrsSelector for $t {
+            const TRANSLATED: bool = $translated;
+
             fn keys<'a>(attrs: &'a TypeAttrs, errors: &mut Vec<TokenStream>) -> Vec<&'a str> {
                 attrs.$keys(errors)
             }
@@ -100,6 +162,6 @@ pub(super) mod selectors {
     pub struct Normal;
     pub struct Translate;
 
-    selector!(Normal, keys, table);
-    selector!(Translate, translate_keys, translate_table);
+    selector!(Normal, keys, table, false);
+    selector!(Translate, translate_keys, translate_table, true);
 }
@@ -0,0 +1,150 @@
+use super::{attrs::FieldAttrs, schema::column_schema_of};
+use crate::{
+    entity_fields::enum_fields_impl, entity_validate::entity_validate_impl, DeriveInputExt, Errors,
+    FieldExt, RenameAll,
+};
+use darling::{FromDeriveInput, FromField};
+use inflector::Inflector;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{spanned::Spanned, DeriveInput, Error, Ident, LitInt, LitStr};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(storm), supports(struct_named))]
+struct FlattenAttrs {
+    #[darling(default)]
+    rename_all: Option<RenameAll>,
+
+    /// impl the field diff traits, the struct is diffed as a whole in the entities.
+    #[darling(default)]
+    diff: bool,
+}
+
+/// Implements `Flatten` for a struct embedded in entities, `Gc`, its `EntityValidate` and its
+/// `{Struct}Fields` enum.
+pub(crate) fn flatten(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let attrs = try_ts!(FlattenAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
+    let enum_fields_ident = Ident::new(&format!("{ident}Fields"), ident.span());
+    let mut columns = Vec::new();
+    let mut enum_fields = Vec::new();
+    let mut errors = Vec::new();
+    let mut loads = Vec::new();
+    let mut saves = Vec::new();
+    let mut schema = Vec::new();
+    let mut validations = Vec::new();
+
+    for field in try_ts!(input.fields()) {
+        let field_attrs = continue_ts!(
+            FieldAttrs::from_field(field).map_err(|e| e.write_errors()),
+            errors
+        );
+
+        if field_attrs.is_flatten_unsupported() {
+            errors.push(
+                Error::new(
+                    field.span(),
+                    "Not supported on the field of a flattened struct.",
+                )
+                .to_compile_error(),
+            );
+            continue;
+        }
+
+        let field_ident = continue_ts!(field.ident(), errors);
+        let field_pascal_ident = Ident::new(
+            &field_ident.to_string().to_pascal_case(),
+            field_ident.span(),
+        );
+        let column = continue_ts!(
+            RenameAll::column(attrs.rename_all, &field_attrs.column, field),
+            errors
+        );
+        let column = LitStr::new(&column, field_ident.span());
+        let index = LitInt::new(&columns.len().to_string(), Span::call_site());
+        let variant = quote!(#enum_fields_ident::#field_pascal_ident);
+
+        loads.push(
+            quote!(#field_ident: storm::tri!(storm_mssql::_macro_load_field(row, index + #index)),),
+        );
+        saves.push(
+            quote!(builder.add_field_ref(&format!("[{}{}]", prefix, #column), &self.#field_ident);),
+        );
+        schema.push(column_schema_of(
+            field,
+            &field_attrs,
+            quote!(storm_mssql::_flatten_column(prefix, #column)),
+        ));
+
+        if field_attrs.max_length > 0 {
+            let max = LitInt::new(&field_attrs.max_length.to_string(), Span::call_site());
+
+            validations.push(quote!(
+                storm::macro_check_max_len(storm::Len::len(&self.#field_ident), #max, #variant, error);
+            ));
+        }
+
+        field_attrs
+            .rules
+            .validations(field_ident, variant, &mut validations, &mut errors);
+
+        columns.push(column);
+        enum_fields.push(field_pascal_ident);
+    }
+
+    try_ts!(errors.result());
+
+    let diff = match attrs.diff {
+        true => quote! {
+            impl storm_mssql::ApplyFieldDiff for #ident {
+                fn apply_field_diff(&mut self, value: storm_mssql::serde_json::Value) -> storm::Result<()> {
+                    storm_mssql::apply_field_diff_impl(self, value)
+                }
+            }
+
+            impl storm_mssql::FieldDiff for #ident {
+                fn field_diff(&self, old: &Self) -> Option<storm_mssql::serde_json::Value> {
+                    storm_mssql::field_diff_impl(self, old)
+                }
+            }
+
+            impl storm_mssql::FromFieldDiff for #ident {
+                fn from_field_diff(value: storm_mssql::serde_json::Value) -> storm::Result<Self> {
+                    storm_mssql::from_field_diff_impl(value)
+                }
+            }
+        },
+        false => quote!(),
+    };
+
+    let entity_validate = entity_validate_impl(validations, ident);
+    let enum_fields = enum_fields_impl(&input.vis, ident, enum_fields, &enum_fields_ident);
+
+    quote! {
+        impl storm_mssql::Flatten for #ident {
+            const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+            fn load_flatten(row: &storm_mssql::tiberius::Row, index: usize) -> storm::Result<Self> {
+                Ok(Self { #(#loads)* })
+            }
+
+            fn save_flatten<'a>(&'a self, prefix: &str, builder: &mut storm_mssql::UpsertBuilder<'a>) {
+                #(#saves)*
+            }
+
+            #[allow(clippy::needless_borrow)]
+            fn flatten_schema(prefix: &str) -> Vec<storm_mssql::ColumnSchema> {
+                #[allow(unused_imports)]
+                use storm_mssql::{ProbeSqlType as _, ProbeSqlTypeColumn as _, ProbeSqlTypeUnknown as _};
+
+                vec![#(#schema),*]
+            }
+        }
+
+        impl storm::Gc for #ident {}
+
+        #diff
+        #entity_validate
+        #enum_fields
+    }
+}
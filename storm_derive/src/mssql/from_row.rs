@@ -0,0 +1,72 @@
+use crate::{DeriveInputExt, RenameAll};
+use darling::{FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(storm), supports(struct_named))]
+struct RowAttrs {
+    #[darling(default)]
+    rename_all: Option<RenameAll>,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(storm, from_row))]
+struct RowFieldAttrs {
+    #[darling(default)]
+    column: Option<String>,
+
+    /// The fields of the nested struct are read from the same row.
+    #[darling(default)]
+    flatten: bool,
+}
+
+/// Reads the fields of a struct from the columns of a row by name.
+pub(crate) fn from_row(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let attrs = try_ts!(RowAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut errors = Vec::new();
+    let mut fields = Vec::new();
+    let entity = LitStr::new(&ident.to_string(), ident.span());
+
+    for field in try_ts!(input.fields()) {
+        let field_attrs = continue_ts!(
+            RowFieldAttrs::from_field(field).map_err(|e| e.write_errors()),
+            errors
+        );
+
+        let name = &field.ident;
+        let ty = &field.ty;
+
+        if field_attrs.flatten {
+            fields.push(quote!(#name: storm::tri!(<#ty as storm_mssql::FromRow>::from_row(row)),));
+            continue;
+        }
+
+        let column = continue_ts!(
+            RenameAll::column(attrs.rename_all, &field_attrs.column, field),
+            errors
+        );
+        let column = LitStr::new(&column, ident.span());
+
+        fields.push(
+            quote!(#name: storm::tri!(storm_mssql::_macro_row_field(row, #entity, #column)),),
+        );
+    }
+
+    if !errors.is_empty() {
+        return quote!(#(#errors)*);
+    }
+
+    quote! {
+        impl #impl_generics storm_mssql::FromRow for #ident #ty_generics #where_clause {
+            fn from_row(row: &storm_mssql::tiberius::Row) -> storm::Result<Self> {
+                Ok(Self {
+                    #(#fields)*
+                })
+            }
+        }
+    }
+}
@@ -1,17 +1,18 @@
 use super::{
-    attrs::{check_empty, check_required, FieldAttrs, TypeAttrs},
+    attrs::{check_empty, FieldAttrs, TypeAttrs},
     builders::SelectBuilder,
-    read_row,
+    read_row, tenant_filter,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt as _};
-use syn::{Field, Ident, LitStr};
+use syn::{Field, Ident, LitInt, LitStr, Type};
 
 pub(super) struct LoadFields<'a> {
     attrs: &'a TypeAttrs,
     entity: &'a Ident,
     select: SelectBuilder,
     fields: Vec<TokenStream>,
+    flatten: Vec<(String, Type)>,
 }
 
 impl<'a> LoadFields<'a> {
@@ -20,6 +21,7 @@ impl<'a> LoadFields<'a> {
             attrs,
             entity,
             fields: Default::default(),
+            flatten: Default::default(),
             select: SelectBuilder::with_alias("t"),
         }
     }
@@ -42,6 +44,26 @@ impl<'a> LoadFields<'a> {
         self.fields.push(quote!(#ident: #read,));
     }
 
+    /// The columns of a flattened field follow the ones of the select, they are added at
+    /// runtime.
+    pub fn add_flatten(&mut self, field: &Field, attrs: &FieldAttrs) {
+        let ident = &field.ident;
+        let ty = &field.ty;
+
+        if attrs.skip_load() {
+            self.fields.push(quote!(#ident: Default::default(),));
+            return;
+        }
+
+        let offset = self
+            .flatten
+            .iter()
+            .map(|(_, ty)| quote!(+ <#ty as storm_mssql::Flatten>::COLUMNS.len()));
+
+        self.fields.push(quote!(#ident: storm::tri!(<#ty as storm_mssql::Flatten>::load_flatten(&row, FLATTEN_INDEX #(#offset)*)),));
+        self.flatten.push((attrs.prefix().to_string(), ty.clone()));
+    }
+
     pub fn skip_field(&mut self, field: &Field, attrs: &FieldAttrs, errors: &mut Vec<TokenStream>) {
         check_empty(&attrs.load_with, errors);
 
@@ -53,45 +75,262 @@ impl<'a> LoadFields<'a> {
 impl ToTokens for LoadFields<'_> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let mut errors = Vec::new();
-        let mut select = self.select.clone();
+        let query = self.query(&mut errors);
+
+        tokens.append_all(quote! {
+            #query
+            let mut map: C = storm::tri!(storm_mssql::QueryRows::query_rows(provider, load_sql, &*params, load_row, args.use_transaction).await);
+        });
+
+        tokens.append_all(quote!(#(#errors)*));
+    }
+}
 
-        check_required(&self.attrs.table, &mut errors);
+impl LoadFields<'_> {
+    /// The `load_sql` and the `load_row` function of the loads.
+    fn query(&self, errors: &mut Vec<TokenStream>) -> TokenStream {
+        let mut select = self.select.clone();
 
-        let keys = add_keys(self.attrs, &mut select, &mut errors);
-        let sql = select.to_sql_lit(&self.attrs.table, &self.attrs.where_clause);
+        let source = self.attrs.load_source(errors);
+        let keys = add_keys(self.attrs, &mut select, errors);
+        let flatten_index = self.flatten_index(&select);
+        let where_clause = &self.attrs.where_clause;
+        let sql = self.select_sql("SQL", &select, &source, where_clause);
 
         let entity = self.entity;
         let fields = &self.fields;
         let fields = quote!(#(#fields)*);
+        let filter = filter_lit(where_clause);
+
+        let load_sql = match self.attrs.soft_delete(errors) {
+            Some(soft_delete) => {
+                let not_deleted = match where_clause.is_empty() {
+                    true => soft_delete.not_deleted("t"),
+                    false => format!("({where_clause}) AND {}", soft_delete.not_deleted("t")),
+                };
+
+                let sql_with_deleted =
+                    self.select_sql("SQL_WITH_DELETED", &select, &source, where_clause);
+                let sql_not_deleted = self.select_sql("SQL", &select, &source, &not_deleted);
+                let filter_not_deleted = filter_lit(&not_deleted);
+
+                quote! {
+                    #sql_with_deleted
+                    #sql_not_deleted
 
-        let filter_lit = LitStr::new(
-            match self.attrs.where_clause.is_empty() {
-                true => "{} WHERE {}",
-                false => "{} AND {}",
+                    let load_sql = match (sql.is_empty(), args.include_deleted) {
+                        (false, false) => format!(#filter_not_deleted, SQL, sql),
+                        (false, true) => format!(#filter, SQL_WITH_DELETED, sql),
+                        (true, false) => SQL.to_string(),
+                        (true, true) => SQL_WITH_DELETED.to_string(),
+                    };
+                }
+            }
+            None => quote! {
+                #sql
+
+                let load_sql = match sql.is_empty() {
+                    false => format!(#filter, SQL, sql),
+                    true => SQL.to_string(),
+                };
             },
-            Span::call_site(),
-        );
+        };
 
-        tokens.append_all(quote! {
-            const SQL: &str = #sql;
+        if !self.attrs.temporal {
+            check_empty(&self.attrs.period_start, errors);
+            check_empty(&self.attrs.period_end, errors);
+        }
 
-            let load_sql = match sql.is_empty() {
-                false => format!(#filter_lit, SQL, sql),
-                true => SQL.to_string(),
-            };
+        quote! {
+            #load_sql
 
             fn load_row(row: storm_mssql::tiberius::Row) -> storm::Result<(<#entity as storm::Entity>::Key, #entity)> {
+                #flatten_index
+
                 Ok((
                     #keys,
                     #entity { #fields }
                 ))
             }
+        }
+    }
 
-            let mut map: C = storm::tri!(storm_mssql::QueryRows::query_rows(provider, load_sql, &*params, load_row, args.use_transaction).await);
-        });
+    /// The stream of the rows of `LoadAllStream`, the current rows of a temporal table.
+    /// The errors are reported by the load, the timeout and the cancellation apply to the
+    /// query until the end of the stream.
+    pub fn stream(&self) -> TokenStream {
+        let query = self.query(&mut Vec::new());
 
-        tokens.append_all(quote!(#(#errors)*));
+        let as_of = match self.attrs.temporal {
+            true => quote!(let as_of = false;),
+            false => quote!(),
+        };
+
+        quote! {
+            #as_of
+            #query
+            let args = storm_mssql::ExecuteArgs {
+                use_transaction: args.use_transaction,
+                timeout: args.timeout,
+                cancel: args.cancel.clone(),
+            };
+
+            Ok(storm_mssql::QueryRowsStream::query_rows_stream_with_args(provider, load_sql, params, load_row, args))
+        }
     }
+
+    /// Implements `LoadHistory`, loading all the versions of a row of a temporal table.
+    /// The translated fields are left empty.
+    pub fn history(&self, filter_sql: &impl ToTokens) -> TokenStream {
+        let mut select = self.select.clone();
+        let (period_start, period_end) = self.attrs.period();
+        let valid_from = read_row(select.add_field(period_start));
+        let valid_to = read_row(select.add_field(period_end));
+        let flatten_index = self.flatten_index(&select);
+
+        // the errors are reported by the load.
+        let source = self.attrs.load_source(&mut Vec::new());
+        let table = format!("{source} FOR SYSTEM_TIME ALL");
+        let sql = self.sql_const("HISTORY_SQL", &select, &table, &self.attrs.where_clause);
+        let order_by = LitStr::new(
+            &format!(
+                "{} ORDER BY t.[{period_start}]",
+                filter_lit(&self.attrs.where_clause).value()
+            ),
+            Span::call_site(),
+        );
+
+        let entity = self.entity;
+        let entity_name = LitStr::new(&entity.to_string(), entity.span());
+        let table_name = LitStr::new(self.attrs.table_name(), Span::call_site());
+        let provider = self.attrs.provider();
+        let fields = &self.fields;
+        let tenant_filter = tenant_filter(self.attrs, quote!(self));
+
+        quote! {
+            impl storm_mssql::LoadHistory<#entity> for storm::provider::ProviderContainer {
+                fn load_history_with_args<'a>(&'a self, k: &'a <#entity as storm::Entity>::Key, args: storm::provider::LoadArgs) -> storm::BoxFuture<'a, storm::Result<Vec<storm_mssql::EntityVersion<#entity>>>> {
+                    let timeout = args.timeout;
+                    let cancel = args.cancel.clone();
+
+                    storm_mssql::metrics_helper::load_wrap(async move {
+                        storm::with_timeout(async move {
+                            #sql
+
+                            let provider: &storm_mssql::MssqlProvider = storm::tri!(self.provide(#provider).await);
+                            let (sql, params) = #filter_sql;
+                            #tenant_filter
+                            let history_sql = format!(#order_by, HISTORY_SQL, sql);
+
+                            fn load_version(row: storm_mssql::tiberius::Row) -> storm::Result<storm_mssql::EntityVersion<#entity>> {
+                                #flatten_index
+
+                                Ok(storm_mssql::EntityVersion {
+                                    valid_from: #valid_from,
+                                    valid_to: #valid_to,
+                                    entity: #entity { #(#fields)* },
+                                })
+                            }
+
+                            storm_mssql::QueryRows::query_rows(provider, history_sql, &*params, load_version, args.use_transaction).await
+                        }, timeout, cancel.as_ref()).await
+                    }, #table_name, #entity_name, #provider)
+                }
+            }
+        }
+    }
+
+    /// The select `name` of a load. The period of a temporal table is applied to the table of
+    /// the select when loading `as_of`, the filter parameters are then shifted.
+    fn select_sql(
+        &self,
+        name: &str,
+        select: &SelectBuilder,
+        source: &str,
+        where_clause: &str,
+    ) -> TokenStream {
+        if !self.attrs.temporal {
+            return self.sql_const(name, select, source, where_clause);
+        }
+
+        let current_name = format!("{name}_CURRENT");
+        let as_of_name = format!("{name}_AS_OF");
+        let current = self.sql_const(&current_name, select, source, where_clause);
+        let as_of = self.sql_const(
+            &as_of_name,
+            select,
+            &SelectBuilder::as_of(source),
+            where_clause,
+        );
+        let current_name = Ident::new(&current_name, Span::call_site());
+        let as_of_name = Ident::new(&as_of_name, Span::call_site());
+        let name = Ident::new(name, Span::call_site());
+
+        quote! {
+            #current
+            #as_of
+
+            #[allow(non_snake_case)]
+            let #name: &str = match as_of {
+                true => &#as_of_name,
+                false => &#current_name,
+            };
+        }
+    }
+
+    /// The select of a load, completed with the columns of the flattened fields the first time
+    /// it is used.
+    fn sql_const(
+        &self,
+        name: &str,
+        select: &SelectBuilder,
+        table: &str,
+        where_clause: &str,
+    ) -> TokenStream {
+        let name = Ident::new(name, Span::call_site());
+        let (select, from) = select.to_sql_parts(table, where_clause);
+
+        if self.flatten.is_empty() {
+            let sql = LitStr::new(&format!("{select}{from}"), Span::call_site());
+            return quote!(const #name: &str = #sql;);
+        }
+
+        let select = LitStr::new(&select, Span::call_site());
+        let from = LitStr::new(&from, Span::call_site());
+        let flatten = self
+            .flatten
+            .iter()
+            .map(|(prefix, ty)| quote!((#prefix, <#ty as storm_mssql::Flatten>::COLUMNS)));
+
+        quote! {
+            #[allow(non_snake_case)]
+            let #name: &str = {
+                static CELL: storm::OnceCell<String> = storm::OnceCell::new();
+                CELL.get_or_init(|| storm_mssql::_flatten_sql(#select, #from, &[#(#flatten),*]))
+            };
+        }
+    }
+
+    /// The index of the first column of the flattened fields, after the ones of `select`.
+    fn flatten_index(&self, select: &SelectBuilder) -> TokenStream {
+        match self.flatten.is_empty() {
+            true => quote!(),
+            false => {
+                let index = LitInt::new(&select.len().to_string(), Span::call_site());
+                quote!(const FLATTEN_INDEX: usize = #index;)
+            }
+        }
+    }
+}
+
+fn filter_lit(where_clause: &str) -> LitStr {
+    LitStr::new(
+        match where_clause.is_empty() {
+            true => "{} WHERE {}",
+            false => "{} AND {}",
+        },
+        Span::call_site(),
+    )
 }
 
 fn add_key(key: &str, select: &mut SelectBuilder, key_ts: &mut Vec<TokenStream>) {
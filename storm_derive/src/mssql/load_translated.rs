@@ -39,6 +39,10 @@ impl<'a> LoadTranslated<'a> {
         });
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
     pub fn to_where_clause(&self) -> TokenStream {
         let entity = &self.entity;
 
@@ -61,23 +65,48 @@ impl ToTokens for LoadTranslated<'_> {
 
             let mut select = self.select.clone();
             let mut joins = JoinBuilder::default();
-            let mut conds = joins.inner_join(&self.attrs.table, Some("t"));
+            // the errors of the source are reported by the load of the fields.
+            let source = self.attrs.load_source(&mut Vec::new());
+            let mut conds = joins.inner_join(&source, Some("t"));
 
             let keys = add_keys(self.attrs, &mut conds, &mut select, &mut errors);
             let culture = read_row(select.add_field("Culture"));
             let sql = select.to_sql_lit(&self.attrs.translate_table, &self.attrs.where_clause);
 
-            let joins = format!("{{}} {} WHERE {{}}", joins.to_sql());
-            let joins = LitStr::new(&joins, Span::call_site());
+            let joins = joins_lit(&joins);
             let entity = self.entity;
             let fields = &self.fields;
             let fields = quote!(#(#fields)*);
 
+            // the translated values are the current ones, only the filtered table is as of.
+            let joins = match self.attrs.temporal {
+                true => {
+                    let mut joins_as_of = JoinBuilder::default();
+                    let mut conds = joins_as_of.inner_join_as_of(&source, Some("t"));
+                    add_keys(
+                        self.attrs,
+                        &mut conds,
+                        &mut self.select.clone(),
+                        &mut Vec::new(),
+                    );
+
+                    let joins_as_of = joins_lit(&joins_as_of);
+
+                    quote! {
+                        match as_of {
+                            true => format!(#joins_as_of, TRANSLATED_SQL, sql),
+                            false => format!(#joins, TRANSLATED_SQL, sql),
+                        }
+                    }
+                }
+                false => quote!(format!(#joins, TRANSLATED_SQL, sql)),
+            };
+
             tokens.append_all(quote! {
                 const TRANSLATED_SQL: &str = #sql;
 
                 let translated_sql = match sql.is_empty() {
-                    false => format!(#joins, TRANSLATED_SQL, sql),
+                    false => #joins,
                     true => TRANSLATED_SQL.to_string(),
                 };
 
@@ -98,6 +127,13 @@ impl ToTokens for LoadTranslated<'_> {
     }
 }
 
+fn joins_lit(joins: &JoinBuilder) -> LitStr {
+    LitStr::new(
+        &format!("{{}} {} WHERE {{}}", joins.to_sql()),
+        Span::call_site(),
+    )
+}
+
 fn add_key(
     translate_key: &str,
     key: &str,
@@ -0,0 +1,68 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{env, fs, path::PathBuf};
+use syn::{Error, LitInt, LitStr};
+
+/// Embeds the `{version}_{name}.sql` files of a directory, relative to the manifest of the
+/// crate, as a slice of `storm_mssql::Migration` sorted by version.
+pub(crate) fn embed_migrations(dir: &LitStr) -> TokenStream {
+    migrations(dir).unwrap_or_else(|e| e.to_compile_error())
+}
+
+fn migrations(dir: &LitStr) -> Result<TokenStream, Error> {
+    let root = env::var("CARGO_MANIFEST_DIR").map_err(|e| Error::new(dir.span(), e))?;
+    let path = PathBuf::from(root).join(dir.value());
+    let entries = fs::read_dir(&path)
+        .map_err(|e| Error::new(dir.span(), format!("{}: {e}", path.display())))?;
+
+    let mut migrations = Vec::new();
+
+    for entry in entries {
+        let path = entry.map_err(|e| Error::new(dir.span(), e))?.path();
+
+        if path.extension().is_none_or(|e| e != "sql") {
+            continue;
+        }
+
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+
+        let (version, name) = stem
+            .split_once('_')
+            .and_then(|(v, n)| Some((v.parse::<u64>().ok()?, n)))
+            .ok_or_else(|| {
+                Error::new(
+                    dir.span(),
+                    format!("`{stem}.sql` must be named `{{version}}_{{name}}.sql`."),
+                )
+            })?;
+
+        if let Some((_, other, _)) = migrations.iter().find(|(v, _, _)| *v == version) {
+            return Err(Error::new(
+                dir.span(),
+                format!("`{stem}` and `{other}` have the same version {version}."),
+            ));
+        }
+
+        migrations.push((version, name.to_string(), path.display().to_string()));
+    }
+
+    migrations.sort_by_key(|(v, _, _)| *v);
+
+    let migrations = migrations.iter().map(|(version, name, file)| {
+        let version = LitInt::new(&version.to_string(), Span::call_site());
+
+        // include_str rebuilds the crate when a script changes, not when one is added.
+        quote! {
+            storm_mssql::Migration {
+                version: #version,
+                name: #name,
+                sql: include_str!(#file),
+            }
+        }
+    });
+
+    Ok(quote!(&[#(#migrations),*]))
+}
@@ -1,44 +1,66 @@
 mod attrs;
 mod builders;
 mod delete;
+mod flatten;
+mod from_row;
 mod load_fields;
 mod load_translated;
+mod migrations;
 mod save_translated;
+mod schema;
+mod sql_enum;
 
 use crate::{
+    entity_fields::enum_fields_impl, entity_validate::entity_validate_impl,
     token_stream_ext::TokenStreamExt, DeriveInputExt, Errors, FieldExt, RenameAll, StringExt,
 };
-use attrs::{FieldAttrs, TypeAttrs};
+use attrs::{FieldAttrs, SoftDelete, TypeAttrs};
 use darling::{FromDeriveInput, FromField};
 use delete::Delete;
+pub(crate) use flatten::flatten;
+pub(crate) use from_row::from_row;
 use inflector::Inflector;
 use load_fields::LoadFields;
 use load_translated::LoadTranslated;
+pub(crate) use migrations::embed_migrations;
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt as _};
 use save_translated::SaveTranslated;
-use syn::{DeriveInput, Error, Ident, LitInt, LitStr, Type, Visibility};
+use schema::Schema;
+pub(crate) use sql_enum::sql_enum;
+use syn::{spanned::Spanned, DeriveInput, Error, Ident, LitInt, LitStr, Type};
 
 pub(crate) fn delete(input: &DeriveInput) -> TokenStream {
     let ident = &input.ident;
+    let entity_name = input.entity_name();
     let attrs = try_ts!(TypeAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
-    let normal = Delete::<delete::selectors::Normal>::new(&attrs);
+    let mut errors = Vec::new();
+
+    attrs.check_writable(&mut errors);
+    try_ts!(errors.result());
+
+    let normal = match attrs.delete_proc.is_empty() {
+        true => Delete::<delete::selectors::Normal>::new(&attrs).into_token_stream(),
+        false => try_ts!(delete_proc(&attrs)),
+    };
     let translate = Delete::<delete::selectors::Translate>::new(&attrs);
     let table_name = LitStr::new(&attrs.table, attrs.table.span());
 
     let provider = attrs.provider();
+    let tenant = tenant_param(&attrs);
 
     quote! {
         impl storm::provider::Delete<#ident> for storm::provider::TransactionProvider<'_> {
             fn delete<'a>(&'a self, k: &'a <#ident as storm::Entity>::Key) -> storm::BoxFuture<'a, storm::Result<()>> {
                 storm_mssql::metrics_helper::delete_wrap(async move {
                     let provider: &storm_mssql::MssqlProvider = storm::tri!(self.container().provide(#provider).await);
+                    #tenant
 
                     #translate
                     #normal
 
                     Ok(())
-                }, #table_name)
+                }, #table_name, #entity_name, #provider)
             }
         }
     }
@@ -46,6 +68,7 @@ pub(crate) fn delete(input: &DeriveInput) -> TokenStream {
 
 pub(crate) fn load(input: &DeriveInput) -> TokenStream {
     let ident = &input.ident;
+    let entity_name = input.entity_name();
     let attrs = try_ts!(TypeAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
     let rename_all = attrs.rename_all;
 
@@ -55,19 +78,26 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
     );
 
     let no_test = attrs.no_test;
+    // a custom select has no table to check the lengths against.
+    let check_max_lengths = !no_test && attrs.load_sql.is_empty();
     let mut diff = attrs.diff.then(Vec::new);
     let mut errors = Vec::new();
     let mut filter_sql = FilterSqlImpl::default();
+    let mut filter_sql_as_of = FilterSqlImpl::with_offset(1);
     let mut load = LoadFields::new(ident, &attrs);
     let mut translated = LoadTranslated::new(ident, &attrs);
-    let table_name = LitStr::new(&attrs.table, attrs.table.span());
+    let mut schema = Schema::new(ident, &attrs);
+    let table_name = LitStr::new(attrs.table_name(), Span::call_site());
     let translated_table_name = LitStr::new(&attrs.translate_table, attrs.translate_table.span());
     let enum_fields_ident = Ident::new(&format!("{ident}Fields"), ident.span());
     let mut max_lengths = Vec::new();
     let mut check_entity_fields = Vec::new();
+    let tenant_column: &str = &attrs.tenant;
+    let tenant_filter = tenant_filter(&attrs, quote!(provider));
 
     for key in attrs.keys(&mut errors) {
         filter_sql.add_filter(key);
+        filter_sql_as_of.add_filter(key);
     }
 
     for field in try_ts!(input.fields()) {
@@ -82,17 +112,27 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
 
         let column = continue_ts!(RenameAll::column(rename_all, &attrs.column, field), errors);
 
-        if is_translated(&field.ty) {
+        if attrs.flatten {
+            load.add_flatten(field, &attrs);
+            schema.add_flatten(field, &attrs, attrs.prefix());
+
+            if !attrs.skip_save() && !attrs.skip_diff() {
+                load_diff_field(&mut diff, field_ident, &enum_fields_ident);
+            }
+        } else if is_translated(&field.ty) {
             load.skip_field(field, &attrs, &mut errors);
             translated.add_field(field, &column);
+            schema.add_translated(field, &attrs, &column);
 
             if !attrs.skip_diff() {
                 load_diff_field(&mut diff, field_ident, &enum_fields_ident);
             }
         } else {
             load.add_field(field, &attrs, &column);
+            schema.add_field(field, &attrs, &column);
 
-            if !attrs.skip_save() && !attrs.skip_diff() {
+            // the tenant field is not saved from the entity.
+            if !attrs.skip_save() && !attrs.skip_diff() && !is_tenant(tenant_column, &column) {
                 load_diff_field(&mut diff, field_ident, &enum_fields_ident);
             }
         }
@@ -107,7 +147,7 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
 
             max_lengths.push(quote! { pub const #const_field_name: usize = #max_length; });
 
-            if !no_test {
+            if check_max_lengths {
                 check_entity_fields.push(quote! { (#column_lit, #max_length) });
             }
         }
@@ -118,6 +158,12 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
     let translated_where = translated.to_where_clause();
     let provider = attrs.provider();
     let diff = apply_entity_diff(diff, ident);
+
+    // the schema describes the table or the view, a custom select has none.
+    let schema = match attrs.load_sql.is_empty() {
+        true => schema.into_token_stream(),
+        false => quote!(),
+    };
     let max_lengths = if max_lengths.is_empty() {
         quote! {}
     } else {
@@ -141,6 +187,44 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
         }
     };
 
+    let (as_of_param, as_of_arg, temporal) = match attrs.temporal {
+        true => (
+            quote!(, as_of: bool),
+            quote!(, false),
+            [
+                temporal_impls(ident, &load_fn, &filter_sql_as_of, &translated_where),
+                load.history(&filter_sql),
+            ]
+            .ts(),
+        ),
+        false => (quote!(), quote!(), quote!()),
+    };
+
+    // the translated values are loaded by a second query, filling the collected entities.
+    let stream = match translated.is_empty() {
+        true => {
+            let stream = load.stream();
+
+            quote! {
+                impl<FILTER> storm::provider::LoadAllStream<#ident, FILTER> for storm::provider::ProviderContainer
+                where
+                    FILTER: storm_mssql::FilterSql,
+                {
+                    fn load_all_stream_with_args<'a>(&'a self, filter: &'a FILTER, args: storm::provider::LoadArgs) -> storm::BoxStream<'a, storm::Result<(<#ident as storm::Entity>::Key, #ident)>> {
+                        storm_mssql::metrics_helper::load_stream_wrap(async move {
+                            let provider = self;
+                            let (sql, params) = storm_mssql::FilterSql::filter_sql(filter, 0);
+                            #tenant_filter
+                            let provider: &storm_mssql::MssqlProvider = storm::tri!(provider.provide(#provider).await);
+                            #stream
+                        }, #table_name, #entity_name, #provider)
+                    }
+                }
+            }
+        }
+        false => quote!(),
+    };
+
     let test = if no_test {
         quote!()
     } else {
@@ -163,16 +247,22 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
     };
 
     quote! {
-        fn #load_fn<'a, C>(provider: &'a storm::provider::ProviderContainer, sql: std::borrow::Cow<'a, str>, params: std::borrow::Cow<'a, [&'a dyn storm_mssql::ToSql]>, args: storm::provider::LoadArgs) -> storm::BoxFuture<'a, storm::Result<C>>
+        fn #load_fn<'a, C>(provider: &'a storm::provider::ProviderContainer, sql: std::borrow::Cow<'a, str>, params: std::borrow::Cow<'a, [&'a dyn storm_mssql::ToSql]>, args: storm::provider::LoadArgs #as_of_param) -> storm::BoxFuture<'a, storm::Result<C>>
         where
             C: Default + Extend<(<#ident as storm::Entity>::Key, #ident)> #translated_where + Send + 'static,
         {
+            let timeout = args.timeout;
+            let cancel = args.cancel.clone();
+
             storm_mssql::metrics_helper::load_wrap(async move {
-                let provider: &storm_mssql::MssqlProvider = storm::tri!(provider.provide(#provider).await);
-                #load
-                #translated
-                Ok(map)
-            }, #table_name)
+                storm::with_timeout(async move {
+                    #tenant_filter
+                    let provider: &storm_mssql::MssqlProvider = storm::tri!(provider.provide(#provider).await);
+                    #load
+                    #translated
+                    Ok(map)
+                }, timeout, cancel.as_ref()).await
+            }, #table_name, #entity_name, #provider)
         }
 
         impl<C, FILTER> storm::provider::LoadAll<#ident, FILTER, C> for storm::provider::ProviderContainer
@@ -182,7 +272,7 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
         {
             fn load_all_with_args<'a>(&'a self, filter: &'a FILTER, args: storm::provider::LoadArgs) -> storm::BoxFuture<'a, storm::Result<C>> {
                 let (sql, params) = storm_mssql::FilterSql::filter_sql(filter, 0);
-                #load_fn(self, sql, params, args)
+                #load_fn(self, sql, params, args #as_of_arg)
             }
         }
 
@@ -201,6 +291,9 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
             const TRANSLATED_TABLE: &'static str = #translated_table_name;
         }
 
+        #stream
+        #temporal
+        #schema
         #max_lengths
         #diff
         #test
@@ -209,6 +302,7 @@ pub(crate) fn load(input: &DeriveInput) -> TokenStream {
 
 pub(crate) fn save(input: &DeriveInput) -> TokenStream {
     let ident = &input.ident;
+    let entity_name = input.entity_name();
     let attrs = try_ts!(TypeAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
     let rename_all = attrs.rename_all;
 
@@ -227,8 +321,25 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
     let enum_fields_ident = Ident::new(&format!("{ident}Fields"), ident.span());
     let vis = &input.vis;
 
+    attrs.check_writable(&mut errors);
+
     let keys = attrs.keys(&mut errors);
     let mut identity_found = is_identity_key;
+    let mut tenant_checks = Vec::new();
+    let tenant_column: &str = &attrs.tenant;
+
+    // saving a soft deleted entity restores it.
+    let restore_soft_deleted = match attrs.soft_delete(&mut errors) {
+        Some(SoftDelete::Flag(c)) => {
+            let name = LitStr::new(&format!("[{c}]"), ident.span());
+            quote!(builder.add_field_owned(#name, false);)
+        }
+        Some(SoftDelete::Timestamp(c)) => {
+            let name = LitStr::new(&format!("[{c}]"), ident.span());
+            quote!(builder.add_field_owned(#name, None::<storm_mssql::tiberius::time::chrono::NaiveDateTime>);)
+        }
+        None => quote!(),
+    };
 
     for field in try_ts!(input.fields()) {
         let attrs: FieldAttrs = continue_ts!(
@@ -239,6 +350,7 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
         attrs.validate_save(&mut errors);
 
         if attrs.skip_save() {
+            attrs.rules.unsupported(field.span(), &mut errors);
             continue;
         }
 
@@ -248,11 +360,23 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
 
         if is_identity {
             identity_found = true;
+            attrs.rules.unsupported(field.span(), &mut errors);
+            continue;
+        }
+
+        // the tenant column is saved from the tenant of the container.
+        if is_tenant(tenant_column, column) {
+            let ident = continue_ts!(field.ident(), errors);
+            attrs.rules.unsupported(field.span(), &mut errors);
+            tenant_checks
+                .push(quote!(storm::tri!(storm_mssql::check_tenant(self.container(), &v.#ident));));
             continue;
         }
 
         // keys are processed at the end.
         if keys.contains(&column.as_str()) {
+            attrs.rules.unsupported(field.span(), &mut errors);
+
             if attrs.save_with.is_some() {
                 errors.push(
                     Error::new(attrs.save_with.span(), "Invalid since this field is a key.")
@@ -265,6 +389,28 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
         let ident = continue_ts!(field.ident(), errors);
         let field_pascal_ident = Ident::new(&ident.to_string().to_pascal_case(), ident.span());
 
+        // the fields of a flattened struct are validated and diffed by the struct.
+        if attrs.flatten {
+            let prefix = LitStr::new(attrs.prefix(), ident.span());
+
+            attrs.rules.unsupported(field.span(), &mut errors);
+            save_part
+                .push(quote!(storm_mssql::Flatten::save_flatten(&self.#ident, #prefix, builder);));
+            entity_validations
+                .push(quote!(storm::EntityValidate::entity_validate(&self.#ident, error);));
+
+            if let Some(diff) = diff.as_mut().filter(|_| !attrs.skip_diff()) {
+                diff.push(quote! {
+                    if let Some(diff) = storm_mssql::FieldDiff::field_diff(&self.#ident, &old.#ident) {
+                        map.insert(#enum_fields_ident::#field_pascal_ident, diff);
+                    }
+                });
+            }
+
+            enum_fields.push(field_pascal_ident);
+            continue;
+        }
+
         if attrs.max_length > 0 {
             let expected = LitInt::new(&attrs.max_length.to_string(), Span::call_site());
 
@@ -273,8 +419,19 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
             ));
         }
 
+        if attrs.part {
+            attrs.rules.unsupported(field.span(), &mut errors);
+        } else {
+            attrs.rules.validations(
+                ident,
+                quote!(#enum_fields_ident::#field_pascal_ident),
+                &mut entity_validations,
+                &mut errors,
+            );
+        }
+
         if is_translated(&field.ty) {
-            translated.add_field(field, column);
+            translated.add_field(field, column, &mut errors);
 
             let name_bk = Ident::new(&format!("{ident}_bk"), Span::call_site());
 
@@ -391,17 +548,31 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
 
         quote! {
             #backup
-            *v = storm::tri!(storm::provider::LoadOne::<#ident>::load_one_with_args(self.container(), &k, storm::provider::LoadArgs { use_transaction: true }).await.and_then(|v| v.ok_or(storm::Error::EntityNotFound)));
+            *v = storm::tri!(storm::provider::LoadOne::<#ident>::load_one_with_args(self.container(), &k, storm::provider::LoadArgs { use_transaction: true, ..Default::default() }).await.and_then(|v| v.ok_or(storm::Error::EntityNotFound)));
             #restore
         }
     } else {
         quote!()
     };
 
-    let builder_invoke = if is_identity_key {
-        quote!(storm::tri!(builder.execute_identity(provider, k).await);)
-    } else {
-        quote!(storm::tri!(builder.execute(provider).await);)
+    let save_proc = LitStr::new(&attrs.save_proc, attrs.save_proc.span());
+
+    let builder_invoke = match (attrs.save_proc.is_empty(), is_identity_key) {
+        (true, true) => quote!(storm::tri!(builder.execute_identity(provider, k).await);),
+        (true, false) => quote!(storm::tri!(builder.execute(provider).await);),
+        (false, true) => {
+            quote!(storm::tri!(builder.execute_proc_identity(provider, #save_proc, k).await);)
+        }
+        (false, false) => quote!(storm::tri!(builder.execute_proc(provider, #save_proc).await);),
+    };
+
+    let tenant = tenant_param(&attrs);
+    let add_tenant = match attrs.tenant.is_empty() {
+        true => quote!(),
+        false => {
+            let name = LitStr::new(&format!("[{}]", attrs.tenant), ident.span());
+            quote!(builder.add_tenant_dyn(#name, tenant);)
+        }
     };
 
     let save_part = save_part.ts();
@@ -410,25 +581,29 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
     let provider = attrs.provider();
     let diff = entity_diff(ident, diff);
     let enum_fields = enum_fields_impl(vis, ident, enum_fields, &enum_fields_ident);
-    let entity_validate = entity_validate(entity_validations, ident);
+    let entity_validate = entity_validate_impl(entity_validations, ident);
 
     quote! {
         impl #upsert_trait for storm::provider::TransactionProvider<'_> {
             #upsert_sig {
                 storm_mssql::metrics_helper::upsert_wrap(async move {
                     let provider: &storm_mssql::MssqlProvider = storm::tri!(self.container().provide(#provider).await);
+                    #(#tenant_checks)*
+                    #tenant
                     let mut builder = storm_mssql::UpsertBuilder::new(#table);
                     let entity_part_key = #entity_part_key;
 
                     storm_mssql::SaveEntityPart::save_entity_part(v, entity_part_key, &mut builder);
+                    #restore_soft_deleted
 
                     #wheres
+                    #add_tenant
                     #builder_invoke
                     #reload_entity
                     #translated
 
                     Ok(())
-                }, #table_name)
+                }, #table_name, #entity_name, #provider)
             }
         }
 
@@ -444,14 +619,116 @@ pub(crate) fn save(input: &DeriveInput) -> TokenStream {
     }
 }
 
+/// The call of the delete procedure, the keys and the tenant are passed to the parameters
+/// named after their columns.
+fn delete_proc(attrs: &TypeAttrs) -> Result<TokenStream, TokenStream> {
+    let mut errors = Vec::new();
+    let keys = attrs.keys(&mut errors);
+
+    errors.result()?;
+
+    let proc = LitStr::new(&attrs.delete_proc, attrs.delete_proc.span());
+    let mut inputs = Vec::new();
+
+    for (index, key) in keys.iter().enumerate() {
+        let name = LitStr::new(key, attrs.keys.span());
+
+        inputs.push(match keys.len() > 1 {
+            true => {
+                let n = LitInt::new(&index.to_string(), attrs.keys.span());
+                quote!(.input(#name, &k.#n))
+            }
+            false => quote!(.input(#name, k)),
+        });
+    }
+
+    if !attrs.tenant.is_empty() {
+        let name = LitStr::new(&attrs.tenant, attrs.keys.span());
+        inputs.push(quote!(.input_dyn(#name, tenant)));
+    }
+
+    Ok(quote! {
+        storm::tri!(storm_mssql::Procedure::new(#proc) #(#inputs)* .execute(provider).await);
+    })
+}
+
+/// The loads as of a point in time of a temporal table, sharing the load function of the entity.
+fn temporal_impls(
+    ident: &Ident,
+    load_fn: &Ident,
+    filter_sql_as_of: &FilterSqlImpl,
+    translated_where: &TokenStream,
+) -> TokenStream {
+    quote! {
+        impl<C, FILTER> storm_mssql::LoadAllAsOf<#ident, FILTER, C> for storm::provider::ProviderContainer
+        where
+            C: Default + Extend<(<#ident as storm::Entity>::Key, #ident)> #translated_where + Send + 'static,
+            FILTER: storm_mssql::FilterSql,
+        {
+            fn load_all_as_of_with_args<'a>(&'a self, filter: &'a FILTER, as_of: storm_mssql::tiberius::time::chrono::NaiveDateTime, args: storm::provider::LoadArgs) -> storm::BoxFuture<'a, storm::Result<C>> {
+                Box::pin(async move {
+                    let (sql, filter_params) = storm_mssql::FilterSql::filter_sql(filter, 1);
+                    let mut params: Vec<&dyn storm_mssql::ToSql> = Vec::with_capacity(filter_params.len() + 1);
+                    params.push(&as_of);
+                    params.extend(filter_params.iter().copied());
+
+                    #load_fn(self, sql, std::borrow::Cow::Owned(params), args, true).await
+                })
+            }
+        }
+
+        impl storm_mssql::LoadOneAsOf<#ident> for storm::provider::ProviderContainer {
+            fn load_one_as_of_with_args<'a>(&'a self, k: &'a <#ident as storm::Entity>::Key, as_of: storm_mssql::tiberius::time::chrono::NaiveDateTime, args: storm::provider::LoadArgs) -> storm::BoxFuture<'a, storm::Result<Option<#ident>>> {
+                Box::pin(async move {
+                    let filter = #filter_sql_as_of;
+                    let v: storm::provider::LoadOneInternal<#ident> = storm::tri!(storm_mssql::LoadAllAsOf::load_all_as_of_with_args(self, &filter, as_of, args).await);
+                    Ok(v.into_inner())
+                })
+            }
+        }
+    }
+}
+
+fn is_tenant(tenant: &str, column: &str) -> bool {
+    !tenant.is_empty() && tenant.eq_ignore_ascii_case(column)
+}
+
+/// The tenant of the container as a parameter, for the entities scoped by tenant.
+fn tenant_param(attrs: &TypeAttrs) -> TokenStream {
+    match attrs.tenant.is_empty() {
+        true => quote!(),
+        false => quote!(let tenant = storm::tri!(storm_mssql::tenant_param(self.container()));),
+    }
+}
+
+/// Restricts the filter of a load to the tenant of the container.
+fn tenant_filter(attrs: &TypeAttrs, container: TokenStream) -> TokenStream {
+    match attrs.tenant.is_empty() {
+        true => quote!(),
+        false => {
+            let column = LitStr::new(&format!("t.[{}]", attrs.tenant), Span::call_site());
+            quote!(let (sql, params) = storm::tri!(storm_mssql::tenant_filter(#container, #column, sql, params));)
+        }
+    }
+}
+
 fn is_translated(t: &Type) -> bool {
+    last_segment_is(t, "Translated") || is_translations(t)
+}
+
+/// `storm::Translations`, saved for the cultures it contains.
+fn is_translations(t: &Type) -> bool {
+    last_segment_is(t, "Translations")
+}
+
+fn last_segment_is(t: &Type, name: &str) -> bool {
     match t {
         Type::Path(p) => p
             .path
             .segments
             .iter()
             .last()
-            .map_or(false, |s| &s.ident == "Translated"),
+            .map_or(false, |s| s.ident == name),
         _ => false,
     }
 }
@@ -459,18 +736,27 @@ fn is_translated(t: &Type) -> bool {
 /// Creates a where clauses and parameters for the load sql query.
 #[derive(Default)]
 struct FilterSqlImpl {
+    offset: usize,
     params: Vec<TokenStream>,
     sql: String,
 }
 
 impl FilterSqlImpl {
+    /// The parameters start after `offset` parameters.
+    fn with_offset(offset: usize) -> Self {
+        Self {
+            offset,
+            ..Default::default()
+        }
+    }
+
     fn add_filter(&mut self, key: &str) {
         self.sql
             .add_sep_str("AND")
             .add_str("(t.[")
             .add_str(key)
             .add_str("]=@p")
-            .add_str(&(self.params.len() + 1).to_string())
+            .add_str(&(self.params.len() + 1 + self.offset).to_string())
             .add(')');
 
         let index = LitInt::new(&self.params.len().to_string(), Span::call_site());
@@ -522,17 +808,6 @@ fn entity_diff(ident: &Ident, diff: Option<Vec<TokenStream>>) -> TokenStream {
     }
 }
 
-fn entity_validate(validations: Vec<TokenStream>, ident: &Ident) -> TokenStream {
-    quote! {
-        impl storm::EntityValidate for #ident {
-            #[allow(unused)]
-            fn entity_validate(&self, error: &mut Option<storm::Error>) {
-                #(#validations)*
-            }
-        }
-    }
-}
-
 fn load_diff_field(diff: &mut Option<Vec<TokenStream>>, field: &Ident, enum_fields_ident: &Ident) {
     if let Some(diff) = diff.as_mut() {
         let name = Ident::new(&field.to_string().to_pascal_case(), field.span());
@@ -544,76 +819,3 @@ fn read_row(column_index: usize) -> TokenStream {
     let l = LitInt::new(&column_index.to_string(), Span::call_site());
     quote!(storm::tri!(storm_mssql::_macro_load_field(&row, #l)))
 }
-
-fn enum_fields_impl(
-    vis: &Visibility,
-    ident: &Ident,
-    fields: Vec<Ident>,
-    enum_ident: &Ident,
-) -> TokenStream {
-    if fields.is_empty() {
-        return quote!();
-    }
-
-    let names = fields
-        .iter()
-        .map(|i| LitStr::new(&i.to_string().to_camel_case(), i.span()));
-
-    quote! {
-        #[derive(Clone, Copy, Eq, serde::Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        #vis enum #enum_ident {
-            #(#fields,)*
-        }
-
-        impl AsRef<str> for #enum_ident {
-            fn as_ref(&self) -> &str {
-                match self {
-                    #(Self::#fields => #names,)*
-                }
-            }
-        }
-
-        impl std::fmt::Debug for #enum_ident {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.write_str(self.as_ref())
-            }
-        }
-
-        impl std::fmt::Display for #enum_ident {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.write_str(self.as_ref())
-            }
-        }
-
-        impl std::hash::Hash for #enum_ident {
-            fn hash<H>(&self, state: &mut H)
-            where
-                H: std::hash::Hasher
-            {
-                (*self as u16).hash(state);
-            }
-        }
-
-        impl std::cmp::PartialEq for #enum_ident {
-            fn eq(&self, other: &Self) -> bool {
-                *self as u16 == *other as u16
-            }
-        }
-
-        impl serde::Serialize for #enum_ident {
-            fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer
-            {
-                self.as_ref().serialize(serializer)
-            }
-        }
-
-        impl storm::Fields for #enum_ident {}
-
-        impl storm::EntityFields for #ident {
-            type Fields = #enum_ident;
-        }
-    }
-}
@@ -1,14 +1,17 @@
 use super::{
     attrs::{check_empty, check_required},
-    builders::{ParamsBuilder, UpsertBuilder},
-    TypeAttrs,
+    builders::{DeleteBuilder, ParamsBuilder, UpsertBuilder},
+    delete, is_translations, TypeAttrs,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt as _};
-use syn::{Field, LitInt};
+use syn::{spanned::Spanned, Error, Field, Ident, LitInt};
 
 pub(super) struct SaveTranslated<'a> {
     attrs: &'a TypeAttrs,
+    /// The fields are `Translations`, saved for the cultures they contain.
+    dynamic: Option<bool>,
+    idents: Vec<&'a Ident>,
     params: ParamsBuilder,
     upsert: UpsertBuilder,
 }
@@ -17,15 +20,31 @@ impl<'a> SaveTranslated<'a> {
     pub fn new(attrs: &'a TypeAttrs) -> Self {
         Self {
             attrs,
+            dynamic: None,
+            idents: Vec::new(),
             params: Default::default(),
             upsert: Default::default(),
         }
     }
 
-    pub fn add_field(&mut self, field: &Field, column: &str) {
+    pub fn add_field(&mut self, field: &'a Field, column: &str, errors: &mut Vec<TokenStream>) {
         let ident = &field.ident;
+        let dynamic = is_translations(&field.ty);
+
+        if *self.dynamic.get_or_insert(dynamic) != dynamic {
+            errors.push(
+                Error::new(
+                    field.ty.span(),
+                    "`Translated` and `Translations` fields cannot be mixed.",
+                )
+                .to_compile_error(),
+            );
+        }
+
+        // a `Translations` missing the culture of another field is saved as `NULL`.
         let param_index = self.params.add_ts(quote!(&v.#ident.get(culture) as _));
 
+        self.idents.extend(ident);
         self.upsert.add_field(column, &param_index.to_string());
     }
 }
@@ -46,15 +65,41 @@ impl ToTokens for SaveTranslated<'_> {
 
             add_keys(&keys, &mut params, &mut upsert);
 
-            upsert.add_key("culture", &params.add_ts(quote!(&culture as _)).to_string());
+            if self.dynamic == Some(true) {
+                upsert.add_key("culture", &params.add_ts(quote!(culture as _)).to_string());
+
+                let sql = upsert.to_sql_lit(&self.attrs.translate_table);
+                let idents = &self.idents;
+
+                // the rows of the removed cultures are deleted.
+                let mut delete_params = ParamsBuilder::default();
+                let mut delete = DeleteBuilder::default();
+
+                delete::add_keys(&keys, &mut delete_params, &mut delete);
+
+                let delete_sql = delete.to_sql_lit(&self.attrs.translate_table);
+
+                tokens.append_all(quote! {
+                    let mut cultures = Vec::new();
+                    #(storm::Translations::collect_cultures(&v.#idents, &mut cultures);)*
+
+                    storm::tri!(storm_mssql::Execute::execute(provider, #delete_sql, #delete_params).await);
+
+                    for culture in cultures {
+                        storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await);
+                    }
+                });
+            } else {
+                upsert.add_key("culture", &params.add_ts(quote!(&culture as _)).to_string());
 
-            let sql = upsert.to_sql_lit(&self.attrs.translate_table);
+                let sql = upsert.to_sql_lit(&self.attrs.translate_table);
 
-            tokens.append_all(quote! {
-                for &culture in Culture::DB_CULTURES.iter() {
-                    storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await);
-                }
-            });
+                tokens.append_all(quote! {
+                    for &culture in Culture::DB_CULTURES.iter() {
+                        storm::tri!(storm_mssql::Execute::execute(provider, #sql, #params).await);
+                    }
+                });
+            }
         }
 
         tokens.append_all(quote!(#(#errors)*));
@@ -0,0 +1,263 @@
+use super::{
+    attrs::{FieldAttrs, SoftDelete, TypeAttrs},
+    is_translations,
+};
+use crate::TypeExt;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens, TokenStreamExt as _};
+use syn::{Field, Ident, LitInt, LitStr};
+
+/// Generates the `MssqlSchema` impl, describing the table, keys and columns of the entity.
+pub(super) struct Schema<'a> {
+    attrs: &'a TypeAttrs,
+    entity: &'a Ident,
+    columns: Vec<(String, TokenStream)>,
+    flatten: Vec<TokenStream>,
+    translated: Vec<TokenStream>,
+}
+
+impl<'a> Schema<'a> {
+    pub fn new(entity: &'a Ident, attrs: &'a TypeAttrs) -> Self {
+        Self {
+            attrs,
+            entity,
+            columns: Default::default(),
+            flatten: Default::default(),
+            translated: Default::default(),
+        }
+    }
+
+    pub fn add_field(&mut self, field: &Field, attrs: &FieldAttrs, column: &str) {
+        // a part is saved in multiple columns and a field loaded by a function without being
+        // saved is not backed by a column.
+        if attrs.part || (attrs.skip_save() && (attrs.skip_load() || attrs.load_with.is_some())) {
+            return;
+        }
+
+        self.columns.push((
+            column.to_lowercase(),
+            column_schema_of(field, attrs, column),
+        ));
+    }
+
+    /// The prefixed columns of a flattened field.
+    pub fn add_flatten(&mut self, field: &Field, attrs: &FieldAttrs, prefix: &str) {
+        if attrs.skip_save() && attrs.skip_load() {
+            return;
+        }
+
+        let ty = &field.ty;
+        self.flatten
+            .push(quote!(<#ty as storm_mssql::Flatten>::flatten_schema(#prefix)));
+    }
+
+    pub fn add_translated(&mut self, field: &Field, attrs: &FieldAttrs, column: &str) {
+        let max_length = lit_usize(attrs.max_length);
+
+        let sql_type = match &attrs.sql_type {
+            Some(t) => quote!(Some(std::borrow::Cow::Borrowed(#t))),
+            None => quote!(Some(<String as storm_mssql::SqlType>::sql_type(#max_length))),
+        };
+
+        self.translated.push(column_schema(
+            column,
+            sql_type,
+            is_translations(&field.ty),
+            attrs.max_length,
+        ));
+    }
+}
+
+impl ToTokens for Schema<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let entity = self.entity;
+        let entity_name = LitStr::new(&entity.to_string(), entity.span());
+        let provider = self.attrs.provider();
+        let table = LitStr::new(self.attrs.table_name(), Span::call_site());
+        let keys = self.attrs.keys_internal();
+
+        let key_types = match keys.len() {
+            1 => quote! {
+                vec![(&&&storm_mssql::SqlTypeProbe::<<#entity as storm::Entity>::Key>::new()).probe_sql_type(0)]
+            },
+            _ => quote! {
+                (&storm_mssql::SqlTypeProbe::<<#entity as storm::Entity>::Key>::new()).probe_key_sql_types()
+            },
+        };
+
+        let mut columns = Vec::new();
+
+        for (index, key) in keys.iter().enumerate() {
+            let key_lc = key.to_lowercase();
+
+            match self.columns.iter().find(|(c, _)| *c == key_lc) {
+                Some((_, field)) => columns.push(field.clone()),
+                None => columns.push(column_schema(key, key_type(index), false, 0)),
+            }
+        }
+
+        columns.extend(
+            self.columns
+                .iter()
+                .filter(|(c, _)| !keys.iter().any(|k| k.to_lowercase() == *c))
+                .map(|(_, ts)| ts.clone()),
+        );
+
+        // the tenant and the soft delete columns follow the ones of the flattened fields,
+        // only known at runtime.
+        let mut trailing = Vec::new();
+
+        // the type of a tenant column without field is unknown.
+        let tenant = &self.attrs.tenant;
+
+        if !tenant.is_empty()
+            && !self
+                .columns
+                .iter()
+                .any(|(c, _)| *c == tenant.to_lowercase())
+        {
+            trailing.push(column_schema(tenant, quote!(None), false, 0));
+        }
+
+        // errors are reported by the loading of the fields.
+        match self.attrs.soft_delete(&mut Vec::new()) {
+            Some(SoftDelete::Flag(c)) => trailing.push(column_schema(
+                c,
+                quote!(Some(std::borrow::Cow::Borrowed("BIT"))),
+                false,
+                0,
+            )),
+            Some(SoftDelete::Timestamp(c)) => trailing.push(column_schema(
+                c,
+                quote!(Some(std::borrow::Cow::Borrowed("DATETIME2"))),
+                true,
+                0,
+            )),
+            None => {}
+        }
+
+        let columns = match self.flatten.is_empty() {
+            true => quote!(vec![#(#columns,)* #(#trailing),*]),
+            false => {
+                let flatten = &self.flatten;
+                quote!([vec![#(#columns),*], #(#flatten,)* vec![#(#trailing),*]].concat())
+            }
+        };
+
+        let view = !self.attrs.view.is_empty();
+        let identity = match self.attrs.identity.is_empty() {
+            true => quote!(None),
+            false => {
+                let identity = LitStr::new(&self.attrs.identity, Span::call_site());
+                quote!(Some(#identity))
+            }
+        };
+
+        let translated = if self.translated.is_empty() {
+            quote!(None)
+        } else {
+            // errors are reported by the loading of the translated fields.
+            let translate_keys = self.attrs.translate_keys(&mut Vec::new());
+            let translated_table = LitStr::new(&self.attrs.translate_table, Span::call_site());
+
+            let key_columns = translate_keys
+                .iter()
+                .enumerate()
+                .map(|(index, key)| column_schema(key, key_type(index), false, 0));
+
+            let fields = &self.translated;
+            let culture = column_schema(
+                quote!("Culture"),
+                quote!((&&&storm_mssql::SqlTypeProbe::<Culture>::new()).probe_sql_type(0)),
+                false,
+                0,
+            );
+
+            quote! {
+                Some(storm_mssql::TranslatedSchema {
+                    table: #translated_table,
+                    columns: vec![#(#key_columns,)* #(#fields,)*],
+                    keys: vec![#(#translate_keys),*],
+                    culture: #culture,
+                })
+            }
+        };
+
+        tokens.append_all(quote! {
+            impl storm_mssql::MssqlSchema for #entity {
+                #[allow(clippy::needless_borrow, unused_variables)]
+                fn schema() -> storm_mssql::TableSchema {
+                    #[allow(unused_imports)]
+                    use storm_mssql::{ProbeKeySqlTypes as _, ProbeSqlType as _, ProbeSqlTypeColumn as _, ProbeSqlTypeUnknown as _};
+
+                    let key_types: Vec<Option<std::borrow::Cow<'static, str>>> = #key_types;
+
+                    storm_mssql::TableSchema {
+                        entity: #entity_name,
+                        provider: #provider,
+                        table: #table,
+                        columns: #columns,
+                        keys: vec![#(#keys),*],
+                        identity: #identity,
+                        translated: #translated,
+                        view: #view,
+                    }
+                }
+            }
+
+            const _: () = {
+                #[static_init::dynamic]
+                static R: () = storm_mssql::register_schema::<#entity>();
+            };
+        });
+    }
+}
+
+/// The column of a field, its sql type inferred from the field type unless overridden.
+pub(super) fn column_schema_of(
+    field: &Field,
+    attrs: &FieldAttrs,
+    column: impl ToTokens,
+) -> TokenStream {
+    let ty = &field.ty;
+    let max_length = lit_usize(attrs.max_length);
+
+    let sql_type = match &attrs.sql_type {
+        Some(t) => quote!(Some(std::borrow::Cow::Borrowed(#t))),
+        None if attrs.save_with.is_some() => quote!(None),
+        None => {
+            quote!((&&&storm_mssql::SqlTypeProbe::<#ty>::new()).probe_sql_type(#max_length))
+        }
+    };
+
+    let nullable = ty.is_type_of_segment(&["Option"]);
+
+    column_schema(column, sql_type, nullable, attrs.max_length)
+}
+
+fn column_schema(
+    column: impl ToTokens,
+    sql_type: TokenStream,
+    nullable: bool,
+    max_length: usize,
+) -> TokenStream {
+    let max_length = lit_usize(max_length);
+
+    quote! {
+        storm_mssql::ColumnSchema {
+            name: std::convert::Into::into(#column),
+            sql_type: #sql_type,
+            nullable: #nullable,
+            max_length: #max_length,
+        }
+    }
+}
+
+fn key_type(index: usize) -> TokenStream {
+    let index = lit_usize(index);
+    quote!(key_types.get(#index).cloned().flatten())
+}
+
+fn lit_usize(v: usize) -> LitInt {
+    LitInt::new(&v.to_string(), Span::call_site())
+}
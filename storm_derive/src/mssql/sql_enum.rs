@@ -0,0 +1,283 @@
+use darling::{ast::Data, util::SpannedValue, FromDeriveInput, FromVariant};
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::quote;
+use syn::{
+    spanned::Spanned, DeriveInput, Error, Expr, ExprLit, ExprUnary, Ident, Lit, LitStr, UnOp,
+};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(sql), supports(enum_unit))]
+struct EnumAttrs {
+    ident: Ident,
+    data: Data<VariantAttrs, ()>,
+
+    /// `u8`, `i16`, `i32`, `i64` or `string`.
+    repr: SpannedValue<String>,
+
+    /// The enum derives serde itself.
+    #[darling(default)]
+    no_serde: bool,
+}
+
+#[derive(Debug, FromVariant)]
+#[darling(attributes(sql))]
+struct VariantAttrs {
+    ident: Ident,
+    discriminant: Option<Expr>,
+
+    #[darling(default)]
+    value: Option<Lit>,
+}
+
+/// Maps a unit enum to an int or a string column.
+pub(crate) fn sql_enum(input: &DeriveInput) -> TokenStream {
+    let attrs = try_ts!(EnumAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
+    let ident = &attrs.ident;
+    let name = ident.to_string();
+
+    let (repr, column, column_data) = match attrs.repr.as_str() {
+        "u8" => (quote!(u8), quote!(u8), quote!(U8)),
+        "i16" => (quote!(i16), quote!(i16), quote!(I16)),
+        "i32" => (quote!(i32), quote!(i32), quote!(I32)),
+        "i64" => (quote!(i64), quote!(i64), quote!(I64)),
+        "string" => (quote!(&'static str), quote!(&'a str), quote!(String)),
+        _ => {
+            return Error::new(
+                attrs.repr.span(),
+                "Expected `u8`, `i16`, `i32`, `i64` or `string`.",
+            )
+            .to_compile_error()
+        }
+    };
+
+    let is_string = *attrs.repr == "string";
+    let mut errors = Vec::new();
+    let mut idents = Vec::new();
+    let mut values = Vec::new();
+    let mut longest = 0usize;
+    let mut next = 0i64;
+
+    for variant in attrs.data.take_enum().unwrap_or_default() {
+        let value = match (&variant.value, is_string) {
+            (Some(Lit::Str(s)), true) => {
+                longest = longest.max(s.value().chars().count());
+                quote!(#s)
+            }
+            (Some(Lit::Int(i)), false) => {
+                next = continue_ts!(
+                    i.base10_parse::<i64>().map_err(|e| e.to_compile_error()),
+                    errors
+                );
+                int_lit(next)
+            }
+            (None, true) => {
+                let s = LitStr::new(&variant.ident.to_string(), variant.ident.span());
+                longest = longest.max(s.value().chars().count());
+                quote!(#s)
+            }
+            // the values follow the discriminants of the enum, like `as` casts.
+            (None, false) => {
+                if let Some(expr) = &variant.discriminant {
+                    next =
+                        continue_ts!(discriminant(expr).map_err(|e| e.to_compile_error()), errors);
+                }
+
+                int_lit(next)
+            }
+            (Some(lit), _) => {
+                errors.push(
+                    Error::new(lit.span(), "The value does not match the repr of the enum.")
+                        .to_compile_error(),
+                );
+                continue;
+            }
+        };
+
+        let value_str = value.to_string();
+
+        if values
+            .iter()
+            .any(|v: &TokenStream| v.to_string() == value_str)
+        {
+            errors.push(Error::new(variant.ident.span(), "Duplicate value.").to_compile_error());
+        }
+
+        next = next.wrapping_add(1);
+        idents.push(variant.ident);
+        values.push(value);
+    }
+
+    if !errors.is_empty() {
+        return quote!(#(#errors)*);
+    }
+
+    let invalid = LitStr::new(
+        &format!("`{{}}` is not a valid `{name}`."),
+        Span::call_site(),
+    );
+
+    let (sql_type, to_column_data) = match is_string {
+        true => (
+            quote!(String),
+            quote!(storm_mssql::tiberius::ColumnData::String(Some(
+                std::borrow::Cow::Borrowed(self.sql_value())
+            ))),
+        ),
+        false => (
+            repr.clone(),
+            quote!(storm_mssql::tiberius::ColumnData::#column_data(Some(self.sql_value()))),
+        ),
+    };
+
+    // the string columns are as long as the longest value by default.
+    let max_length = match is_string {
+        true => quote!(if max_length == 0 { #longest } else { max_length }),
+        false => quote!(max_length),
+    };
+
+    let (param, from_diff_value) = match is_string {
+        true => (quote!(&str), quote!(&*v)),
+        false => (repr.clone(), quote!(v)),
+    };
+
+    let serde = match attrs.no_serde {
+        true => quote!(),
+        false => quote! {
+            impl<'de> storm_mssql::serde::Deserialize<'de> for #ident {
+                fn deserialize<D: storm_mssql::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let v = <#sql_type as storm_mssql::serde::Deserialize>::deserialize(deserializer)?;
+
+                    Self::from_sql_value(#from_diff_value)
+                        .ok_or_else(|| <D::Error as storm_mssql::serde::de::Error>::custom(format!(#invalid, v)))
+                }
+            }
+
+            impl storm_mssql::serde::Serialize for #ident {
+                fn serialize<S: storm_mssql::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    storm_mssql::serde::Serialize::serialize(&self.sql_value(), serializer)
+                }
+            }
+        },
+    };
+
+    quote! {
+        impl #ident {
+            /// The value stored in the database.
+            pub const fn sql_value(&self) -> #repr {
+                match self {
+                    #(Self::#idents => #values,)*
+                }
+            }
+
+            pub fn from_sql_value(v: #param) -> Option<Self> {
+                match v {
+                    #(#values => Some(Self::#idents),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl storm_mssql::ApplyFieldDiff for #ident {
+            fn apply_field_diff(&mut self, value: storm_mssql::serde_json::Value) -> storm::Result<()> {
+                *self = storm_mssql::FromFieldDiff::from_field_diff(value)?;
+                Ok(())
+            }
+        }
+
+        impl storm_mssql::FieldDiff for #ident {
+            fn field_diff(&self, old: &Self) -> Option<storm_mssql::serde_json::Value> {
+                let old = old.sql_value();
+
+                if self.sql_value() == old {
+                    None
+                } else {
+                    Some(storm_mssql::serde_json::json!(old))
+                }
+            }
+        }
+
+        impl storm_mssql::FromFieldDiff for #ident {
+            fn from_field_diff(value: storm_mssql::serde_json::Value) -> storm::Result<Self> {
+                let v: #sql_type = storm_mssql::serde_json::from_value(value).map_err(storm::Error::std)?;
+
+                Self::from_sql_value(#from_diff_value)
+                    .ok_or_else(|| storm::Error::ConvertFailed(format!(#invalid, v)))
+            }
+        }
+
+        impl<'a> storm_mssql::FromSql<'a> for #ident {
+            type Column = #column;
+
+            fn from_sql(col: Option<Self::Column>) -> storm::Result<Self> {
+                match col {
+                    Some(v) => Self::from_sql_value(v).ok_or_else(|| storm::Error::ConvertFailed(format!(#invalid, v))),
+                    None => Err(storm::Error::ColumnNull),
+                }
+            }
+        }
+
+        #serde
+
+        impl storm::Gc for #ident {}
+
+        impl storm_mssql::SqlType for #ident {
+            fn sql_type(max_length: usize) -> std::borrow::Cow<'static, str> {
+                <#sql_type as storm_mssql::SqlType>::sql_type(#max_length)
+            }
+        }
+
+        impl storm_mssql::ToSql for #ident {
+            fn to_sql(&self) -> storm_mssql::tiberius::ColumnData<'_> {
+                #to_column_data
+            }
+        }
+
+        impl storm_mssql::ToSqlNull for #ident {
+            fn to_sql_null() -> storm_mssql::tiberius::ColumnData<'static> {
+                storm_mssql::tiberius::ColumnData::#column_data(None)
+            }
+        }
+    }
+}
+
+/// The value of a discriminant, an integer literal optionally negated.
+fn discriminant(expr: &Expr) -> syn::Result<i64> {
+    let (negative, lit) = match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => (false, i),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(i), ..
+            }) => (true, i),
+            _ => return Err(discriminant_error(expr)),
+        },
+        _ => return Err(discriminant_error(expr)),
+    };
+
+    let v = lit.base10_parse::<i128>()?;
+    let v = if negative { -v } else { v };
+
+    i64::try_from(v).map_err(|_| Error::new(lit.span(), "The discriminant is out of range."))
+}
+
+fn discriminant_error(expr: &Expr) -> Error {
+    Error::new(
+        expr.span(),
+        "Only integer literals are supported as discriminants, use `#[sql(value = ...)]`.",
+    )
+}
+
+/// An integer literal token, negative values are emitted as a negation.
+fn int_lit(v: i64) -> TokenStream {
+    let abs = Literal::u64_unsuffixed(v.unsigned_abs());
+
+    match v < 0 {
+        true => quote!(-#abs),
+        false => quote!(#abs),
+    }
+}
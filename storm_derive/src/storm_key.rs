@@ -0,0 +1,187 @@
+use crate::TypeExt;
+use darling::{ast::Data, util::SpannedValue, FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, DeriveInput, Error, Ident, LitStr, Type};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(storm), supports(struct_newtype))]
+struct KeyAttrs {
+    ident: Ident,
+    data: Data<(), KeyField>,
+
+    /// Implements the `usize` conversions required by `VecTable` and `OneToMany`, on by default
+    /// for the integer keys.
+    #[darling(default)]
+    vec_table: SpannedValue<Option<bool>>,
+}
+
+#[derive(Debug, FromField)]
+struct KeyField {
+    ty: Type,
+}
+
+/// The integers stored by `storm_mssql`, `isize` and `usize` depend on the platform.
+const INTEGERS: [&str; 8] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"];
+
+pub(crate) fn generate(input: &DeriveInput) -> TokenStream {
+    let attrs = try_ts!(KeyAttrs::from_derive_input(input).map_err(|e| e.write_errors()));
+    let ident = &attrs.ident;
+
+    let inner = match attrs
+        .data
+        .take_struct()
+        .and_then(|s| s.fields.into_iter().next())
+    {
+        Some(f) => f.ty,
+        None => return Error::new(ident.span(), "Expected a newtype.").to_compile_error(),
+    };
+
+    let is_integer = INTEGERS.iter().any(|t| inner.is_type_of_segment(&[t]));
+
+    if !is_integer && !inner.is_type_of_segment(&["Uuid"]) {
+        return Error::new(
+            inner.span(),
+            "Expected `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64` or `Uuid`.",
+        )
+        .to_compile_error();
+    }
+
+    let vec_table = match *attrs.vec_table {
+        Some(true) if !is_integer => {
+            return Error::new(
+                attrs.vec_table.span(),
+                "Only an integer key can back a `VecTable`.",
+            )
+            .to_compile_error()
+        }
+        Some(v) => v,
+        None => is_integer,
+    };
+
+    // the conversions are infallible for `VecTable`, a key out of range panics instead of
+    // wrapping to another index.
+    let vec_table = match vec_table {
+        true => {
+            let to_key = LitStr::new(
+                &format!("The index is out of the range of `{ident}`."),
+                ident.span(),
+            );
+            let to_index = LitStr::new(
+                &format!("The `{ident}` is not a valid index."),
+                ident.span(),
+            );
+
+            quote! {
+                impl From<usize> for #ident {
+                    #[inline]
+                    #[allow(clippy::expect_used)]
+                    fn from(v: usize) -> Self {
+                        Self(<#inner as std::convert::TryFrom<usize>>::try_from(v).expect(#to_key))
+                    }
+                }
+
+                impl From<#ident> for usize {
+                    #[inline]
+                    #[allow(clippy::expect_used)]
+                    fn from(v: #ident) -> Self {
+                        <usize as std::convert::TryFrom<#inner>>::try_from(v.0).expect(#to_index)
+                    }
+                }
+            }
+        }
+        false => quote!(),
+    };
+
+    let mssql = mssql(ident, &inner);
+
+    quote! {
+        impl Clone for #ident {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl Copy for #ident {}
+
+        impl Eq for #ident {}
+
+        impl std::hash::Hash for #ident {
+            #[inline]
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                std::hash::Hash::hash(&self.0, state)
+            }
+        }
+
+        impl PartialEq for #ident {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl<'de> storm::serde::Deserialize<'de> for #ident {
+            fn deserialize<D: storm::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <#inner as storm::serde::Deserialize>::deserialize(deserializer).map(Self)
+            }
+        }
+
+        impl storm::Gc for #ident {}
+
+        impl storm::IsDefined for #ident {
+            #[inline]
+            fn is_defined(&self) -> bool {
+                storm::IsDefined::is_defined(&self.0)
+            }
+        }
+
+        impl storm::serde::Serialize for #ident {
+            fn serialize<S: storm::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                storm::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        #vec_table
+        #mssql
+    }
+}
+
+#[cfg(feature = "mssql")]
+fn mssql(ident: &Ident, inner: &Type) -> TokenStream {
+    quote! {
+        impl<'a> storm_mssql::FromSql<'a> for #ident {
+            type Column = <#inner as storm_mssql::FromSql<'a>>::Column;
+
+            #[inline]
+            fn from_sql(col: Option<Self::Column>) -> storm::Result<Self> {
+                <#inner as storm_mssql::FromSql<'a>>::from_sql(col).map(Self)
+            }
+        }
+
+        impl storm_mssql::SqlType for #ident {
+            fn sql_type(max_length: usize) -> std::borrow::Cow<'static, str> {
+                <#inner as storm_mssql::SqlType>::sql_type(max_length)
+            }
+        }
+
+        impl storm_mssql::ToSql for #ident {
+            #[inline]
+            fn to_sql(&self) -> storm_mssql::tiberius::ColumnData<'_> {
+                storm_mssql::ToSql::to_sql(&self.0)
+            }
+        }
+
+        impl storm_mssql::ToSqlNull for #ident {
+            #[inline]
+            fn to_sql_null() -> storm_mssql::tiberius::ColumnData<'static> {
+                <#inner as storm_mssql::ToSqlNull>::to_sql_null()
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "mssql"))]
+fn mssql(_ident: &Ident, _inner: &Type) -> TokenStream {
+    quote!()
+}
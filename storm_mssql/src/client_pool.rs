@@ -0,0 +1,264 @@
+use crate::{Client, ClientFactory, RetryPolicy};
+use std::time::{Duration, Instant};
+use storm::{parking_lot::Mutex, Error, Result};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::warn;
+
+/// Indicate how the non-transactional clients of the [MssqlProvider](crate::MssqlProvider)
+/// are pooled.
+///
+/// Connections are opened on demand, up to `max_size` connections used at the same time.
+/// The transaction client is not part of the pool, it stays exclusive to the transaction.
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    /// The maximum number of non-transactional clients used at the same time.
+    pub max_size: usize,
+
+    /// The number of idle clients kept open by the garbage collector, even when
+    /// they exceed the idle timeout.
+    pub min_size: usize,
+
+    /// Idle clients unused for this duration are closed on checkout or on
+    /// `ProviderContainer::gc`. `None` keeps them open.
+    pub idle_timeout: Option<Duration>,
+
+    /// Send a `SELECT 1` to an idle client before returning it from the pool.
+    pub health_check: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            min_size: 1,
+            idle_timeout: Some(Duration::from_secs(300)),
+            health_check: true,
+        }
+    }
+}
+
+struct IdleClient {
+    client: Client,
+    lock_timeout: Option<Duration>,
+    since: Instant,
+}
+
+pub(crate) struct ClientPool {
+    config: PoolConfig,
+    factory: Box<dyn ClientFactory>,
+    idle: Mutex<Vec<IdleClient>>,
+    lock_timeout: Mutex<Option<Duration>>,
+    permits: Semaphore,
+}
+
+impl ClientPool {
+    pub fn new(factory: Box<dyn ClientFactory>, config: PoolConfig) -> Self {
+        Self {
+            // at least one client must be available, otherwise checkout waits forever.
+            permits: Semaphore::new(config.max_size.clamp(1, Semaphore::MAX_PERMITS)),
+            config,
+            factory,
+            idle: Default::default(),
+            lock_timeout: Mutex::new(Some(crate::mssql_provider::DEFAULT_LOCK_TIMEOUT)),
+        }
+    }
+
+    pub fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+
+    pub fn factory(&self) -> &dyn ClientFactory {
+        self.factory.as_ref()
+    }
+
+    /// Returns a client from the pool, waiting if `max_size` clients are already in use.
+    pub async fn checkout(&self, policy: &RetryPolicy) -> Result<PooledClient<'_>> {
+        let permit = self.permits.acquire().await.map_err(Error::std)?;
+        let lock_timeout = self.lock_timeout();
+        let client = self.take_imp(policy, lock_timeout).await?;
+
+        Ok(PooledClient {
+            client: Some(client),
+            lock_timeout,
+            permit: Some(permit),
+            pool: self,
+        })
+    }
+
+    /// Closes the idle clients that exceeded the idle timeout, keeping `min_size` clients open.
+    pub fn gc(&self) {
+        let Some(timeout) = self.config.idle_timeout else {
+            return;
+        };
+
+        let mut idle = self.idle.lock();
+        let mut keep = self.config.min_size;
+
+        // the most recently used clients are at the end.
+        idle.reverse();
+        idle.retain(|c| {
+            if keep > 0 {
+                keep -= 1;
+                true
+            } else {
+                c.since.elapsed() < timeout
+            }
+        });
+        idle.reverse();
+    }
+
+    pub fn lock_timeout(&self) -> Option<Duration> {
+        *self.lock_timeout.lock()
+    }
+
+    /// Returns a client to the pool. A client returned while the pool already holds
+    /// `max_size` idle clients is closed.
+    pub fn release(&self, client: Client, lock_timeout: Option<Duration>) {
+        let mut idle = self.idle.lock();
+
+        if idle.len() < self.config.max_size {
+            idle.push(IdleClient {
+                client,
+                lock_timeout,
+                since: Instant::now(),
+            });
+        }
+    }
+
+    /// Changes the lock timeout of the clients. Idle clients are updated on checkout.
+    pub fn set_lock_timeout(&self, timeout: Option<Duration>) {
+        *self.lock_timeout.lock() = timeout;
+    }
+
+    /// Takes a client out of the pool without any permit, for the exclusive use of a
+    /// transaction.
+    pub async fn take(&self, policy: &RetryPolicy) -> Result<Client> {
+        self.take_imp(policy, self.lock_timeout()).await
+    }
+
+    async fn take_imp(
+        &self,
+        policy: &RetryPolicy,
+        lock_timeout: Option<Duration>,
+    ) -> Result<Client> {
+        while let Some(idle) = self.pop_idle() {
+            match self.revive(idle, lock_timeout).await {
+                Ok(client) => return Ok(client),
+                Err(e) => warn!(error = %e, "discarding pooled mssql client"),
+            }
+        }
+
+        self.create_client(policy, lock_timeout).await
+    }
+
+    pub async fn create_client(
+        &self,
+        policy: &RetryPolicy,
+        lock_timeout: Option<Duration>,
+    ) -> Result<Client> {
+        let mut attempt = 1;
+
+        loop {
+            match self.create_client_imp(lock_timeout).await {
+                Ok(client) => return Ok(client),
+                Err(e) => match policy.retry_delay("create_client", attempt, &e) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return Err(e),
+                },
+            }
+
+            attempt += 1;
+        }
+    }
+
+    async fn create_client_imp(&self, lock_timeout: Option<Duration>) -> Result<Client> {
+        let mut client = self.factory.create_client().await?;
+
+        set_client_lock_timeout(&mut client, lock_timeout).await?;
+
+        Ok(client)
+    }
+
+    fn pop_idle(&self) -> Option<IdleClient> {
+        let mut idle = self.idle.lock();
+
+        while let Some(c) = idle.pop() {
+            match self.config.idle_timeout {
+                Some(timeout) if c.since.elapsed() >= timeout => continue,
+                _ => return Some(c),
+            }
+        }
+
+        None
+    }
+
+    async fn revive(&self, idle: IdleClient, lock_timeout: Option<Duration>) -> Result<Client> {
+        let mut client = idle.client;
+
+        if idle.lock_timeout != lock_timeout {
+            set_client_lock_timeout(&mut client, lock_timeout).await?;
+        } else if self.config.health_check {
+            client
+                .simple_query("SELECT 1")
+                .await?
+                .into_results()
+                .await?;
+        }
+
+        Ok(client)
+    }
+}
+
+/// A client checked out of the pool. It must be given back with `release` once the
+/// operation succeeds; a client dropped after a failure, a timeout or a cancellation is evicted
+/// and its connection closed since its state is unknown.
+///
+/// tiberius cannot send an attention to cancel a running statement, closing the connection
+/// is what makes the server abort the statement and roll back its transaction.
+pub(crate) struct PooledClient<'a> {
+    client: Option<Client>,
+    lock_timeout: Option<Duration>,
+    permit: Option<SemaphorePermit<'a>>,
+    pool: &'a ClientPool,
+}
+
+impl PooledClient<'_> {
+    pub fn client(&mut self) -> Result<&mut Client> {
+        self.client.as_mut().ok_or(Error::Internal)
+    }
+
+    pub fn release(mut self) {
+        if let Some(client) = self.client.take() {
+            self.pool.release(client, self.lock_timeout);
+        }
+
+        self.permit = None;
+    }
+}
+
+impl Drop for PooledClient<'_> {
+    fn drop(&mut self) {
+        if self.client.take().is_some() {
+            warn!("evicting pooled mssql client after an incomplete operation");
+
+            #[cfg(feature = "telemetry")]
+            {
+                metrics::counter!("storm_mssql_pool_evicted").increment(1);
+            }
+        }
+    }
+}
+
+pub(crate) async fn set_client_lock_timeout(
+    client: &mut Client,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    client
+        .simple_query(format!(
+            "SET LOCK_TIMEOUT {};",
+            timeout.map_or(-1, |d| d.as_millis() as i128)
+        ))
+        .await?;
+
+    Ok(())
+}
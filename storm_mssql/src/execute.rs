@@ -1,6 +1,6 @@
 use crate::ToSql;
-use std::{borrow::Cow, fmt::Debug};
-use storm::{BoxFuture, Result};
+use std::{borrow::Cow, fmt::Debug, time::Duration};
+use storm::{BoxFuture, CancellationToken, Result};
 
 pub trait Execute {
     fn execute_with_args<'a, S>(
@@ -25,15 +25,27 @@ pub trait Execute {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct ExecuteArgs {
     pub use_transaction: bool,
+
+    /// Fails the statement with `Error::Timeout` if it takes longer, retries included.
+    ///
+    /// tiberius cannot cancel a running statement, the connection running it is evicted and
+    /// closed when the timeout fires, the server then aborts the statement and rolls back the
+    /// transaction.
+    pub timeout: Option<Duration>,
+
+    /// Fails the statement with `Error::Cancelled` when the token is cancelled.
+    pub cancel: Option<CancellationToken>,
 }
 
 impl Default for ExecuteArgs {
     fn default() -> Self {
         Self {
             use_transaction: true,
+            timeout: None,
+            cancel: None,
         }
     }
 }
@@ -5,8 +5,10 @@ use serde_json::Value;
 use std::{
     collections::HashMap,
     hash::{BuildHasher, Hash},
+    sync::Arc,
 };
-use storm::FieldsOrStr;
+use storm::{FieldsOrStr, Translations};
+use tiberius::xml::XmlData;
 
 /// Create the field from the diff value.
 pub trait FromFieldDiff: Sized {
@@ -80,6 +82,30 @@ impl<T: PartialEq + Serialize> FieldDiff for Option<T> {
     }
 }
 
+impl<C> ApplyFieldDiff for Translations<C>
+where
+    C: for<'de> Deserialize<'de> + PartialEq,
+{
+    fn apply_field_diff(&mut self, value: Value) -> Result<()> {
+        apply_field_diff_impl(self, value)
+    }
+}
+
+impl<C: Ord + Serialize> FieldDiff for Translations<C> {
+    fn field_diff(&self, old: &Self) -> Option<Value> {
+        field_diff_impl(self, old)
+    }
+}
+
+impl<C> FromFieldDiff for Translations<C>
+where
+    C: for<'de> Deserialize<'de> + PartialEq,
+{
+    fn from_field_diff(value: Value) -> Result<Self> {
+        from_field_diff_impl(value)
+    }
+}
+
 pub fn from_field_diff_impl<T: for<'de> Deserialize<'de>>(value: Value) -> Result<T> {
     serde_json::from_value(value).map_err(Error::std)
 }
@@ -106,8 +132,10 @@ macro_rules! diff {
     };
 }
 
+diff!(Box<[u8]>);
 diff!(Box<str>);
 diff!(String);
+diff!(Vec<u8>);
 diff!(bool);
 diff!(chrono::DateTime<FixedOffset>);
 diff!(chrono::DateTime<Local>);
@@ -129,3 +157,40 @@ diff!(uuid::Uuid);
 
 #[cfg(feature = "dec19x5")]
 diff!(dec19x5::Decimal);
+
+#[cfg(feature = "rust_decimal")]
+diff!(rust_decimal::Decimal);
+
+/// Implements the diff of a type through its text, for the types without serde support.
+macro_rules! diff_str {
+    ($n:ty, $from:expr) => {
+        impl ApplyFieldDiff for $n {
+            fn apply_field_diff(&mut self, value: Value) -> Result<()> {
+                *self = FromFieldDiff::from_field_diff(value)?;
+                Ok(())
+            }
+        }
+
+        impl FieldDiff for $n {
+            fn field_diff(&self, old: &Self) -> Option<Value> {
+                let old: &str = old.as_ref();
+
+                if <$n as AsRef<str>>::as_ref(self) == old {
+                    None
+                } else {
+                    Some(Value::String(old.to_owned()))
+                }
+            }
+        }
+
+        impl FromFieldDiff for $n {
+            fn from_field_diff(value: Value) -> Result<Self> {
+                let v: String = from_field_diff_impl(value)?;
+                Ok($from(v))
+            }
+        }
+    };
+}
+
+diff_str!(Arc<str>, Arc::from);
+diff_str!(XmlData, XmlData::new);
@@ -0,0 +1,79 @@
+use crate::{ColumnSchema, UpsertBuilder};
+use std::borrow::Cow;
+use storm::Result;
+use tiberius::Row;
+
+/// A value object embedded in entities with `#[storm(flatten, prefix = "...")]`, its fields
+/// are stored in the columns of the entity, named after the columns of the struct with the
+/// prefix. Usually implemented with `#[derive(MssqlFlatten)]`.
+///
+/// ```ignore
+/// #[derive(MssqlFlatten)]
+/// #[storm(rename_all = "PascalCase")]
+/// struct Address {
+///     city: String,
+///     street: String,
+/// }
+///
+/// #[derive(Ctx, MssqlLoad, MssqlSave)]
+/// #[storm(table = "Customers", keys = "Id", rename_all = "PascalCase")]
+/// struct Customer {
+///     name: String,
+///
+///     // the columns [BillingCity] and [BillingStreet].
+///     #[storm(flatten, prefix = "Billing")]
+///     billing: Address,
+/// }
+/// ```
+pub trait Flatten: Sized {
+    /// The columns of the fields, without the prefix.
+    const COLUMNS: &'static [&'static str];
+
+    /// Reads the fields from the columns of `row` starting at `index`, in the order of
+    /// [Flatten::COLUMNS].
+    fn load_flatten(row: &Row, index: usize) -> Result<Self>;
+
+    fn save_flatten<'a>(&'a self, prefix: &str, builder: &mut UpsertBuilder<'a>);
+
+    /// The prefixed columns in the schema of the entity.
+    fn flatten_schema(prefix: &str) -> Vec<ColumnSchema>;
+}
+
+/// Internal used for macros, the select of a load with the prefixed columns of the flattened
+/// fields added before the `FROM`. The derive computes it once per statement.
+#[doc(hidden)]
+pub fn _flatten_sql(select: &str, from: &str, fields: &[(&str, &[&str])]) -> String {
+    let mut sql = select.to_string();
+
+    for (prefix, cols) in fields {
+        for col in *cols {
+            sql.push_str(",t.[");
+            sql.push_str(prefix);
+            sql.push_str(col);
+            sql.push(']');
+        }
+    }
+
+    sql.push_str(from);
+    sql
+}
+
+/// Internal used for macros, the name of a prefixed column in the schema.
+#[doc(hidden)]
+pub fn _flatten_column(prefix: &str, column: &str) -> Cow<'static, str> {
+    Cow::Owned(format!("{prefix}{column}"))
+}
+
+#[test]
+fn flatten_sql() {
+    assert_eq!(
+        _flatten_sql(
+            "SELECT t.[Name],t.[Id]",
+            " FROM Customers t WHERE t.[Id]>0",
+            &[("Billing", &["City", "Street"]), ("", &["Zip"])]
+        ),
+        "SELECT t.[Name],t.[Id],t.[BillingCity],t.[BillingStreet],t.[Zip] FROM Customers t WHERE t.[Id]>0"
+    );
+
+    assert_eq!(_flatten_column("Billing", "City"), "BillingCity");
+}
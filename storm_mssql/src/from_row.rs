@@ -0,0 +1,51 @@
+use crate::FromSql;
+use storm::{Error, ErrorFrame, Operation, Result};
+use tiberius::Row;
+
+/// Maps a row of a query by the names of its columns, regardless of their case, usually
+/// implemented with `#[derive(FromRow)]`.
+///
+/// ```ignore
+/// #[derive(FromRow)]
+/// #[storm(rename_all = "PascalCase")]
+/// struct Sales {
+///     region: String,
+///     #[storm(column = "Total")]
+///     amount: f64,
+/// }
+///
+/// let sales: Vec<Sales> = provider
+///     .query_rows("SELECT Region, SUM(Amount) Total FROM Sales GROUP BY Region".to_string(), &[], from_row, false)
+///     .await?;
+/// ```
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> Result<Self>;
+}
+
+/// The mapper of a [QueryRows](crate::QueryRows) query.
+pub fn from_row<T: FromRow>(row: Row) -> Result<T> {
+    T::from_row(&row)
+}
+
+/// Internal used for macros, the column is found regardless of its case. The errors of a
+/// mistyped column keep their source, with the column in their context.
+#[doc(hidden)]
+pub fn _macro_row_field<'a, T: FromSql<'a>>(
+    row: &'a Row,
+    entity: &'static str,
+    column: &str,
+) -> Result<T> {
+    let index = row
+        .columns()
+        .iter()
+        .position(|c| c.name().eq_ignore_ascii_case(column))
+        .ok_or_else(|| Error::ConvertFailed(format!("Column `{column}` not found.")))?;
+
+    row.try_get(index)
+        .map_err(Error::Mssql)
+        .and_then(FromSql::from_sql)
+        .map_err(|e| match e {
+            Error::ColumnNull => Error::ConvertFailed(format!("Column `{column}` is null.")),
+            e => e.context(ErrorFrame::new(entity, Operation::Load).column(column)),
+        })
+}
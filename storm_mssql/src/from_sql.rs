@@ -1,7 +1,7 @@
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use std::{borrow::Cow, sync::Arc};
 use storm::{Error, Result};
-use tiberius::Uuid;
+use tiberius::{xml::XmlData, Uuid};
 
 pub trait FromSql<'a>: Sized {
     type Column: tiberius::FromSql<'a>;
@@ -36,6 +36,23 @@ macro_rules! from_sql {
             }
         }
     };
+    (checked $s:ty, $col:ty) => {
+        impl<'a> FromSql<'a> for $s {
+            type Column = $col;
+
+            fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+                match col {
+                    Some(v) => <$s>::try_from(v).map_err(|_| {
+                        storm::Error::ConvertFailed(format!(
+                            "`{v}` is out of the range of `{}`.",
+                            stringify!($s)
+                        ))
+                    }),
+                    None => Err(storm::Error::ColumnNull),
+                }
+            }
+        }
+    };
 }
 
 from_sql!(&'a str, &'a str);
@@ -54,6 +71,33 @@ from_sql!(i64, i64);
 from_sql!(u8, u8);
 from_sql!(&'a [u8], &'a [u8]);
 
+from_sql!(checked i8, i16);
+from_sql!(checked u16, i32);
+from_sql!(checked u32, i64);
+from_sql!(checked u64, i64);
+
+impl<'a> FromSql<'a> for DateTime<Local> {
+    type Column = DateTime<FixedOffset>;
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(v) => Ok(v.with_timezone(&Local)),
+            None => Err(Error::ColumnNull),
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for XmlData {
+    type Column = &'a XmlData;
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(v) => Ok(v.clone()),
+            None => Err(Error::ColumnNull),
+        }
+    }
+}
+
 #[cfg(feature = "dec19x5")]
 impl FromSql<'_> for dec19x5::Decimal {
     type Column = dec19x5::Decimal;
@@ -66,6 +110,18 @@ impl FromSql<'_> for dec19x5::Decimal {
     }
 }
 
+#[cfg(feature = "rust_decimal")]
+impl FromSql<'_> for rust_decimal::Decimal {
+    type Column = rust_decimal::Decimal;
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(v) => Ok(v),
+            None => Err(storm::Error::ColumnNull),
+        }
+    }
+}
+
 #[cfg(feature = "str_utils")]
 impl<'a, F: Default + str_utils::form_str::Format> FromSql<'a> for str_utils::form_str::FormStr<F> {
     type Column = &'a str;
@@ -147,6 +203,28 @@ impl<'a> FromSql<'a> for Box<str> {
     }
 }
 
+impl<'a> FromSql<'a> for Arc<[u8]> {
+    type Column = &'a [u8];
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(col) => Ok(Arc::from(col)),
+            None => Err(Error::ColumnNull),
+        }
+    }
+}
+
+impl<'a> FromSql<'a> for Arc<str> {
+    type Column = &'a str;
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(col) => Ok(Arc::from(col)),
+            None => Err(Error::ColumnNull),
+        }
+    }
+}
+
 impl<'a> FromSql<'a> for Cow<'_, [u8]> {
     type Column = &'a [u8];
 
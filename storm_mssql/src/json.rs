@@ -0,0 +1,198 @@
+use crate::{
+    field_diff::{apply_field_diff_impl, field_diff_impl, from_field_diff_impl},
+    ApplyFieldDiff, FieldDiff, FromFieldDiff, FromSql, SqlType, ToSql, ToSqlNull,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    ops::{Deref, DerefMut},
+};
+use storm::{Error, Gc, Len, Result};
+use tiberius::ColumnData;
+use tracing::error;
+
+/// A structured field stored as JSON in an `NVARCHAR` column.
+///
+/// ```ignore
+/// #[derive(Ctx, MssqlLoad, MssqlSave)]
+/// #[storm(table = "dbo.Users", keys = "Id")]
+/// struct User {
+///     #[storm(max_length = 4000)]
+///     settings: Json<Settings>,
+/// }
+/// ```
+///
+/// [Len] is the number of chars of the JSON text, a `max_length` limits the stored text.
+/// Saving an entity fails with the error of a value that cannot be serialized.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(transparent)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Serialize> Json<T> {
+    /// The JSON text of the value.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(&self.0).map_err(Error::std)
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(v: T) -> Self {
+        Self(v)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Json<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    type Column = &'a str;
+
+    fn from_sql(col: Option<Self::Column>) -> Result<Self> {
+        match col {
+            Some(v) => serde_json::from_str(v)
+                .map(Self)
+                .map_err(|e| Error::ConvertFailed(format!("Invalid json: {e}"))),
+            None => Err(Error::ColumnNull),
+        }
+    }
+}
+
+impl<T> Gc for Json<T> {}
+
+impl<T: Serialize> Len for Json<T> {
+    fn len(&self) -> usize {
+        self.to_json().map_or(0, |s| Len::len(&s))
+    }
+}
+
+impl<T> SqlType for Json<T> {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        String::sql_type(max_length)
+    }
+}
+
+impl<T: Serialize + Send + Sync> ToSql for Json<T> {
+    fn to_sql(&self) -> ColumnData<'_> {
+        match self.try_to_sql() {
+            Ok(v) => v,
+            Err(e) => {
+                // only outside of a save, which fails instead, the column is set to null.
+                error!(error = %e, "json serialization failed");
+                ColumnData::String(None)
+            }
+        }
+    }
+
+    fn try_to_sql(&self) -> Result<ColumnData<'_>> {
+        Ok(ColumnData::String(Some(Cow::Owned(self.to_json()?))))
+    }
+}
+
+impl<T> ToSqlNull for Json<T> {
+    fn to_sql_null() -> ColumnData<'static> {
+        ColumnData::String(None)
+    }
+}
+
+impl<T> ApplyFieldDiff for Json<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    fn apply_field_diff(&mut self, value: Value) -> Result<()> {
+        apply_field_diff_impl(self, value)
+    }
+}
+
+impl<T: PartialEq + Serialize> FieldDiff for Json<T> {
+    fn field_diff(&self, old: &Self) -> Option<Value> {
+        field_diff_impl(self, old)
+    }
+}
+
+impl<T> FromFieldDiff for Json<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    fn from_field_diff(value: Value) -> Result<Self> {
+        from_field_diff_impl(value)
+    }
+}
+
+#[test]
+fn json_round_trip() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Settings {
+        dark: bool,
+        size: i32,
+    }
+
+    let v = Json(Settings {
+        dark: true,
+        size: 3,
+    });
+
+    assert_eq!(
+        v.to_json().ok().as_deref(),
+        Some(r#"{"dark":true,"size":3}"#)
+    );
+    assert_eq!(Len::len(&v), 22);
+
+    let r = Json::<Settings>::from_sql(Some(r#"{"dark":true,"size":3}"#)).ok();
+    assert_eq!(r, Some(v));
+
+    assert!(matches!(
+        Json::<Settings>::from_sql(Some("{")),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(
+        Json::<Settings>::from_sql(None),
+        Err(Error::ColumnNull)
+    ));
+
+    let old = Json(Settings {
+        dark: false,
+        size: 3,
+    });
+
+    let diff = FieldDiff::field_diff(
+        &Json(Settings {
+            dark: true,
+            size: 3,
+        }),
+        &old,
+    );
+    assert_eq!(diff, Some(serde_json::json!({ "dark": false, "size": 3 })));
+}
+
+#[test]
+fn json_save_error() {
+    // the keys of a json object must be strings.
+    let v = Json(std::collections::HashMap::from([((1, 2), 3)]));
+
+    assert!(v.try_to_sql().is_err());
+    assert!(matches!(v.to_sql(), ColumnData::String(None)));
+    assert!(crate::Parameter::try_from_ref(&Some(v)).is_err());
+}
@@ -1,43 +1,73 @@
 mod client_factory;
+mod client_pool;
 mod entity_diff;
 mod execute;
 mod field_diff;
 mod filter_sql;
+mod flatten;
+mod from_row;
 mod from_sql;
+mod json;
 #[doc(hidden)]
 pub mod metrics_helper;
+mod migrations;
 mod mssql_factory;
 mod mssql_meta;
 mod mssql_provider;
 mod parameter;
+mod procedure;
 mod query_rows;
+mod retry_policy;
 mod save_entity_part;
+mod schema;
+mod temporal;
+mod tenant;
 mod to_sql;
 mod transaction_scoped;
 mod upsert_builder;
+mod verify_schema;
 
 use std::pin::Pin;
 
 pub use client_factory::ClientFactory;
+pub use client_pool::PoolConfig;
 pub use entity_diff::*;
 pub use execute::*;
 pub use field_diff::*;
 pub use filter_sql::*;
+pub use flatten::{Flatten, _flatten_column, _flatten_sql};
+pub use from_row::{from_row, FromRow, _macro_row_field};
 pub use from_sql::{FromSql, _macro_load_field};
-pub use mssql_factory::MssqlFactory;
+pub use json::Json;
+pub use migrations::{AppliedMigration, Migration, Migrator, DEFAULT_HISTORY_TABLE};
+pub use mssql_factory::{ConfiguredMssqlFactory, MssqlFactory};
 pub use mssql_meta::MssqlMeta;
 pub use mssql_provider::{MssqlProvider, MssqlTransactionGuard};
 pub use parameter::{into_column_data_static, Parameter};
-pub use query_rows::QueryRows;
+pub use procedure::{Procedure, ProcedureResult};
+pub use query_rows::{QueryRows, QueryRowsStream};
+pub use retry_policy::{RetryPolicy, DEFAULT_TRANSIENT_ERRORS};
 pub use save_entity_part::SaveEntityPart;
+pub use schema::{
+    create_table_sql, ColumnSchema, MssqlSchema, ProbeKeySqlTypes, ProbeSqlType,
+    ProbeSqlTypeColumn, ProbeSqlTypeUnknown, SqlType, SqlTypeProbe, TableSchema, TranslatedSchema,
+};
+#[doc(hidden)]
+pub use serde;
 pub use serde_json;
 use std::future::Future;
 use storm::ProviderContainer;
 pub use storm::{Error, Result};
+pub use temporal::{EntityVersion, LoadAllAsOf, LoadHistory, LoadOneAsOf};
+pub use tenant::{check_tenant, check_tenant_row, tenant_filter, tenant_param, MssqlTenant};
 pub use tiberius;
 pub use to_sql::{ToSql, ToSqlNull};
 pub use transaction_scoped::TransactionScoped;
 pub use upsert_builder::UpsertBuilder;
+pub use verify_schema::{
+    register_schema, registered_schemas, verify_schema, verify_table_schema, SchemaIssue,
+    SchemaReport, TableReport,
+};
 
 pub type Client = tiberius::Client<tokio_util::compat::Compat<tokio::net::TcpStream>>;
 
@@ -1,56 +1,127 @@
-use std::{fmt::Display, future::Future, pin::Pin};
+use futures::{stream, StreamExt};
+use std::{future::Future, pin::Pin, task::Poll};
+use storm::{BoxStream, Error, ErrorFrame, Operation};
 
 #[doc(hidden)]
-pub fn delete_wrap<'a, F, T, E>(
+pub fn delete_wrap<'a, F, T>(
     f: F,
     table: &'static str,
+    entity: &'static str,
+    provider: &'static str,
 ) -> Pin<Box<dyn Future<Output = F::Output> + Send + 'a>>
 where
-    F: Future<Output = Result<T, E>> + Send + 'a,
-    E: Display,
+    F: Future<Output = Result<T, Error>> + Send + 'a,
 {
-    op_wrap(f, table, "delete")
+    op_wrap(f, table, Operation::Delete, entity, provider)
 }
 
 #[doc(hidden)]
-pub fn load_wrap<'a, F, T, E>(
+pub fn load_wrap<'a, F, T>(
     f: F,
     table: &'static str,
+    entity: &'static str,
+    provider: &'static str,
 ) -> Pin<Box<dyn Future<Output = F::Output> + Send + 'a>>
 where
-    F: Future<Output = Result<T, E>> + Send + 'a,
-    E: Display,
+    F: Future<Output = Result<T, Error>> + Send + 'a,
 {
-    op_wrap(f, table, "load")
+    op_wrap(f, table, Operation::Load, entity, provider)
+}
+
+/// Opens the stream of a load, the errors of the rows are reported with the entity and the
+/// load is measured until the stream ends or is dropped.
+#[doc(hidden)]
+pub fn load_stream_wrap<'a, F, T>(
+    f: F,
+    #[allow(unused_variables)] table: &'static str,
+    entity: &'static str,
+    provider: &'static str,
+) -> BoxStream<'a, Result<T, Error>>
+where
+    F: Future<Output = Result<BoxStream<'a, Result<T, Error>>, Error>> + Send + 'a,
+    T: Send + 'a,
+{
+    #[cfg(feature = "telemetry")]
+    let mut metrics = StreamMetrics {
+        error: None,
+        instant: std::time::Instant::now(),
+        table,
+    };
+
+    let mut rows = stream::once(f)
+        .flat_map(|r| match r {
+            Ok(s) => s,
+            Err(e) => stream::once(async { Err(e) }).boxed(),
+        })
+        .boxed();
+
+    stream::poll_fn(move |cx| {
+        let row = rows.poll_next_unpin(cx);
+
+        match row {
+            Poll::Ready(Some(Err(e))) => {
+                let e = e.context(ErrorFrame::new(entity, Operation::Load).provider(provider));
+
+                #[cfg(feature = "telemetry")]
+                metrics.error.get_or_insert_with(|| e.to_string());
+
+                Poll::Ready(Some(Err(e)))
+            }
+            row => row,
+        }
+    })
+    .boxed()
+}
+
+/// Counts a streamed load when dropped, with the first error of the stream.
+#[cfg(feature = "telemetry")]
+struct StreamMetrics {
+    error: Option<String>,
+    instant: std::time::Instant,
+    table: &'static str,
+}
+
+#[cfg(feature = "telemetry")]
+impl Drop for StreamMetrics {
+    fn drop(&mut self) {
+        counter_impl(
+            self.table,
+            Operation::Load.as_str(),
+            self.instant,
+            self.error.take(),
+        );
+    }
 }
 
 #[allow(clippy::redundant_async_block)]
 #[doc(hidden)]
-fn op_wrap<'a, F, T, E>(
+fn op_wrap<'a, F, T>(
     f: F,
     #[allow(unused_variables)] table: &'static str,
-    #[allow(unused_variables)] op: &'static str,
+    op: Operation,
+    entity: &'static str,
+    provider: &'static str,
 ) -> Pin<Box<dyn Future<Output = F::Output> + Send + 'a>>
 where
-    F: Future<Output = Result<T, E>> + Send + 'a,
-    E: Display,
+    F: Future<Output = Result<T, Error>> + Send + 'a,
 {
     Box::pin(async move {
         #[cfg(feature = "telemetry")]
-        {
-            let d = std::time::Instant::now();
-            let r = f.await;
-            let e = r.as_ref().err().map(|e| e.to_string());
+        let d = std::time::Instant::now();
 
-            counter_impl(table, op, d, e);
+        let r = f
+            .await
+            .map_err(|e| e.context(ErrorFrame::new(entity, op).provider(provider)));
 
-            r
-        }
+        #[cfg(feature = "telemetry")]
+        counter_impl(
+            table,
+            op.as_str(),
+            d,
+            r.as_ref().err().map(|e| e.to_string()),
+        );
 
-        #[cfg(not(feature = "telemetry"))]
-        {
-            f.await
-        }
+        r
     })
 }
 
@@ -81,13 +152,14 @@ fn counter_impl(
 }
 
 #[doc(hidden)]
-pub fn upsert_wrap<'a, F, T, E>(
+pub fn upsert_wrap<'a, F, T>(
     f: F,
     table: &'static str,
+    entity: &'static str,
+    provider: &'static str,
 ) -> Pin<Box<dyn Future<Output = F::Output> + Send + 'a>>
 where
-    F: Future<Output = Result<T, E>> + Send + 'a,
-    E: Display,
+    F: Future<Output = Result<T, Error>> + Send + 'a,
 {
-    op_wrap(f, table, "upsert")
+    op_wrap(f, table, Operation::Upsert, entity, provider)
 }
@@ -0,0 +1,355 @@
+use crate::{schema::object_name, Execute, ExecuteArgs, MssqlProvider, QueryRows};
+use chrono::NaiveDateTime;
+use std::borrow::Cow;
+use storm::{provider::Provider, Error, ErrorFrame, Operation, Result};
+use tracing::{info, warn};
+
+/// The table recording the applied migrations, created on the first [run](Migrator::run).
+pub const DEFAULT_HISTORY_TABLE: &str = "dbo.__StormMigrations";
+
+/// A migration script, usually embedded from a directory with `storm::embed_migrations!`.
+#[derive(Clone, Copy, Debug)]
+pub struct Migration {
+    pub version: u64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+impl Migration {
+    /// A FNV-1a hash of the script, insensitive to the line endings.
+    pub fn checksum(&self) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+        for b in self.sql.bytes().filter(|b| *b != b'\r') {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+
+        format!("{hash:016x}")
+    }
+
+    /// Identifies the migration in the errors, the version being the key.
+    fn frame(&self) -> ErrorFrame {
+        ErrorFrame::new(self.name, Operation::Migrate).key(&self.version)
+    }
+
+    /// The batches of the script, separated by `GO` lines.
+    fn batches(&self) -> impl Iterator<Item = String> + '_ {
+        let mut batches = vec![String::new()];
+
+        for line in self.sql.lines() {
+            if line.trim().eq_ignore_ascii_case("GO") {
+                batches.push(String::new());
+            } else if let Some(batch) = batches.last_mut() {
+                batch.push_str(line);
+                batch.push('\n');
+            }
+        }
+
+        batches.into_iter().filter(|b| !b.trim().is_empty())
+    }
+}
+
+/// A migration recorded in the history table.
+#[derive(Clone, Debug)]
+pub struct AppliedMigration {
+    pub version: u64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_on: NaiveDateTime,
+}
+
+/// Applies the pending migrations in version order, each one in its own transaction.
+///
+/// Migrations must run before the provider is used by a `Ctx`, since they commit
+/// the transaction opened on the provider.
+///
+/// ```ignore
+/// Migrator::new(storm::embed_migrations!("migrations"))
+///     .run(provider)
+///     .await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    history_table: Cow<'static, str>,
+}
+
+impl Migrator {
+    pub fn new(migrations: &[Migration]) -> Self {
+        let mut migrations = migrations.to_vec();
+        migrations.sort_by_key(|m| m.version);
+
+        Self {
+            migrations,
+            history_table: Cow::Borrowed(DEFAULT_HISTORY_TABLE),
+        }
+    }
+
+    /// Changes the table recording the applied migrations.
+    pub fn history_table(mut self, table: impl Into<Cow<'static, str>>) -> Self {
+        self.history_table = table.into();
+        self
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// The migrations recorded in the history table, in version order. Empty when the history
+    /// table does not exist yet, it is not created.
+    pub async fn applied(&self, provider: &MssqlProvider) -> Result<Vec<AppliedMigration>> {
+        match self.history_table_exists(provider).await? {
+            true => self.load_applied(provider, false).await,
+            false => Ok(Vec::new()),
+        }
+    }
+
+    /// Lists the migrations that [run](Self::run) would apply, without applying them nor
+    /// changing the schema of the database.
+    ///
+    /// Fails if an applied migration has been changed since it was applied.
+    pub async fn pending(&self, provider: &MssqlProvider) -> Result<Vec<Migration>> {
+        self.check_versions()?;
+
+        let applied = self.applied(provider).await?;
+        self.check_applied(&applied)?;
+
+        Ok(self
+            .migrations
+            .iter()
+            .filter(|m| !applied.iter().any(|a| a.version == m.version))
+            .copied()
+            .collect())
+    }
+
+    /// Applies the pending migrations and returns them.
+    ///
+    /// Nothing is applied if an applied migration has been changed. A failing migration
+    /// is rolled back and stops the run, the previous ones stay applied.
+    pub async fn run(&self, provider: &MssqlProvider) -> Result<Vec<Migration>> {
+        self.create_history_table(provider).await?;
+
+        let pending = self.pending(provider).await?;
+
+        for migration in &pending {
+            if let Err(e) = self.apply(provider, migration).await {
+                let _ = provider.rollback().await;
+
+                return Err(e.context(migration.frame()));
+            }
+
+            info!(
+                version = migration.version,
+                name = migration.name,
+                "migration applied"
+            );
+        }
+
+        Ok(pending)
+    }
+
+    async fn apply(&self, provider: &MssqlProvider, migration: &Migration) -> Result<()> {
+        // serializes the migrations of the instances starting at the same time,
+        // the lock is released with the transaction.
+        provider
+            .execute_with_args(
+                "EXEC sp_getapplock @Resource = @P1, @LockMode = 'Exclusive', @LockOwner = 'Transaction';",
+                &[&&*self.history_table],
+                transaction(),
+            )
+            .await?;
+
+        let applied = self.load_applied(provider, true).await?;
+
+        if applied.iter().any(|a| a.version == migration.version) {
+            return provider.commit().await;
+        }
+
+        for batch in migration.batches() {
+            provider
+                .execute_with_args(batch, &[], transaction())
+                .await?;
+        }
+
+        let version = i64::try_from(migration.version).map_err(Error::std)?;
+        let checksum = migration.checksum();
+
+        provider
+            .execute_with_args(
+                format!(
+                    "INSERT INTO {} ([Version], [Name], [Checksum], [AppliedOn]) VALUES (@P1, @P2, @P3, SYSUTCDATETIME());",
+                    self.history_table
+                ),
+                &[&version, &migration.name, &checksum.as_str()],
+                transaction(),
+            )
+            .await?;
+
+        provider.commit().await
+    }
+
+    fn check_applied(&self, applied: &[AppliedMigration]) -> Result<()> {
+        for applied in applied {
+            match self
+                .migrations
+                .iter()
+                .find(|m| m.version == applied.version)
+            {
+                Some(m) if m.checksum() != applied.checksum => {
+                    return Err(
+                        Error::Str("Migration changed since it was applied.").context(m.frame())
+                    );
+                }
+                Some(_) => {}
+                None => warn!(
+                    version = applied.version,
+                    name = applied.name,
+                    "applied migration not found in the scripts"
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_versions(&self) -> Result<()> {
+        for w in self.migrations.windows(2) {
+            if let [a, b] = w {
+                if a.version == b.version {
+                    return Err(Error::Str("Migrations with the same version.")
+                        .context(a.frame())
+                        .context(b.frame()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn create_history_table(&self, provider: &MssqlProvider) -> Result<()> {
+        let sql = format!(
+            "IF OBJECT_ID(@P1) IS NULL CREATE TABLE {} ([Version] BIGINT NOT NULL PRIMARY KEY, [Name] NVARCHAR(255) NOT NULL, [Checksum] NVARCHAR(64) NOT NULL, [AppliedOn] DATETIME2 NOT NULL);",
+            self.history_table
+        );
+
+        let object = object_name(&self.history_table).1;
+
+        provider
+            .execute_with_args(sql, &[&&*object], no_transaction())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn history_table_exists(&self, provider: &MssqlProvider) -> Result<bool> {
+        let object = object_name(&self.history_table).1;
+
+        let exists: Vec<bool> = provider
+            .query_rows(
+                "SELECT CAST(CASE WHEN OBJECT_ID(@P1) IS NULL THEN 0 ELSE 1 END AS BIT);"
+                    .to_string(),
+                &[&&*object],
+                |row| Ok(row.get::<bool, _>(0).unwrap_or_default()),
+                false,
+            )
+            .await?;
+
+        Ok(exists.first().copied().unwrap_or_default())
+    }
+
+    async fn load_applied(
+        &self,
+        provider: &MssqlProvider,
+        use_transaction: bool,
+    ) -> Result<Vec<AppliedMigration>> {
+        let sql = format!(
+            "SELECT [Version], [Name], [Checksum], [AppliedOn] FROM {} ORDER BY [Version];",
+            self.history_table
+        );
+
+        provider
+            .query_rows(
+                sql,
+                &[],
+                |row| {
+                    let version = row.get::<i64, _>(0).unwrap_or_default();
+
+                    Ok(AppliedMigration {
+                        version: u64::try_from(version).map_err(Error::std)?,
+                        name: row.get::<&str, _>(1).unwrap_or_default().to_string(),
+                        checksum: row.get::<&str, _>(2).unwrap_or_default().to_string(),
+                        applied_on: row.get::<NaiveDateTime, _>(3).unwrap_or_default(),
+                    })
+                },
+                use_transaction,
+            )
+            .await
+    }
+}
+
+fn no_transaction() -> ExecuteArgs {
+    ExecuteArgs {
+        use_transaction: false,
+        ..Default::default()
+    }
+}
+
+fn transaction() -> ExecuteArgs {
+    ExecuteArgs {
+        use_transaction: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn migration_batches_and_checksum() {
+    let m = Migration {
+        version: 1,
+        name: "init",
+        sql: "CREATE TABLE A (Id INT);\r\ngo\r\nCREATE VIEW V AS SELECT Id FROM A;\r\nGO\r\n",
+    };
+
+    let batches = m.batches().collect::<Vec<_>>();
+
+    assert_eq!(
+        batches,
+        vec![
+            "CREATE TABLE A (Id INT);\n".to_string(),
+            "CREATE VIEW V AS SELECT Id FROM A;\n".to_string()
+        ]
+    );
+
+    let unix = Migration {
+        sql: "CREATE TABLE A (Id INT);\ngo\nCREATE VIEW V AS SELECT Id FROM A;\nGO\n",
+        ..m
+    };
+
+    assert_eq!(m.checksum(), unix.checksum());
+    assert_ne!(m.checksum(), Migration { sql: "", ..m }.checksum());
+}
+
+#[test]
+fn migration_errors_context() {
+    let init = Migration {
+        version: 1,
+        name: "init",
+        sql: "",
+    };
+
+    let e = Migrator::new(&[
+        init,
+        Migration {
+            name: "users",
+            ..init
+        },
+    ])
+    .check_versions()
+    .err()
+    .map(|e| e.to_string());
+
+    assert_eq!(
+        e.as_deref(),
+        Some("Migrations with the same version. [migrate init, key: 1] [migrate users, key: 1]")
+    );
+}
@@ -1,4 +1,4 @@
-use crate::MssqlProvider;
+use crate::{MssqlProvider, PoolConfig, RetryPolicy};
 use std::{env::var, ffi::OsStr};
 use storm::{provider::ProviderFactory, BoxFuture, Error, Result};
 use tiberius::Config;
@@ -14,6 +14,14 @@ impl MssqlFactory {
             &var(var_name).map_err(Error::std)?,
         )?))
     }
+
+    pub fn with_pool_config(self, pool_config: PoolConfig) -> ConfiguredMssqlFactory {
+        ConfiguredMssqlFactory::from(self).with_pool_config(pool_config)
+    }
+
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> ConfiguredMssqlFactory {
+        ConfiguredMssqlFactory::from(self).with_retry_policy(retry_policy)
+    }
 }
 
 impl ProviderFactory for MssqlFactory {
@@ -23,3 +31,52 @@ impl ProviderFactory for MssqlFactory {
         Box::pin(async move { Ok(MssqlProvider::new(self.0.clone())) })
     }
 }
+
+/// A `MssqlFactory` with a pool configuration or a retry policy other than the defaults.
+///
+/// ```ignore
+/// let factory = MssqlFactory(config)
+///     .with_retry_policy(RetryPolicy::none())
+///     .with_pool_config(PoolConfig::default());
+/// ```
+pub struct ConfiguredMssqlFactory {
+    pub config: Config,
+    pub pool_config: PoolConfig,
+    pub retry_policy: RetryPolicy,
+}
+
+impl ConfiguredMssqlFactory {
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+impl From<MssqlFactory> for ConfiguredMssqlFactory {
+    fn from(factory: MssqlFactory) -> Self {
+        Self {
+            config: factory.0,
+            pool_config: PoolConfig::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl ProviderFactory for ConfiguredMssqlFactory {
+    type Provider = MssqlProvider;
+
+    fn create_provider(&self) -> BoxFuture<'_, Result<Self::Provider>> {
+        Box::pin(async move {
+            Ok(MssqlProvider::with_config(
+                self.config.clone(),
+                self.retry_policy.clone(),
+                self.pool_config.clone(),
+            ))
+        })
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{execute::ExecuteArgs, Client, ClientFactory, Execute, Parameter, QueryRows, ToSql};
-use futures::{Stream, StreamExt, TryStreamExt};
+use crate::{
+    client_pool::{set_client_lock_timeout, ClientPool, PooledClient},
+    execute::ExecuteArgs,
+    Client, ClientFactory, Execute, Parameter, PoolConfig, QueryRows, QueryRowsStream, RetryPolicy,
+    ToSql,
+};
+use futures::{channel::mpsc, SinkExt, Stream, StreamExt, TryStreamExt};
 use std::{
     borrow::Cow,
     fmt::Debug,
@@ -12,7 +17,7 @@ use std::{
     task::{Context, Poll},
     time::Duration,
 };
-use storm::{provider, BoxFuture, Error, Result};
+use storm::{provider, BoxFuture, BoxStream, Error, Result};
 use tiberius::Row;
 use tokio::sync::{Mutex, MutexGuard};
 
@@ -22,14 +27,56 @@ pub struct MssqlProvider(Arc<Inner>);
 
 impl MssqlProvider {
     pub fn new<F: ClientFactory>(client_factory: F) -> Self {
-        (Box::new(client_factory) as Box<dyn ClientFactory>).into()
+        Self::with_retry_policy(client_factory, RetryPolicy::default())
+    }
+
+    pub fn with_retry_policy<F: ClientFactory>(
+        client_factory: F,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        Self::with_config(client_factory, retry_policy, PoolConfig::default())
+    }
+
+    pub fn with_config<F: ClientFactory>(
+        client_factory: F,
+        retry_policy: RetryPolicy,
+        pool_config: PoolConfig,
+    ) -> Self {
+        Self::from_parts(Box::new(client_factory), retry_policy, pool_config)
+    }
+
+    fn from_parts(
+        factory: Box<dyn ClientFactory>,
+        retry_policy: RetryPolicy,
+        pool_config: PoolConfig,
+    ) -> Self {
+        Self(Arc::new(Inner {
+            cancel_transaction: Default::default(),
+            pool: ClientPool::new(factory, pool_config),
+            retry_policy,
+            state: Mutex::new(State::default()),
+        }))
+    }
+
+    pub fn pool_config(&self) -> &PoolConfig {
+        self.0.pool.config()
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.0.retry_policy
+    }
+
+    /// Non-transactional operations use the pool, unless the client factory operates under
+    /// a transaction where all operations must share the same connection.
+    fn use_pool(&self, use_transaction: bool) -> bool {
+        !use_transaction && !self.0.pool.factory().under_transaction()
     }
 
     async fn state(&self) -> MutexGuard<'_, State> {
         let mut guard = self.0.state.lock().await;
 
         if self.0.cancel_transaction.swap(false, Relaxed) {
-            let _ = guard.cancel().await;
+            let _ = guard.cancel(&self.0.pool).await;
         }
 
         guard
@@ -39,7 +86,9 @@ impl MssqlProvider {
     /// # Safety
     /// This operation is safe but the returning client is not constrained by the lock and can modify the database without storm's knowledge.
     pub async unsafe fn create_client(&self) -> Result<Client> {
-        self.0.state.lock().await.create_client().await
+        let pool = &self.0.pool;
+        pool.create_client(&self.0.retry_policy, pool.lock_timeout())
+            .await
     }
 
     /// Creates a new [Client](Client) instance.
@@ -59,6 +108,7 @@ impl MssqlProvider {
         params: &'b [&'b (dyn ToSql)],
         mut mapper: M,
         use_transaction: bool,
+        retryable: &mut bool,
     ) -> Result<C>
     where
         C: Default + Extend<R> + Send,
@@ -66,7 +116,7 @@ impl MssqlProvider {
         R: Send,
         'a: 'b,
     {
-        let mut conn = QueryConn::new(self, use_transaction).await?;
+        let mut conn = QueryConn::new(self, use_transaction, retryable).await?;
         let mut query = conn.query(sql, params).await?;
         let mut vec = Vec::with_capacity(10);
         let mut coll = C::default();
@@ -90,8 +140,133 @@ impl MssqlProvider {
         Ok(coll)
     }
 
+    async fn query_rows_stream_imp<M, R>(
+        &self,
+        sql: &str,
+        params: &[&(dyn ToSql)],
+        mapper: &mut M,
+        use_transaction: bool,
+        retryable: &mut bool,
+        rows: &mut mpsc::Sender<R>,
+    ) -> Result<()>
+    where
+        M: FnMut(Row) -> Result<R> + Send,
+        R: Send,
+    {
+        let mut conn = QueryConn::new(self, use_transaction, retryable).await?;
+        let mut query = conn.query(sql, params).await?;
+
+        while let Some(row) = query.try_next().await? {
+            let row = mapper(row)?;
+
+            // the rows already handed out would be repeated by a retry.
+            *retryable = false;
+
+            if rows.send(row).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        query.complete().await?;
+        conn.complete();
+
+        Ok(())
+    }
+
+    async fn execute_imp(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql)],
+        use_transaction: bool,
+        retryable: &mut bool,
+    ) -> Result<u64> {
+        let mut intermediate = Vec::new();
+        let mut output = Vec::new();
+
+        adapt_params(params, &mut intermediate, &mut output);
+
+        let mut conn = QueryConn::new(self, use_transaction, retryable).await?;
+
+        // the statement may have been applied before the error is reported, only the failures
+        // to get a connection are retried.
+        *retryable = false;
+
+        let count = conn.client()?.execute(statement, &output).await?.total();
+
+        conn.complete();
+        Ok(count)
+    }
+
+    /// Rolls back the opened transaction, if any.
+    pub(crate) async fn rollback(&self) -> Result<()> {
+        self.state().await.cancel(&self.0.pool).await
+    }
+
+    /// Executes a batch and returns the rows of each of its result sets, used by the
+    /// [Procedure](crate::Procedure) calls. Like the statements, the batch is not retried once
+    /// sent.
+    pub async fn query_result_sets(
+        &self,
+        statement: &str,
+        params: &[&(dyn ToSql)],
+        args: ExecuteArgs,
+    ) -> Result<Vec<Vec<Row>>> {
+        let query = async {
+            let mut attempt = 1;
+
+            loop {
+                let mut retryable = false;
+
+                match self
+                    .query_result_sets_imp(statement, params, args.use_transaction, &mut retryable)
+                    .await
+                {
+                    Err(e) if retryable => {
+                        match self
+                            .0
+                            .retry_policy
+                            .retry_delay("query_result_sets", attempt, &e)
+                        {
+                            Some(delay) => tokio::time::sleep(delay).await,
+                            None => return Err(e),
+                        }
+                    }
+                    r => return r,
+                }
+
+                attempt += 1;
+            }
+        };
+
+        storm::with_timeout(query, args.timeout, args.cancel.as_ref()).await
+    }
+
+    async fn query_result_sets_imp(
+        &self,
+        sql: &str,
+        params: &[&(dyn ToSql)],
+        use_transaction: bool,
+        retryable: &mut bool,
+    ) -> Result<Vec<Vec<Row>>> {
+        let mut conn = QueryConn::new(self, use_transaction, retryable).await?;
+
+        // a procedure may have applied its changes before the error is reported, only the
+        // failures to get a connection are retried.
+        *retryable = false;
+
+        let sets = conn.query(sql, params).await?.into_results().await?;
+
+        conn.complete();
+        Ok(sets)
+    }
+
     pub async fn set_client_lock_timeout(&self, timeout: Option<Duration>) -> Result<()> {
-        self.0.state.lock().await.set_lock_timeout(timeout).await
+        self.0
+            .state
+            .lock()
+            .await
+            .set_lock_timeout(&self.0.pool, timeout)
+            .await
     }
 }
 
@@ -105,43 +280,48 @@ impl Execute for MssqlProvider {
     where
         S: Debug + Into<Cow<'a, str>> + Send + 'a,
     {
-        Box::pin(async move {
-            let mut intermediate = Vec::new();
-            let mut output = Vec::new();
-
-            adapt_params(params, &mut intermediate, &mut output);
+        let statement = statement.into();
 
-            let mut client;
-            let client_ref;
-            let mut guard = self.state().await;
-
-            if args.use_transaction {
-                client = guard.transaction().await?;
-                client_ref = &mut guard.transaction;
-            } else {
-                client = guard.client().await?;
-                client_ref = &mut guard.client;
+        Box::pin(async move {
+            let execute = async {
+                let mut attempt = 1;
+
+                loop {
+                    let mut retryable = false;
+
+                    match self
+                        .execute_imp(&statement, params, args.use_transaction, &mut retryable)
+                        .await
+                    {
+                        Err(e) if retryable => {
+                            match self.0.retry_policy.retry_delay("execute", attempt, &e) {
+                                Some(delay) => tokio::time::sleep(delay).await,
+                                None => return Err(e),
+                            }
+                        }
+                        r => return r,
+                    }
+
+                    attempt += 1;
+                }
             };
 
-            let count = client.execute(statement, &output).await?.total();
-
-            *client_ref = Some(client);
-            Ok(count)
+            // the client is dropped, closing its connection, if the timeout fires.
+            storm::with_timeout(execute, args.timeout, args.cancel.as_ref()).await
         })
     }
 }
 
 struct Inner {
     cancel_transaction: AtomicBool,
+    pool: ClientPool,
+    retry_policy: RetryPolicy,
     state: Mutex<State>,
 }
 
 impl From<Box<dyn ClientFactory>> for MssqlProvider {
     fn from(factory: Box<dyn ClientFactory>) -> Self {
-        Self(Arc::new(Inner {
-            cancel_transaction: Default::default(),
-            state: Mutex::new(State::new(factory)),
-        }))
+        Self::from_parts(factory, RetryPolicy::default(), PoolConfig::default())
     }
 }
 
@@ -157,7 +337,11 @@ impl provider::Provider for MssqlProvider {
     }
 
     fn commit(&self) -> BoxFuture<'_, Result<()>> {
-        Box::pin(async move { self.state().await.commit().await })
+        Box::pin(async move { self.state().await.commit(&self.0.pool).await })
+    }
+
+    fn gc(&self) {
+        self.0.pool.gc();
     }
 }
 
@@ -178,49 +362,195 @@ impl QueryRows for MssqlProvider {
         let sql = statement.into();
 
         Box::pin(async move {
-            let mut count = 0;
+            let mut attempt = 1;
 
             loop {
-                let r = self
-                    .query_rows_imp(&sql, params, &mut mapper, use_transaction)
-                    .await;
+                let mut retryable = false;
+
+                match self
+                    .query_rows_imp(&sql, params, &mut mapper, use_transaction, &mut retryable)
+                    .await
+                {
+                    Err(e) if retryable => {
+                        match self.0.retry_policy.retry_delay("query_rows", attempt, &e) {
+                            Some(delay) => tokio::time::sleep(delay).await,
+                            None => return Err(e),
+                        }
+                    }
+                    r => return r,
+                }
 
-                if r.is_ok() || count > 5 {
-                    return r;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+impl QueryRowsStream for MssqlProvider {
+    fn query_rows_stream_with_args<'a, S, P, M, R>(
+        &'a self,
+        statement: S,
+        params: P,
+        mut mapper: M,
+        args: ExecuteArgs,
+    ) -> BoxStream<'a, Result<R>>
+    where
+        M: FnMut(Row) -> Result<R> + Send + 'a,
+        P: Into<Cow<'a, [&'a (dyn ToSql)]>>,
+        R: Send + 'a,
+        S: Debug + for<'b> Into<Cow<'b, str>> + Send + 'a,
+    {
+        let sql = statement.into();
+        let params = params.into();
+        let use_transaction = args.use_transaction;
+
+        // a single row is buffered, the query only progresses when the stream is polled.
+        let (mut tx, rx) = mpsc::channel(0);
+
+        let query = async move {
+            let mut attempt = 1;
+
+            loop {
+                let mut retryable = false;
+
+                match self
+                    .query_rows_stream_imp(
+                        &sql,
+                        &params,
+                        &mut mapper,
+                        use_transaction,
+                        &mut retryable,
+                        &mut tx,
+                    )
+                    .await
+                {
+                    Err(e) if retryable => {
+                        match self.0.retry_policy.retry_delay("query_rows", attempt, &e) {
+                            Some(delay) => tokio::time::sleep(delay).await,
+                            None => return Err(e),
+                        }
+                    }
+                    r => return r,
                 }
 
-                count += 1;
+                attempt += 1;
             }
+        };
+
+        // the producer reads all the rows, the deadline covers the stream until its end and
+        // the client is dropped, closing its connection, if the timeout fires.
+        let producer = Box::pin(async move {
+            storm::with_timeout(query, args.timeout, args.cancel.as_ref()).await
+        });
+
+        Box::pin(RowStream {
+            error: None,
+            producer: Some(producer),
+            rows: rx,
         })
     }
 }
 
-struct QueryConn<'a> {
-    client: Client,
-    guard: MutexGuard<'a, State>,
-    use_transaction: bool,
+/// The rows of [QueryRowsStream::query_rows_stream], the query is driven by the producer which
+/// hands the rows one at a time through the channel.
+struct RowStream<'a, R> {
+    /// The error of the producer, returned once the rows sent before it are consumed.
+    error: Option<Error>,
+    producer: Option<BoxFuture<'a, Result<()>>>,
+    rows: mpsc::Receiver<R>,
+}
+
+impl<R> Stream for RowStream<'_, R> {
+    type Item = Result<R>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.rows.poll_next_unpin(cx) {
+                Poll::Ready(Some(row)) => return Poll::Ready(Some(Ok(row))),
+                Poll::Ready(None) => return Poll::Ready(self.error.take().map(Err)),
+                Poll::Pending => {}
+            }
+
+            let Some(producer) = self.producer.as_mut() else {
+                return Poll::Ready(self.error.take().map(Err));
+            };
+
+            match producer.as_mut().poll(cx) {
+                Poll::Ready(r) => {
+                    // dropping the producer closes the channel, the buffered rows are returned
+                    // before its error and the end of the stream.
+                    self.producer = None;
+                    self.error = r.err();
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+enum QueryConn<'a> {
+    Pooled(PooledClient<'a>),
+    State {
+        client: Client,
+        guard: MutexGuard<'a, State>,
+        use_transaction: bool,
+    },
 }
 
 impl<'a> QueryConn<'a> {
-    async fn new(provider: &'a MssqlProvider, use_transaction: bool) -> Result<QueryConn<'a>> {
+    async fn new(
+        provider: &'a MssqlProvider,
+        use_transaction: bool,
+        retryable: &mut bool,
+    ) -> Result<QueryConn<'a>> {
+        let policy = &provider.0.retry_policy;
+
+        if provider.use_pool(use_transaction) {
+            *retryable = true;
+            return Ok(Self::Pooled(provider.0.pool.checkout(policy).await?));
+        }
+
         let mut guard = provider.state().await;
+        let pool = &provider.0.pool;
+
+        // the work already done in an open transaction would be lost by a retry.
+        *retryable = !guard.in_transaction(pool, use_transaction);
 
         let client = match use_transaction {
-            true => guard.transaction().await,
-            false => guard.client().await,
+            true => guard.transaction(pool, policy).await,
+            false => guard.client(pool, policy).await,
         }?;
 
-        Ok(Self {
+        guard.transaction_out = use_transaction;
+
+        Ok(Self::State {
             client,
             guard,
             use_transaction,
         })
     }
 
-    fn complete(mut self) {
-        match self.use_transaction {
-            true => self.guard.transaction = Some(self.client),
-            false => self.guard.client = Some(self.client),
+    fn client(&mut self) -> Result<&mut Client> {
+        match self {
+            Self::Pooled(pooled) => pooled.client(),
+            Self::State { client, .. } => Ok(client),
+        }
+    }
+
+    fn complete(self) {
+        match self {
+            Self::Pooled(pooled) => pooled.release(),
+            Self::State {
+                client,
+                mut guard,
+                use_transaction,
+            } => match use_transaction {
+                true => {
+                    guard.transaction = Some(client);
+                    guard.transaction_out = false;
+                }
+                false => guard.client = Some(client),
+            },
         }
     }
 
@@ -237,7 +567,7 @@ impl<'a> QueryConn<'a> {
 
         adapt_params(params, &mut intermediate, &mut output);
 
-        let stream = self.client.query(sql, &output[..]).await?;
+        let stream = self.client()?.query(sql, &output[..]).await?;
 
         Ok(QueryStream(stream))
     }
@@ -250,6 +580,10 @@ impl<'a> QueryStream<'a> {
         self.0.into_results().await?;
         Ok(())
     }
+
+    async fn into_results(self) -> Result<Vec<Vec<Row>>> {
+        Ok(self.0.into_results().await?)
+    }
 }
 
 impl Stream for QueryStream<'_> {
@@ -268,28 +602,36 @@ impl Stream for QueryStream<'_> {
     }
 }
 
+/// The exclusive clients of the provider. The `client` is only used when the client factory
+/// operates under a transaction, otherwise non-transactional operations use the pool.
+#[derive(Default)]
 struct State {
     client: Option<Client>,
-    factory: Box<dyn ClientFactory>,
-    lock_timeout: Option<Duration>,
     transaction: Option<Client>,
+
+    /// The transaction client is in use by an operation. It remains set if the operation
+    /// failed or was dropped on a timeout; the client and its connection are then closed
+    /// and the server rolls back the transaction.
+    transaction_out: bool,
 }
 
 impl State {
-    fn new(factory: Box<dyn ClientFactory>) -> Self {
-        Self {
-            client: None,
-            factory,
-            lock_timeout: Some(DEFAULT_LOCK_TIMEOUT),
-            transaction: None,
-        }
+    async fn cancel(&mut self, pool: &ClientPool) -> Result<()> {
+        self.cancel_or_commit(pool, "ROLLBACK").await
     }
 
-    async fn cancel(&mut self) -> Result<()> {
-        self.cancel_or_commit("ROLLBACK").await
-    }
+    async fn cancel_or_commit(&mut self, pool: &ClientPool, statement: &'static str) -> Result<()> {
+        if self.transaction_out {
+            self.transaction_out = false;
+
+            #[cfg(feature = "telemetry")]
+            {
+                metrics::gauge!("storm_mssql_transaction_count").decrement(1.0);
+            }
+
+            return Err(Error::TransactionError);
+        }
 
-    async fn cancel_or_commit(&mut self, statement: &'static str) -> Result<()> {
         if let Some(mut client) = self.transaction.take() {
             let r = client.simple_query(statement).await.map_err(Error::Mssql);
 
@@ -300,20 +642,24 @@ impl State {
 
             r?;
 
-            if self.client.is_none() {
-                self.client = Some(client);
+            if pool.factory().under_transaction() {
+                if self.client.is_none() {
+                    self.client = Some(client);
+                }
+            } else {
+                pool.release(client, pool.lock_timeout());
             }
         }
 
         Ok(())
     }
 
-    async fn client(&mut self) -> Result<Client> {
+    async fn client(&mut self, pool: &ClientPool, policy: &RetryPolicy) -> Result<Client> {
         match self.client.take() {
             Some(c) => Ok(c),
             None => {
-                if let Some(client) = self
-                    .factory
+                if let Some(client) = pool
+                    .factory()
                     .under_transaction()
                     .then(|| self.transaction.take())
                     .flatten()
@@ -321,25 +667,26 @@ impl State {
                     return Ok(client);
                 }
 
-                self.create_client().await
+                pool.take(policy).await
             }
         }
     }
 
-    async fn commit(&mut self) -> Result<()> {
-        self.cancel_or_commit("COMMIT").await
+    async fn commit(&mut self, pool: &ClientPool) -> Result<()> {
+        self.cancel_or_commit(pool, "COMMIT").await
     }
 
-    async fn create_client(&self) -> Result<Client> {
-        let mut client = self.factory.create_client().await?;
-
-        set_client_lock_timeout(&mut client, self.lock_timeout).await?;
-
-        Ok(client)
+    /// Indicate if the operation would run inside an already opened transaction.
+    fn in_transaction(&self, pool: &ClientPool, use_transaction: bool) -> bool {
+        pool.factory().under_transaction() || (use_transaction && self.transaction.is_some())
     }
 
-    async fn set_lock_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
-        if self.lock_timeout != timeout {
+    async fn set_lock_timeout(
+        &mut self,
+        pool: &ClientPool,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        if pool.lock_timeout() != timeout {
             let mut client = self.client.take();
 
             if let Some(client) = client.as_mut() {
@@ -351,16 +698,22 @@ impl State {
             }
 
             self.client = client;
+            pool.set_lock_timeout(timeout);
         }
 
         Ok(())
     }
 
-    async fn transaction(&mut self) -> Result<Client> {
+    async fn transaction(&mut self, pool: &ClientPool, policy: &RetryPolicy) -> Result<Client> {
+        // the transaction was lost with its connection, it cannot continue on a new one.
+        if self.transaction_out {
+            return Err(Error::TransactionError);
+        }
+
         match self.transaction.take() {
             Some(t) => Ok(t),
             None => {
-                let mut client = self.client().await?;
+                let mut client = self.client(pool, policy).await?;
 
                 let r = client
                     .simple_query("BEGIN TRAN")
@@ -380,17 +733,6 @@ impl State {
     }
 }
 
-async fn set_client_lock_timeout(client: &mut Client, timeout: Option<Duration>) -> Result<()> {
-    client
-        .simple_query(format!(
-            "SET LOCK_TIMEOUT {};",
-            timeout.map_or(-1, |d| d.as_millis() as i128)
-        ))
-        .await?;
-
-    Ok(())
-}
-
 fn adapt_params<'a>(
     input: &'a [&dyn ToSql],
     intermediate: &'a mut Vec<Parameter<'a>>,
@@ -417,3 +759,68 @@ impl DerefMut for MssqlTransactionGuard<'_> {
         self.0.transaction.as_mut().expect("Transaction")
     }
 }
+
+#[test]
+fn row_stream_backpressure() {
+    use std::sync::atomic::AtomicUsize;
+
+    let produced = Arc::new(AtomicUsize::new(0));
+    let (mut tx, rx) = mpsc::channel(0);
+    let counter = Arc::clone(&produced);
+
+    let producer: BoxFuture<'static, Result<()>> = Box::pin(async move {
+        for i in 0..3 {
+            counter.fetch_add(1, Relaxed);
+            tx.send(i).await.map_err(Error::std)?;
+        }
+
+        Err(Error::ConvertFailed("row 3".to_string()))
+    });
+
+    let mut stream = RowStream {
+        error: None,
+        producer: Some(producer),
+        rows: rx,
+    };
+
+    futures::executor::block_on(async {
+        assert!(matches!(stream.next().await, Some(Ok(0))));
+
+        // the producer waits for the consumer, only the next row is buffered.
+        assert!(produced.load(Relaxed) <= 2);
+
+        assert!(matches!(stream.next().await, Some(Ok(1))));
+        assert!(matches!(stream.next().await, Some(Ok(2))));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::ConvertFailed(_)))
+        ));
+        assert!(stream.next().await.is_none());
+    });
+}
+
+#[test]
+fn row_stream_error_after_buffered_row() {
+    let (mut tx, rx) = mpsc::channel(0);
+
+    // the row is buffered and the producer fails without waiting for the consumer.
+    let producer: BoxFuture<'static, Result<()>> = Box::pin(async move {
+        tx.send(0).await.map_err(Error::std)?;
+        Err(Error::ConvertFailed("row 1".to_string()))
+    });
+
+    let mut stream = RowStream {
+        error: None,
+        producer: Some(producer),
+        rows: rx,
+    };
+
+    futures::executor::block_on(async {
+        assert!(matches!(stream.next().await, Some(Ok(0))));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(Error::ConvertFailed(_)))
+        ));
+        assert!(stream.next().await.is_none());
+    });
+}
@@ -1,5 +1,6 @@
 use crate::ToSql;
 use std::borrow::{Borrow, Cow};
+use storm::Result;
 use tiberius::ColumnData;
 
 pub struct Parameter<'a>(pub(crate) ColumnData<'a>);
@@ -8,6 +9,10 @@ impl<'a> Parameter<'a> {
     pub fn from_ref<T: ToSql>(t: &'a T) -> Self {
         Self(t.to_sql())
     }
+
+    pub fn try_from_ref<T: ToSql + ?Sized>(t: &'a T) -> Result<Self> {
+        t.try_to_sql().map(Self)
+    }
 }
 
 impl Parameter<'static> {
@@ -41,6 +46,10 @@ impl Parameter<'static> {
             ColumnData::Xml(v) => ColumnData::Xml(cow(v)),
         })
     }
+
+    pub fn try_from_owned<T: ToSql>(t: T) -> Result<Self> {
+        Ok(Self(into_column_data_static(&t.try_to_sql()?)))
+    }
 }
 
 impl ToSql for Parameter<'_> {
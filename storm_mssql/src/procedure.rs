@@ -0,0 +1,263 @@
+use crate::{
+    from_row::_macro_row_field, ExecuteArgs, FromRow, FromSql, MssqlProvider, Parameter, SqlType,
+    ToSql,
+};
+use std::{borrow::Cow, fmt::Write};
+use storm::{Error, Result};
+use tiberius::{ColumnData, Row};
+
+/// A call to a stored procedure with input and output parameters.
+///
+/// ```ignore
+/// let r = Procedure::new("dbo.SaveUser")
+///     .input("Name", &name)
+///     .in_out("Id", id)
+///     .output::<String>("Code")
+///     .execute(provider)
+///     .await?;
+///
+/// let id: i32 = r.output("Id")?;
+/// let users: Vec<User> = r.rows(0)?;
+/// ```
+///
+/// The call is run in a batch declaring the output parameters, their values and the
+/// return value of the procedure are read from a last result set added to the ones
+/// returned by the procedure.
+pub struct Procedure<'a> {
+    /// The first parameter that failed to convert, the call is not sent.
+    error: Option<Error>,
+    name: Cow<'a, str>,
+    args: Vec<ProcArg>,
+    params: Vec<Parameter<'a>>,
+}
+
+struct ProcArg {
+    name: String,
+    output: Option<Cow<'static, str>>,
+    param: Option<usize>,
+}
+
+impl<'a> Procedure<'a> {
+    pub fn new(name: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            error: None,
+            name: name.into(),
+            args: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// An input and output parameter, initialized to `value`.
+    pub fn in_out<T: SqlType + ToSql>(mut self, name: &str, value: T) -> Self {
+        self.push_input(name, Parameter::try_from_owned(value), Some(T::sql_type(0)));
+        self
+    }
+
+    pub fn input<T: ToSql>(mut self, name: &str, value: &'a T) -> Self {
+        self.push_input(name, Parameter::try_from_ref(value), None);
+        self
+    }
+
+    pub fn input_dyn(mut self, name: &str, value: &'a dyn ToSql) -> Self {
+        self.push_input(name, Parameter::try_from_ref(value), None);
+        self
+    }
+
+    pub fn input_owned<T: ToSql>(mut self, name: &str, value: T) -> Self {
+        self.push_input(name, Parameter::try_from_owned(value), None);
+        self
+    }
+
+    /// An output parameter, declared with the sql type of `T`.
+    pub fn output<T: SqlType>(self, name: &str) -> Self {
+        self.output_typed(name, T::sql_type(0))
+    }
+
+    /// An output parameter declared with `sql_type`, like `NVARCHAR(50)`.
+    pub fn output_typed(mut self, name: &str, sql_type: impl Into<Cow<'static, str>>) -> Self {
+        self.push_arg(name, Some(sql_type.into()), None);
+        self
+    }
+
+    pub(crate) fn push_param(
+        &mut self,
+        name: &str,
+        param: Parameter<'a>,
+        output: Option<Cow<'static, str>>,
+    ) {
+        self.params.push(param);
+        self.push_arg(name, output, Some(self.params.len()));
+    }
+
+    fn push_input(
+        &mut self,
+        name: &str,
+        param: Result<Parameter<'a>>,
+        output: Option<Cow<'static, str>>,
+    ) {
+        let param = param.unwrap_or_else(|e| {
+            // keeps the indexes of the next parameters, the call is never sent.
+            self.error.get_or_insert(e);
+            Parameter(ColumnData::String(None))
+        });
+
+        self.push_param(name, param, output);
+    }
+
+    fn push_arg(&mut self, name: &str, output: Option<Cow<'static, str>>, param: Option<usize>) {
+        self.args.push(ProcArg {
+            name: name.trim_start_matches('@').to_string(),
+            output,
+            param,
+        });
+    }
+
+    /// Fails without calling the procedure if a parameter failed to convert.
+    pub async fn execute(self, provider: &MssqlProvider) -> Result<ProcedureResult> {
+        self.execute_with_args(provider, ExecuteArgs::default())
+            .await
+    }
+
+    pub async fn execute_with_args(
+        mut self,
+        provider: &MssqlProvider,
+        args: ExecuteArgs,
+    ) -> Result<ProcedureResult> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        let params = self
+            .params
+            .iter()
+            .map(|p| p as &dyn ToSql)
+            .collect::<Vec<_>>();
+
+        let mut sets = provider
+            .query_result_sets(&self.sql(), &params, args)
+            .await?;
+
+        let outputs = sets
+            .pop()
+            .and_then(|rows| rows.into_iter().next())
+            .ok_or_else(|| Error::ConvertFailed("Missing output parameters.".to_string()))?;
+
+        Ok(ProcedureResult {
+            outputs,
+            result_sets: sets,
+        })
+    }
+
+    pub fn sql(&self) -> String {
+        let mut declare = String::from("DECLARE @return INT;");
+        let mut exec = format!("EXEC @return = {}", self.name);
+        let mut select = String::from("SELECT @return AS [@return]");
+
+        for (index, arg) in self.args.iter().enumerate() {
+            let name = &arg.name;
+
+            exec.push_str(if index == 0 { " " } else { ", " });
+
+            match (&arg.output, arg.param) {
+                (Some(ty), param) => {
+                    let _ = write!(declare, " DECLARE @o{index} {ty}");
+
+                    if let Some(p) = param {
+                        let _ = write!(declare, " = @p{p}");
+                    }
+
+                    declare.push(';');
+
+                    let _ = write!(exec, "@{name} = @o{index} OUTPUT");
+                    let _ = write!(select, ", @o{index} AS [{name}]");
+                }
+                (None, Some(p)) => {
+                    let _ = write!(exec, "@{name} = @p{p}");
+                }
+                (None, None) => {}
+            }
+        }
+
+        format!("{declare} {exec}; {select};")
+    }
+}
+
+/// The result of a [Procedure] call.
+pub struct ProcedureResult {
+    outputs: Row,
+    result_sets: Vec<Vec<Row>>,
+}
+
+impl ProcedureResult {
+    /// The value of an output parameter.
+    pub fn output<'a, T: FromSql<'a>>(&'a self, name: &str) -> Result<T> {
+        _macro_row_field(&self.outputs, "Procedure", name.trim_start_matches('@'))
+    }
+
+    /// The number of result sets returned by the procedure.
+    pub fn result_sets(&self) -> usize {
+        self.result_sets.len()
+    }
+
+    pub fn return_value(&self) -> Result<i32> {
+        _macro_row_field(&self.outputs, "Procedure", "@return")
+    }
+
+    /// The rows of a result set, mapped by the names of the columns.
+    pub fn rows<T: FromRow>(&self, result_set: usize) -> Result<Vec<T>> {
+        self.result_set(result_set)?
+            .iter()
+            .map(T::from_row)
+            .collect()
+    }
+
+    /// The first column of the rows of a result set.
+    pub fn values<'a, T: FromSql<'a>>(&'a self, result_set: usize) -> Result<Vec<T>> {
+        self.result_set(result_set)?
+            .iter()
+            .map(|row| crate::_macro_load_field(row, 0))
+            .collect()
+    }
+
+    fn result_set(&self, index: usize) -> Result<&[Row]> {
+        self.result_sets
+            .get(index)
+            .map(Vec::as_slice)
+            .ok_or_else(|| Error::ConvertFailed(format!("Result set {index} not found.")))
+    }
+}
+
+#[test]
+fn procedure_sql() {
+    let name = "Bob";
+
+    let p = Procedure::new("dbo.SaveUser")
+        .input("Name", &name)
+        .in_out("@Id", 0i32)
+        .output::<String>("Code")
+        .output_typed("Total", "DECIMAL(19, 5)");
+
+    assert_eq!(
+        p.sql(),
+        "DECLARE @return INT; DECLARE @o1 INT = @p2; DECLARE @o2 NVARCHAR(MAX); DECLARE @o3 DECIMAL(19, 5); \
+        EXEC @return = dbo.SaveUser @Name = @p1, @Id = @o1 OUTPUT, @Code = @o2 OUTPUT, @Total = @o3 OUTPUT; \
+        SELECT @return AS [@return], @o1 AS [Id], @o2 AS [Code], @o3 AS [Total];"
+    );
+}
+
+#[test]
+fn procedure_param_error() {
+    // the keys of a json object must be strings.
+    let v = crate::Json(std::collections::HashMap::from([((1, 2), 3)]));
+
+    let p = Procedure::new("dbo.SaveUser")
+        .input("Value", &v)
+        .input_owned("Name", "Bob".to_string());
+
+    // the next parameters keep their index, the call is not sent.
+    assert!(p.error.is_some());
+    assert_eq!(
+        p.sql(),
+        "DECLARE @return INT; EXEC @return = dbo.SaveUser @Value = @p1, @Name = @p2; SELECT @return AS [@return];"
+    );
+}
@@ -1,6 +1,6 @@
-use crate::ToSql;
+use crate::{ExecuteArgs, ToSql};
 use std::{borrow::Cow, fmt::Debug};
-use storm::{BoxFuture, Result};
+use storm::{BoxFuture, BoxStream, Result};
 use tiberius::Row;
 
 pub trait QueryRows {
@@ -25,6 +25,53 @@ pub trait QueryRows {
         S: Debug + for<'b> Into<Cow<'b, str>> + Send + 'a;
 }
 
+pub trait QueryRowsStream {
+    /// Execute a query on the sql server and streams the mapped rows.
+    ///
+    /// The rows are read from the server as the stream is polled and the connection is held
+    /// until the stream is exhausted or dropped. Opening the query is retried like
+    /// [query_rows](QueryRows::query_rows), a failure after the first row ends the stream
+    /// with the error. The timeout and the cancellation of the args apply until the stream
+    /// is exhausted.
+    fn query_rows_stream_with_args<'a, S, P, M, R>(
+        &'a self,
+        statement: S,
+        params: P,
+        mapper: M,
+        args: ExecuteArgs,
+    ) -> BoxStream<'a, Result<R>>
+    where
+        M: FnMut(Row) -> Result<R> + Send + 'a,
+        P: Into<Cow<'a, [&'a (dyn ToSql)]>>,
+        R: Send + 'a,
+        S: Debug + for<'b> Into<Cow<'b, str>> + Send + 'a;
+
+    #[inline]
+    fn query_rows_stream<'a, S, P, M, R>(
+        &'a self,
+        statement: S,
+        params: P,
+        mapper: M,
+        use_transaction: bool,
+    ) -> BoxStream<'a, Result<R>>
+    where
+        M: FnMut(Row) -> Result<R> + Send + 'a,
+        P: Into<Cow<'a, [&'a (dyn ToSql)]>>,
+        R: Send + 'a,
+        S: Debug + for<'b> Into<Cow<'b, str>> + Send + 'a,
+    {
+        self.query_rows_stream_with_args(
+            statement,
+            params,
+            mapper,
+            ExecuteArgs {
+                use_transaction,
+                ..Default::default()
+            },
+        )
+    }
+}
+
 impl<P> QueryRows for &P
 where
     P: QueryRows + Send + Sync,
@@ -45,3 +92,24 @@ where
         (**self).query_rows(statement, params, mapper, use_transaction)
     }
 }
+
+impl<P> QueryRowsStream for &P
+where
+    P: QueryRowsStream + Send + Sync,
+{
+    fn query_rows_stream_with_args<'a, S, Q, M, R>(
+        &'a self,
+        statement: S,
+        params: Q,
+        mapper: M,
+        args: ExecuteArgs,
+    ) -> BoxStream<'a, Result<R>>
+    where
+        M: FnMut(Row) -> Result<R> + Send + 'a,
+        Q: Into<Cow<'a, [&'a (dyn ToSql)]>>,
+        R: Send + 'a,
+        S: Debug + for<'b> Into<Cow<'b, str>> + Send + 'a,
+    {
+        (**self).query_rows_stream_with_args(statement, params, mapper, args)
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    borrow::Cow,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+use storm::Error;
+use tracing::warn;
+
+/// SQL Server error numbers considered transient by default.
+///
+/// Deadlock victim (1205), dropped connections (20, 64, 233, 10053, 10054, 10060),
+/// database unavailable or failover in progress (4060, 4221, 40143, 40197, 40501,
+/// 40540, 40613) and resource limits (10928, 10929, 49918, 49919, 49920).
+pub const DEFAULT_TRANSIENT_ERRORS: &[u32] = &[
+    20, 64, 233, 1205, 4060, 4221, 10053, 10054, 10060, 10928, 10929, 40143, 40197, 40501, 40540,
+    40613, 49918, 49919, 49920,
+];
+
+/// Indicate how failing operations of the [MssqlProvider](crate::MssqlProvider) are retried.
+///
+/// Only transient errors are retried, with an exponential backoff between attempts.
+/// Operations running inside an open transaction are never retried, since the work
+/// already done in the transaction is lost with the connection. The statements that are not
+/// queries are only retried when no connection could be obtained, they may have been applied
+/// before the error is reported.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, doubled on each subsequent retry.
+    pub initial_backoff: Duration,
+
+    /// The upper bound of the delay between two attempts.
+    pub max_backoff: Duration,
+
+    /// Randomize the delay between half and the full backoff to avoid
+    /// all clients retrying at the same time.
+    pub jitter: bool,
+
+    /// The SQL Server error numbers considered transient.
+    pub transient_errors: Cow<'static, [u32]>,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// The delay to wait before the retry following the given attempt (1 based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            let half = backoff / 2;
+            let random = RandomState::new().build_hasher().finish();
+            let nanos = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+
+            half + Duration::from_nanos(random.checked_rem(nanos).unwrap_or_default())
+        } else {
+            backoff
+        }
+    }
+
+    /// Indicate if the error is worth retrying.
+    pub fn is_transient(&self, error: &Error) -> bool {
+        match error.root() {
+            Error::Mssql(tiberius::error::Error::Io { .. }) => true,
+            Error::Mssql(e) => e.code().is_some_and(|c| self.transient_errors.contains(&c)),
+            Error::Std(e) => e.downcast_ref::<std::io::Error>().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Returns the delay to wait before retrying the failed attempt (1 based) or `None`
+    /// if the error must be returned to the caller.
+    pub(crate) fn retry_delay(
+        &self,
+        op: &'static str,
+        attempt: u32,
+        error: &Error,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_transient(error) {
+            return None;
+        }
+
+        let delay = self.backoff(attempt);
+
+        warn!(op, attempt, ?delay, error = %error, "transient mssql error, retrying");
+
+        Some(delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            jitter: true,
+            transient_errors: Cow::Borrowed(DEFAULT_TRANSIENT_ERRORS),
+        }
+    }
+}
+
+#[test]
+fn backoff_is_exponential_and_bounded() {
+    let policy = RetryPolicy {
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+
+    assert_eq!(policy.backoff(1), Duration::from_millis(50));
+    assert_eq!(policy.backoff(2), Duration::from_millis(100));
+    assert_eq!(policy.backoff(3), Duration::from_millis(200));
+    assert_eq!(policy.backoff(10), Duration::from_secs(2));
+    assert_eq!(policy.backoff(100), Duration::from_secs(2));
+
+    let policy = RetryPolicy::default();
+    let backoff = policy.backoff(2);
+
+    assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(100));
+}
+
+#[test]
+fn transient_errors() {
+    let policy = RetryPolicy::default();
+
+    let io = Error::Mssql(tiberius::error::Error::Io {
+        kind: std::io::ErrorKind::ConnectionReset,
+        message: "reset".to_string(),
+    });
+
+    assert!(policy.is_transient(&io));
+    assert!(!policy.is_transient(&Error::EntityNotFound));
+    assert!(!policy.is_transient(&Error::Mssql(tiberius::error::Error::Conversion("".into()))));
+}
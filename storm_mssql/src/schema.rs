@@ -0,0 +1,400 @@
+use crate::FromSql;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use std::{borrow::Cow, fmt::Write, marker::PhantomData};
+use storm::{Error, Result};
+use tiberius::xml::XmlData;
+use uuid::Uuid;
+
+/// The SQL Server type of a column, used to generate the schema of an entity.
+///
+/// Implemented for the column types of [FromSql]. Types with a custom [FromSql] are
+/// mapped through their `Column` type; others can implement this trait directly
+/// or use `#[storm(sql_type = "...")]` on the field.
+pub trait SqlType {
+    /// The type declaration, `max_length` is 0 when no maximum length is specified.
+    fn sql_type(max_length: usize) -> Cow<'static, str>;
+}
+
+macro_rules! sql_type {
+    ($sql:literal, $($t:ty),+) => {
+        $(
+            impl SqlType for $t {
+                fn sql_type(_max_length: usize) -> Cow<'static, str> {
+                    Cow::Borrowed($sql)
+                }
+            }
+        )+
+    };
+}
+
+sql_type!("BIT", bool);
+sql_type!("TINYINT", u8);
+sql_type!("SMALLINT", i8, i16);
+sql_type!("INT", i32, u16);
+sql_type!("BIGINT", i64, u32, u64);
+sql_type!("REAL", f32);
+sql_type!("FLOAT", f64);
+sql_type!("UNIQUEIDENTIFIER", Uuid);
+sql_type!("DATE", NaiveDate);
+sql_type!("TIME", NaiveTime);
+sql_type!("DATETIME2", NaiveDateTime);
+sql_type!(
+    "DATETIMEOFFSET",
+    DateTime<Utc>,
+    DateTime<FixedOffset>,
+    DateTime<Local>
+);
+sql_type!("XML", XmlData);
+
+#[cfg(feature = "dec19x5")]
+sql_type!("DECIMAL(19, 5)", dec19x5::Decimal);
+
+#[cfg(feature = "rust_decimal")]
+sql_type!("DECIMAL(28, 10)", rust_decimal::Decimal);
+
+impl SqlType for &str {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        with_length("NVARCHAR", max_length)
+    }
+}
+
+impl SqlType for String {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        with_length("NVARCHAR", max_length)
+    }
+}
+
+impl SqlType for &[u8] {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        with_length("VARBINARY", max_length)
+    }
+}
+
+impl SqlType for Vec<u8> {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        with_length("VARBINARY", max_length)
+    }
+}
+
+impl<T: SqlType> SqlType for Option<T> {
+    fn sql_type(max_length: usize) -> Cow<'static, str> {
+        T::sql_type(max_length)
+    }
+}
+
+fn with_length(sql_type: &str, max_length: usize) -> Cow<'static, str> {
+    match max_length {
+        0 => Cow::Owned(format!("{sql_type}(MAX)")),
+        n => Cow::Owned(format!("{sql_type}({n})")),
+    }
+}
+
+/// The schema of an entity, generated by the `MssqlLoad` derive.
+pub trait MssqlSchema {
+    fn schema() -> TableSchema;
+}
+
+#[derive(Clone, Debug)]
+pub struct TableSchema {
+    /// The rust name of the entity.
+    pub entity: &'static str,
+
+    /// The name of the provider in the `ProviderContainer`.
+    pub provider: &'static str,
+    pub table: &'static str,
+
+    /// The key columns first, followed by the fields.
+    pub columns: Vec<ColumnSchema>,
+    pub keys: Vec<&'static str>,
+    pub identity: Option<&'static str>,
+    pub translated: Option<TranslatedSchema>,
+
+    /// The entity is loaded from a view, its keys and identity are not verified and it has no
+    /// `CREATE TABLE`.
+    pub view: bool,
+}
+
+/// The table holding the values of the translated fields, one row per culture.
+#[derive(Clone, Debug)]
+pub struct TranslatedSchema {
+    pub table: &'static str,
+
+    /// The key columns, referencing the keys of the main table, followed by the fields.
+    /// The `Culture` column is not included.
+    pub columns: Vec<ColumnSchema>,
+    pub keys: Vec<&'static str>,
+
+    /// The column identifying the culture of the values, its sql type inferred from the
+    /// `Culture` type.
+    pub culture: ColumnSchema,
+}
+
+#[derive(Clone, Debug)]
+pub struct ColumnSchema {
+    pub name: Cow<'static, str>,
+
+    /// `None` when the type could not be inferred from the rust type.
+    pub sql_type: Option<Cow<'static, str>>,
+    pub nullable: bool,
+
+    /// 0 when no maximum length is specified.
+    pub max_length: usize,
+}
+
+/// Creates the `CREATE TABLE` statements of the entity, followed by the one of the translated
+/// table if any.
+///
+/// Fails if the sql type of a column is unknown or if the entity is loaded from a view.
+pub fn create_table_sql(schema: &TableSchema) -> Result<String> {
+    if schema.view {
+        return Err(Error::String(format!(
+            "{}: `{}` is a view, no table is created for it.",
+            schema.entity, schema.table
+        )));
+    }
+
+    let mut sql = String::new();
+
+    create_table(
+        &mut sql,
+        schema.entity,
+        schema.table,
+        &schema.columns,
+        &schema.keys,
+        schema.identity,
+        None,
+    )?;
+
+    if let Some(t) = &schema.translated {
+        sql.push('\n');
+
+        create_table(
+            &mut sql,
+            schema.entity,
+            t.table,
+            &t.columns,
+            &t.keys,
+            None,
+            Some(&t.culture),
+        )?;
+    }
+
+    Ok(sql)
+}
+
+/// The database prefix of the system views and the name to give to `OBJECT_ID`,
+/// temp tables live in tempdb.
+pub(crate) fn object_name(table: &str) -> (&'static str, Cow<'_, str>) {
+    match table.starts_with('#') {
+        true => ("tempdb.", Cow::Owned(format!("tempdb..{table}"))),
+        false => ("", Cow::Borrowed(table)),
+    }
+}
+
+fn create_table(
+    sql: &mut String,
+    entity: &str,
+    table: &str,
+    columns: &[ColumnSchema],
+    keys: &[&str],
+    identity: Option<&str>,
+    culture: Option<&ColumnSchema>,
+) -> Result<()> {
+    let (key_columns, fields) = columns.split_at(keys.len().min(columns.len()));
+    let columns = key_columns.iter().chain(culture).chain(fields);
+
+    let _ = write!(sql, "CREATE TABLE {table} (");
+
+    for column in columns {
+        let Some(sql_type) = column.sql_type.as_deref() else {
+            return Err(Error::String(format!(
+                "{entity}: the sql type of column `{}` is unknown, implement `storm_mssql::SqlType` for its type or use `#[storm(sql_type = \"...\")]`.",
+                column.name
+            )));
+        };
+
+        let _ = write!(sql, "[{}] {sql_type}", column.name);
+
+        if identity.is_some_and(|i| i.eq_ignore_ascii_case(&column.name)) {
+            sql.push_str(" IDENTITY(1,1)");
+        }
+
+        sql.push_str(match column.nullable {
+            true => " NULL, ",
+            false => " NOT NULL, ",
+        });
+    }
+
+    sql.push_str("PRIMARY KEY (");
+
+    let culture_key = culture.map(|c| &*c.name);
+
+    for (index, key) in keys.iter().chain(&culture_key).enumerate() {
+        if index > 0 {
+            sql.push_str(", ");
+        }
+
+        let _ = write!(sql, "[{key}]");
+    }
+
+    sql.push_str("));");
+
+    Ok(())
+}
+
+#[doc(hidden)]
+pub struct SqlTypeProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> SqlTypeProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Used by the derive to find the sql type of a field, first from [SqlType],
+/// then from the `Column` of [FromSql], otherwise `None`.
+#[doc(hidden)]
+pub trait ProbeSqlType {
+    fn probe_sql_type(&self, max_length: usize) -> Option<Cow<'static, str>>;
+}
+
+impl<T: SqlType> ProbeSqlType for &&SqlTypeProbe<T> {
+    fn probe_sql_type(&self, max_length: usize) -> Option<Cow<'static, str>> {
+        Some(T::sql_type(max_length))
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeSqlTypeColumn {
+    fn probe_sql_type(&self, max_length: usize) -> Option<Cow<'static, str>>;
+}
+
+impl<T> ProbeSqlTypeColumn for &SqlTypeProbe<T>
+where
+    T: FromSql<'static>,
+    T::Column: SqlType,
+{
+    fn probe_sql_type(&self, max_length: usize) -> Option<Cow<'static, str>> {
+        Some(<T::Column as SqlType>::sql_type(max_length))
+    }
+}
+
+#[doc(hidden)]
+pub trait ProbeSqlTypeUnknown {
+    fn probe_sql_type(&self, _max_length: usize) -> Option<Cow<'static, str>> {
+        None
+    }
+
+    fn probe_key_sql_types(&self) -> Vec<Option<Cow<'static, str>>> {
+        Vec::new()
+    }
+}
+
+impl<T: ?Sized> ProbeSqlTypeUnknown for SqlTypeProbe<T> {}
+
+/// Used by the derive to find the sql types of a composite key.
+#[doc(hidden)]
+pub trait ProbeKeySqlTypes {
+    fn probe_key_sql_types(&self) -> Vec<Option<Cow<'static, str>>>;
+}
+
+macro_rules! key_sql_types {
+    ($($t:ident),+) => {
+        impl<$($t),+> ProbeKeySqlTypes for &SqlTypeProbe<($($t,)+)>
+        where
+            $($t: FromSql<'static>, $t::Column: SqlType,)+
+        {
+            fn probe_key_sql_types(&self) -> Vec<Option<Cow<'static, str>>> {
+                vec![$(Some(<$t::Column as SqlType>::sql_type(0))),+]
+            }
+        }
+    };
+}
+
+key_sql_types!(A, B);
+key_sql_types!(A, B, C);
+key_sql_types!(A, B, C, D);
+key_sql_types!(A, B, C, D, E);
+
+#[test]
+fn create_table_statements() {
+    let schema = TableSchema {
+        entity: "Label",
+        provider: "",
+        table: "dbo.Labels",
+        columns: vec![
+            ColumnSchema {
+                name: Cow::Borrowed("Id"),
+                sql_type: (&&SqlTypeProbe::<i32>::new()).probe_sql_type(0),
+                nullable: false,
+                max_length: 0,
+            },
+            ColumnSchema {
+                name: Cow::Borrowed("Code"),
+                sql_type: (&&SqlTypeProbe::<String>::new()).probe_sql_type(10),
+                nullable: false,
+                max_length: 10,
+            },
+            ColumnSchema {
+                name: Cow::Borrowed("Parent"),
+                sql_type: (&&SqlTypeProbe::<Option<Box<str>>>::new()).probe_sql_type(0),
+                nullable: true,
+                max_length: 0,
+            },
+        ],
+        keys: vec!["Id"],
+        identity: Some("id"),
+        translated: Some(TranslatedSchema {
+            table: "dbo.LabelsTranslatedValues",
+            columns: vec![
+                ColumnSchema {
+                    name: Cow::Borrowed("LabelId"),
+                    sql_type: Some(Cow::Borrowed("INT")),
+                    nullable: false,
+                    max_length: 0,
+                },
+                ColumnSchema {
+                    name: Cow::Borrowed("Name"),
+                    sql_type: Some(String::sql_type(50)),
+                    nullable: false,
+                    max_length: 50,
+                },
+            ],
+            keys: vec!["LabelId"],
+            culture: ColumnSchema {
+                name: Cow::Borrowed("Culture"),
+                sql_type: (&&SqlTypeProbe::<i16>::new()).probe_sql_type(0),
+                nullable: false,
+                max_length: 0,
+            },
+        }),
+        view: false,
+    };
+
+    assert_eq!(
+        create_table_sql(&schema).ok().as_deref(),
+        Some("CREATE TABLE dbo.Labels ([Id] INT IDENTITY(1,1) NOT NULL, [Code] NVARCHAR(10) NOT NULL, [Parent] NVARCHAR(MAX) NULL, PRIMARY KEY ([Id]));\n\
+        CREATE TABLE dbo.LabelsTranslatedValues ([LabelId] INT NOT NULL, [Culture] SMALLINT NOT NULL, [Name] NVARCHAR(50) NOT NULL, PRIMARY KEY ([LabelId], [Culture]));")
+    );
+
+    let unknown = TableSchema {
+        columns: vec![ColumnSchema {
+            name: Cow::Borrowed("Id"),
+            sql_type: None,
+            nullable: false,
+            max_length: 0,
+        }],
+        translated: None,
+        ..schema
+    };
+
+    assert!(create_table_sql(&unknown).is_err());
+
+    let view = TableSchema {
+        view: true,
+        ..unknown
+    };
+
+    assert!(create_table_sql(&view).is_err());
+}
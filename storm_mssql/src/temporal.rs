@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use storm::{provider::LoadArgs, BoxFuture, Entity, Result};
+
+/// A version of an entity read from the history of a system-versioned table.
+///
+/// The version is valid from `valid_from` (inclusive) until `valid_to` (exclusive), both in UTC.
+/// The current version ends at `9999-12-31 23:59:59.9999999`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityVersion<E> {
+    pub valid_from: NaiveDateTime,
+    pub valid_to: NaiveDateTime,
+    pub entity: E,
+}
+
+/// Loads the entities of a system-versioned table as they were at a point in time
+/// (`FOR SYSTEM_TIME AS OF`).
+///
+/// Implemented by the `MssqlLoad` derive with `#[storm(temporal)]`. The entities are returned
+/// to the caller, the tables of the `Ctx` are not touched. The translated fields are loaded with
+/// their current values.
+pub trait LoadAllAsOf<E: Entity, FILTER: Send + Sync, C>: Send + Sync
+where
+    C: Default + Extend<(E::Key, E)> + Send,
+{
+    /// `as_of` is in UTC, the filter parameters start at `@p2`.
+    fn load_all_as_of_with_args<'a>(
+        &'a self,
+        filter: &'a FILTER,
+        as_of: NaiveDateTime,
+        args: LoadArgs,
+    ) -> BoxFuture<'a, Result<C>>;
+
+    fn load_all_as_of<'a>(
+        &'a self,
+        filter: &'a FILTER,
+        as_of: NaiveDateTime,
+    ) -> BoxFuture<'a, Result<C>> {
+        self.load_all_as_of_with_args(filter, as_of, LoadArgs::default())
+    }
+}
+
+/// Loads one entity of a system-versioned table as it was at a point in time.
+pub trait LoadOneAsOf<E: Entity>: Send + Sync {
+    /// `as_of` is in UTC.
+    fn load_one_as_of_with_args<'a>(
+        &'a self,
+        k: &'a E::Key,
+        as_of: NaiveDateTime,
+        args: LoadArgs,
+    ) -> BoxFuture<'a, Result<Option<E>>>;
+
+    fn load_one_as_of<'a>(
+        &'a self,
+        k: &'a E::Key,
+        as_of: NaiveDateTime,
+    ) -> BoxFuture<'a, Result<Option<E>>> {
+        self.load_one_as_of_with_args(k, as_of, LoadArgs::default())
+    }
+}
+
+/// Loads all the versions of one entity of a system-versioned table (`FOR SYSTEM_TIME ALL`),
+/// ordered by `valid_from`.
+///
+/// The soft deleted versions are included and the translated fields are left empty.
+pub trait LoadHistory<E: Entity>: Send + Sync {
+    fn load_history_with_args<'a>(
+        &'a self,
+        k: &'a E::Key,
+        args: LoadArgs,
+    ) -> BoxFuture<'a, Result<Vec<EntityVersion<E>>>>;
+
+    fn load_history<'a>(&'a self, k: &'a E::Key) -> BoxFuture<'a, Result<Vec<EntityVersion<E>>>> {
+        self.load_history_with_args(k, LoadArgs::default())
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{QueryRows, ToSql};
+use std::{any::Any, borrow::Cow};
+use storm::{Error, ProviderContainer, Result};
+
+type Filter<'a> = (Cow<'a, str>, Cow<'a, [&'a dyn ToSql]>);
+
+/// Scopes a container to a tenant passed as a parameter to the queries of the entities having a
+/// `tenant` column, the type of the tenant must implement `ToSql`.
+///
+/// ```ignore
+/// let provider = ProviderContainer::new().with_mssql_tenant(7);
+///
+/// // the tenant is set by the registry.
+/// let registry = TenantRegistry::new(|_tenant: &i32| {
+///     ProviderContainer::new().with_mssql_tenant_type::<i32>()
+/// });
+/// ```
+pub trait MssqlTenant: Sized {
+    fn with_mssql_tenant<T: Any + ToSql>(self, tenant: T) -> Self;
+
+    /// Declares the type of the tenant set afterward with `with_tenant`.
+    fn with_mssql_tenant_type<T: Any + ToSql>(self) -> Self;
+}
+
+impl MssqlTenant for ProviderContainer {
+    fn with_mssql_tenant<T: Any + ToSql>(self, tenant: T) -> Self {
+        self.with_mssql_tenant_type::<T>().with_tenant(tenant)
+    }
+
+    fn with_mssql_tenant_type<T: Any + ToSql>(self) -> Self {
+        self.with_tenant_param(TenantParam(tenant_to_sql::<T>))
+    }
+}
+
+/// The conversion of the tenant to a parameter, captured with the type of the tenant.
+struct TenantParam(fn(&(dyn Any + Send + Sync)) -> Option<&dyn ToSql>);
+
+fn tenant_to_sql<T: Any + ToSql>(tenant: &(dyn Any + Send + Sync)) -> Option<&dyn ToSql> {
+    tenant.downcast_ref::<T>().map(|t| t as &dyn ToSql)
+}
+
+/// The tenant of the container as a parameter.
+#[doc(hidden)]
+pub fn tenant_param(provider: &ProviderContainer) -> Result<&dyn ToSql> {
+    let tenant = provider.tenant_any().ok_or(Error::TenantNotSet)?;
+
+    let param = provider
+        .tenant_param::<TenantParam>()
+        .ok_or(Error::Str("The tenant must be set with `MssqlTenant`."))?;
+
+    (param.0)(tenant).ok_or(Error::Str(
+        "The tenant is not of the type declared with `MssqlTenant`.",
+    ))
+}
+
+/// Adds the tenant condition to the filter of a load, the tenant is the last parameter.
+#[doc(hidden)]
+pub fn tenant_filter<'a>(
+    provider: &'a ProviderContainer,
+    column: &str,
+    sql: impl Into<Cow<'a, str>>,
+    params: impl Into<Cow<'a, [&'a dyn ToSql]>>,
+) -> Result<Filter<'a>> {
+    let tenant = tenant_param(provider)?;
+    let sql = sql.into();
+    let mut params = params.into().into_owned();
+
+    params.push(tenant);
+
+    let condition = format!("{column}=@p{}", params.len());
+
+    let sql = match sql.is_empty() {
+        true => condition,
+        false => format!("({sql}) AND {condition}"),
+    };
+
+    Ok((Cow::Owned(sql), Cow::Owned(params)))
+}
+
+/// Fails with `Error::TenantMismatch` if `sql` finds the row of another tenant, called when
+/// a write scoped to the tenant affected no rows.
+#[doc(hidden)]
+pub async fn check_tenant_row<P: QueryRows>(
+    provider: &P,
+    sql: &str,
+    params: &[&dyn ToSql],
+) -> Result<()> {
+    let rows: Vec<()> = provider
+        .query_rows(sql.to_string(), params, |_| Ok(()), true)
+        .await?;
+
+    match rows.is_empty() {
+        true => Ok(()),
+        false => Err(Error::TenantMismatch),
+    }
+}
+
+/// Fails if the tenant field of an entity is not the tenant of the container.
+#[doc(hidden)]
+pub fn check_tenant<T: Any + PartialEq>(provider: &ProviderContainer, value: &T) -> Result<()> {
+    let tenant = provider.tenant_any().ok_or(Error::TenantNotSet)?;
+
+    match tenant.downcast_ref::<T>() {
+        Some(t) if t == value => Ok(()),
+        Some(_) => Err(Error::TenantMismatch),
+        None => Err(Error::Str(
+            "The tenant field is not of the type of the tenant of the container.",
+        )),
+    }
+}
+
+#[test]
+fn tenant_filters() {
+    let provider = ProviderContainer::new().with_mssql_tenant(7);
+    let id = 1;
+    let params: &[&dyn ToSql] = &[&id];
+
+    let (sql, params) = tenant_filter(&provider, "t.[TenantId]", "t.[Id]=@p1", params)
+        .unwrap_or((Cow::Borrowed(""), Cow::Borrowed(&[])));
+
+    assert_eq!(sql, "(t.[Id]=@p1) AND t.[TenantId]=@p2");
+    assert_eq!(params.len(), 2);
+
+    assert!(check_tenant(&provider, &7).is_ok());
+    assert!(matches!(
+        check_tenant(&provider, &8),
+        Err(Error::TenantMismatch)
+    ));
+    assert!(matches!(
+        check_tenant(&ProviderContainer::new(), &7),
+        Err(Error::TenantNotSet)
+    ));
+
+    // the tenant type is required to pass the tenant as a parameter.
+    assert!(tenant_param(&ProviderContainer::new().with_tenant(7)).is_err());
+    assert!(tenant_param(
+        &ProviderContainer::new()
+            .with_mssql_tenant_type::<i64>()
+            .with_tenant(7)
+    )
+    .is_err());
+}
@@ -1,8 +1,15 @@
 use std::{borrow::Cow, sync::Arc};
-use tiberius::ColumnData;
+use storm::{Error, Result};
+use tiberius::{xml::XmlData, ColumnData};
+use tracing::error;
 
 pub trait ToSql: Send + Sync {
     fn to_sql(&self) -> ColumnData;
+
+    /// The conversion used when saving an entity, for the values that can fail to convert.
+    fn try_to_sql(&self) -> Result<ColumnData> {
+        Ok(self.to_sql())
+    }
 }
 
 pub trait ToSqlNull {
@@ -13,6 +20,10 @@ impl<T: ToSql> ToSql for &T {
     fn to_sql(&self) -> ColumnData {
         (**self).to_sql()
     }
+
+    fn try_to_sql(&self) -> Result<ColumnData> {
+        (**self).try_to_sql()
+    }
 }
 
 impl<T> ToSql for Option<T>
@@ -25,6 +36,13 @@ where
             None => T::to_sql_null(),
         }
     }
+
+    fn try_to_sql(&self) -> Result<ColumnData> {
+        match self.as_ref() {
+            Some(v) => v.try_to_sql(),
+            None => Ok(T::to_sql_null()),
+        }
+    }
 }
 
 macro_rules! to_sql {
@@ -58,6 +76,21 @@ macro_rules! to_sql {
             }
         }
     };
+    (widen $t:ty => $n:ident as $w:ty) => {
+        impl ToSql for $t {
+            #[inline]
+            fn to_sql(&self) -> ColumnData {
+                ColumnData::$n(Some(<$w>::from(*self)))
+            }
+        }
+
+        impl ToSqlNull for $t {
+            #[inline]
+            fn to_sql_null() -> ColumnData<'static> {
+                ColumnData::$n(None)
+            }
+        }
+    };
     (deref<'a> $t:ty, $n:ident) => {
         impl<'a> ToSql for $t {
             #[inline]
@@ -120,6 +153,10 @@ to_sql!(copied i32 => I32);
 to_sql!(copied i64 => I64);
 to_sql!(copied u8 => U8);
 
+to_sql!(widen i8 => I16 as i16);
+to_sql!(widen u16 => I32 as i32);
+to_sql!(widen u32 => I64 as i64);
+
 to_sql!(deref Arc<[u8]>, Binary);
 to_sql!(deref Arc<str>, String);
 to_sql!(deref Box<[u8]>, Binary);
@@ -134,6 +171,64 @@ to_sql!(transform chrono::NaiveDateTime);
 to_sql!(transform chrono::NaiveTime);
 to_sql!(transform uuid::Uuid);
 
+impl ToSql for chrono::DateTime<chrono::Local> {
+    #[inline]
+    fn to_sql(&self) -> ColumnData {
+        tiberius::IntoSql::into_sql(self.fixed_offset())
+    }
+}
+
+impl ToSqlNull for chrono::DateTime<chrono::Local> {
+    #[inline]
+    fn to_sql_null() -> ColumnData<'static> {
+        ColumnData::DateTimeOffset(None)
+    }
+}
+
+/// Saved in a `BIGINT`, a value above `i64::MAX` fails to convert instead of wrapping.
+impl ToSql for u64 {
+    fn to_sql(&self) -> ColumnData {
+        match self.try_to_sql() {
+            Ok(v) => v,
+            Err(e) => {
+                // only outside of a save, which fails instead, the column is set to null.
+                error!(error = %e, "u64 conversion failed");
+                ColumnData::I64(None)
+            }
+        }
+    }
+
+    fn try_to_sql(&self) -> Result<ColumnData> {
+        match i64::try_from(*self) {
+            Ok(v) => Ok(ColumnData::I64(Some(v))),
+            Err(_) => Err(Error::ConvertFailed(format!(
+                "`{self}` is out of the range of `i64`."
+            ))),
+        }
+    }
+}
+
+impl ToSqlNull for u64 {
+    #[inline]
+    fn to_sql_null() -> ColumnData<'static> {
+        ColumnData::I64(None)
+    }
+}
+
+impl ToSql for XmlData {
+    #[inline]
+    fn to_sql(&self) -> ColumnData {
+        ColumnData::Xml(Some(Cow::Borrowed(self)))
+    }
+}
+
+impl ToSqlNull for XmlData {
+    #[inline]
+    fn to_sql_null() -> ColumnData<'static> {
+        ColumnData::Xml(None)
+    }
+}
+
 #[cfg(feature = "dec19x5")]
 impl ToSql for dec19x5::Decimal {
     #[inline]
@@ -150,6 +245,9 @@ impl ToSqlNull for dec19x5::Decimal {
     }
 }
 
+#[cfg(feature = "rust_decimal")]
+to_sql!(transform rust_decimal::Decimal);
+
 #[cfg(feature = "str_utils")]
 impl<F: Send + Sync> ToSql for str_utils::form_str::FormStr<F> {
     fn to_sql(&self) -> ColumnData {
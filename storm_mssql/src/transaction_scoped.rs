@@ -1,4 +1,4 @@
-use crate::{Client, ClientFactory, MssqlFactory, MssqlProvider};
+use crate::{Client, ClientFactory, ConfiguredMssqlFactory, MssqlFactory, MssqlProvider};
 use storm::{provider::ProviderFactory, BoxFuture, Error, Result};
 
 /// This can wrap a ClientFactory and creates a transaction for each Client that are returned.
@@ -20,6 +20,26 @@ impl ProviderFactory for TransactionScoped<MssqlFactory> {
     }
 }
 
+impl From<ConfiguredMssqlFactory> for TransactionScoped<ConfiguredMssqlFactory> {
+    fn from(f: ConfiguredMssqlFactory) -> Self {
+        TransactionScoped(f)
+    }
+}
+
+impl ProviderFactory for TransactionScoped<ConfiguredMssqlFactory> {
+    type Provider = MssqlProvider;
+
+    fn create_provider(&self) -> BoxFuture<'_, Result<Self::Provider>> {
+        Box::pin(async move {
+            Ok(MssqlProvider::with_config(
+                TransactionScoped(self.0.config.clone()),
+                self.0.retry_policy.clone(),
+                self.0.pool_config.clone(),
+            ))
+        })
+    }
+}
+
 impl<F> ClientFactory for TransactionScoped<F>
 where
     F: ClientFactory + Send + Sync,
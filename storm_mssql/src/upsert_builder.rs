@@ -1,12 +1,25 @@
-use crate::{Error, Execute, FromSql, Parameter, QueryRows, Result, ToSql};
+use crate::{
+    check_tenant_row, Error, Execute, FromSql, MssqlProvider, Parameter, Procedure, QueryRows,
+    Result, ToSql,
+};
 use storm::IsDefined;
 use tiberius::ColumnData;
 use tracing::error;
 
 pub struct UpsertBuilder<'a> {
+    /// The column of each parameter, used to call a stored procedure.
+    columns: Vec<String>,
+
+    /// The first value that failed to convert, returned instead of executing the upsert.
+    error: Option<Error>,
+    identity: Option<usize>,
     insert_fields: String,
     insert_values: String,
     params: Vec<Parameter<'a>>,
+
+    /// The condition on the tenant column, kept apart from the keys to detect the rows of
+    /// another tenant.
+    tenant_where: Option<String>,
     update_setters: String,
     update_wheres: String,
     upsert_mode: UpsertMode,
@@ -16,9 +29,13 @@ pub struct UpsertBuilder<'a> {
 impl<'a> UpsertBuilder<'a> {
     pub fn new(table: &'a str) -> Self {
         Self {
+            columns: Vec::new(),
+            error: None,
+            identity: None,
             insert_fields: String::new(),
             insert_values: String::new(),
             params: Vec::new(),
+            tenant_where: None,
             update_setters: String::new(),
             update_wheres: String::new(),
             upsert_mode: UpsertMode::InsertThanUpdate,
@@ -46,7 +63,7 @@ impl<'a> UpsertBuilder<'a> {
     pub fn add_field_identity<T: IsDefined + ToSql>(&mut self, name: &str, value: T) {
         if value.is_defined() {
             self.upsert_mode = UpsertMode::Update;
-            self.params.push(Parameter::from_owned(value));
+            self.push_param(name, Parameter::try_from_owned(value));
             self.add_field(name);
         } else {
             self.upsert_mode = UpsertMode::Insert;
@@ -54,12 +71,12 @@ impl<'a> UpsertBuilder<'a> {
     }
 
     pub fn add_field_owned<T: ToSql>(&mut self, name: &str, value: T) {
-        self.params.push(Parameter::from_owned(value));
+        self.push_param(name, Parameter::try_from_owned(value));
         self.add_field(name);
     }
 
     pub fn add_field_ref<T: ToSql>(&mut self, name: &str, value: &'a T) {
-        self.params.push(Parameter::from_ref(value));
+        self.push_param(name, Parameter::try_from_ref(value));
         self.add_field(name);
     }
 
@@ -70,7 +87,8 @@ impl<'a> UpsertBuilder<'a> {
             self.upsert_mode = UpsertMode::Insert;
         }
 
-        self.params.push(Parameter::from_owned(value));
+        self.identity = Some(self.params.len());
+        self.push_param(name, Parameter::try_from_owned(value));
 
         if !self.update_wheres.is_empty() {
             self.update_wheres.push_str("AND");
@@ -82,7 +100,11 @@ impl<'a> UpsertBuilder<'a> {
     }
 
     pub fn add_key_ref<T: ToSql>(&mut self, name: &str, value: &'a T) {
-        self.params.push(Parameter::from_ref(value));
+        self.add_key_dyn(name, value);
+    }
+
+    pub fn add_key_dyn(&mut self, name: &str, value: &'a dyn ToSql) {
+        self.push_param(name, Parameter::try_from_ref(value));
 
         if !self.insert_fields.is_empty() {
             self.insert_fields.push(',');
@@ -101,6 +123,23 @@ impl<'a> UpsertBuilder<'a> {
         self.add_wheres(name, param);
     }
 
+    /// Scopes the upsert to the tenant, saving the row of another tenant with the same keys
+    /// fails with `Error::TenantMismatch`.
+    pub fn add_tenant_dyn(&mut self, name: &str, value: &'a dyn ToSql) {
+        self.push_param(name, Parameter::try_from_ref(value));
+
+        if !self.insert_fields.is_empty() {
+            self.insert_fields.push(',');
+            self.insert_values.push(',');
+        }
+
+        let param = &self.param();
+
+        self.insert_fields.push_str(name);
+        self.insert_values.push_str(param);
+        self.tenant_where = Some(format!("({name}={param})"));
+    }
+
     fn add_wheres(&mut self, name: &str, param: &str) {
         self.update_wheres.push('(');
         self.update_wheres.push_str(name);
@@ -109,22 +148,32 @@ impl<'a> UpsertBuilder<'a> {
         self.update_wheres.push(')');
     }
 
-    pub async fn execute<P: Execute>(self, provider: &P) -> Result<()> {
+    pub async fn execute<P: Execute + QueryRows>(mut self, provider: &P) -> Result<()> {
+        self.take_error()?;
+
         let sql = self.sql();
         let params = self.params.iter().map(|v| v as _).collect::<Vec<_>>();
-        provider.execute(sql, params.as_slice()).await?;
+
+        if provider.execute(sql, params.as_slice()).await? == 0 {
+            self.check_tenant(provider, &params).await?;
+        }
+
         Ok(())
     }
 
-    pub async fn execute_identity<K, P>(self, provider: &P, key: &mut K) -> Result<()>
+    pub async fn execute_identity<K, P>(mut self, provider: &P, key: &mut K) -> Result<()>
     where
         K: for<'b> FromSql<'b> + ToSql + Send,
         P: Execute + QueryRows,
     {
+        self.take_error()?;
+
         let sql = self.sql();
         let params = self.params.iter().map(|v| v as _).collect::<Vec<_>>();
 
-        provider.execute(sql, params.as_slice()).await?;
+        if provider.execute(sql, params.as_slice()).await? == 0 {
+            self.check_tenant(provider, &params).await?;
+        }
 
         if self.upsert_mode == UpsertMode::Insert {
             let cast_ty = column_data_to_sql_type(key.to_sql())?;
@@ -144,6 +193,77 @@ impl<'a> UpsertBuilder<'a> {
         Ok(())
     }
 
+    /// Fails if nothing was saved because the keys are the ones of a row of another tenant.
+    async fn check_tenant<P: QueryRows>(&self, provider: &P, params: &[&dyn ToSql]) -> Result<()> {
+        let Some(tenant_where) = &self.tenant_where else {
+            return Ok(());
+        };
+
+        if self.update_wheres.is_empty() {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "SELECT 1 FROM {} WHERE {}AND NOT {tenant_where}",
+            self.table, self.update_wheres
+        );
+
+        check_tenant_row(provider, &sql, params).await
+    }
+
+    /// Calls the stored procedure `proc` instead of the upsert statement, each column is
+    /// passed to the parameter of the same name.
+    pub async fn execute_proc(mut self, provider: &MssqlProvider, proc: &str) -> Result<()> {
+        self.take_error()?;
+        self.into_procedure(proc, None).execute(provider).await?;
+        Ok(())
+    }
+
+    /// Calls the stored procedure `proc`, the identity key is an `OUTPUT` parameter the
+    /// procedure sets on insert.
+    pub async fn execute_proc_identity<K>(
+        mut self,
+        provider: &MssqlProvider,
+        proc: &str,
+        key: &mut K,
+    ) -> Result<()>
+    where
+        K: for<'b> FromSql<'b> + ToSql + Send,
+    {
+        self.take_error()?;
+
+        let cast_ty = column_data_to_sql_type(key.to_sql())?;
+
+        let column = self
+            .identity
+            .and_then(|i| self.columns.get(i))
+            .map(|c| proc_param_name(c).to_string())
+            .ok_or(Error::Internal)?;
+
+        let result = self
+            .into_procedure(proc, Some(cast_ty))
+            .execute(provider)
+            .await?;
+
+        *key = result.output(&column)?;
+
+        Ok(())
+    }
+
+    fn into_procedure(self, proc: &str, identity_ty: Option<&'static str>) -> Procedure<'a> {
+        let mut procedure = Procedure::new(proc.to_string());
+        let identity = self.identity;
+
+        for (index, (column, param)) in self.columns.iter().zip(self.params).enumerate() {
+            let output = identity_ty
+                .filter(|_| identity == Some(index))
+                .map(Into::into);
+            procedure.push_param(proc_param_name(column), param, output);
+        }
+
+        procedure
+    }
+
     fn insert_sql(&self) -> String {
         if self.insert_fields.is_empty() {
             // when there is no fields in the table except an identity column.
@@ -156,6 +276,26 @@ impl<'a> UpsertBuilder<'a> {
         }
     }
 
+    fn push_param(&mut self, name: &str, param: Result<Parameter<'a>>) {
+        self.columns.push(name.to_string());
+
+        match param {
+            Ok(param) => self.params.push(param),
+            Err(e) => {
+                // keeps the indexes of the next parameters, the upsert is never executed.
+                self.error.get_or_insert(e);
+                self.params.push(Parameter(ColumnData::String(None)));
+            }
+        }
+    }
+
+    fn take_error(&mut self) -> Result<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
     fn param(&self) -> String {
         format!("@p{}", self.params.len())
     }
@@ -172,6 +312,18 @@ impl<'a> UpsertBuilder<'a> {
                         "IF NOT EXISTS(SELECT 1 FROM {} WHERE {}) {insert};",
                         self.table, self.update_wheres
                     )
+                } else if self.tenant_where.is_some() {
+                    // the row of another tenant is neither updated nor inserted again.
+                    format!(
+                        "
+                        {update}
+                        IF @@ROWCOUNT = 0 AND NOT EXISTS(SELECT 1 FROM {} WHERE {})
+                        BEGIN
+                            {insert}
+                        END
+                    ",
+                        self.table, self.update_wheres
+                    )
                 } else {
                     format!(
                         "
@@ -192,14 +344,24 @@ impl<'a> UpsertBuilder<'a> {
         if self.update_setters.is_empty() {
             String::new()
         } else {
+            let wheres = match (&self.tenant_where, self.update_wheres.is_empty()) {
+                (Some(tenant_where), true) => tenant_where.clone(),
+                (Some(tenant_where), false) => format!("{}AND{tenant_where}", self.update_wheres),
+                (None, _) => self.update_wheres.clone(),
+            };
+
             format!(
                 "UPDATE {} SET {} WHERE {}",
-                self.table, self.update_setters, self.update_wheres
+                self.table, self.update_setters, wheres
             )
         }
     }
 }
 
+fn proc_param_name(column: &str) -> &str {
+    column.trim_start_matches('[').trim_end_matches(']')
+}
+
 struct OneValue<T>(Option<T>);
 
 impl<T> Default for OneValue<T> {
@@ -235,3 +397,26 @@ fn column_data_to_sql_type(data: ColumnData<'_>) -> Result<&'static str> {
         }
     }
 }
+
+#[test]
+fn upsert_tenant_sql() {
+    let id = 1;
+    let name = "a";
+    let tenant = 7;
+    let mut builder = UpsertBuilder::new("T");
+
+    builder.add_field_ref("[Name]", &name);
+    builder.add_key_ref("[Id]", &id);
+    builder.add_tenant_dyn("[TenantId]", &tenant);
+
+    let sql = builder
+        .sql()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    assert_eq!(
+        sql,
+        "UPDATE T SET [Name]=@p1 WHERE ([Id]=@p2)AND([TenantId]=@p3) IF @@ROWCOUNT = 0 AND NOT EXISTS(SELECT 1 FROM T WHERE ([Id]=@p2)) BEGIN INSERT INTO T ([Name],[Id],[TenantId]) VALUES (@p1,@p2,@p3) END"
+    );
+}
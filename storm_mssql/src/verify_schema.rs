@@ -0,0 +1,541 @@
+use crate::{
+    schema::object_name, ColumnSchema, MssqlProvider, MssqlSchema, QueryRows, TableSchema,
+};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+};
+use storm::{parking_lot::RwLock, provider::ProviderContainer, Error, Result};
+
+static SCHEMAS: RwLock<Vec<fn() -> TableSchema>> = RwLock::new(Vec::new());
+
+/// Called by the `MssqlLoad` derive at startup to register the schema of the entity.
+#[doc(hidden)]
+pub fn register_schema<E: MssqlSchema>() {
+    SCHEMAS.write().push(E::schema);
+}
+
+/// The schemas of all the entities deriving `MssqlLoad`, sorted by entity name.
+pub fn registered_schemas() -> Vec<TableSchema> {
+    let mut vec = SCHEMAS.read().iter().map(|f| f()).collect::<Vec<_>>();
+    vec.sort_by(|a, b| a.entity.cmp(b.entity).then(a.table.cmp(b.table)));
+    vec
+}
+
+/// Compares the schema of every registered entity with the tables of the database.
+///
+/// Columns present in the database but unknown to the entity are not reported. Only the columns
+/// of the entities loaded from a view are verified.
+pub async fn verify_schema(container: &ProviderContainer) -> Result<SchemaReport> {
+    let mut tables = Vec::new();
+
+    for schema in registered_schemas() {
+        let provider: &MssqlProvider = container.provide(schema.provider).await?;
+        tables.extend(verify_table_schema(provider, &schema).await?);
+    }
+
+    Ok(SchemaReport { tables })
+}
+
+/// Compares the schema of an entity with its table and the translated table if any.
+pub async fn verify_table_schema(
+    provider: &MssqlProvider,
+    schema: &TableSchema,
+) -> Result<Vec<TableReport>> {
+    let mut reports = Vec::with_capacity(2);
+
+    reports.push(
+        verify_table(
+            provider,
+            schema.entity,
+            schema.table,
+            &schema.columns,
+            &schema.keys,
+            schema.identity,
+            schema.view,
+        )
+        .await?,
+    );
+
+    if let Some(t) = &schema.translated {
+        let mut columns = t.columns.clone();
+        columns.push(t.culture.clone());
+
+        let mut keys: Vec<&str> = t.keys.clone();
+        keys.push(&t.culture.name);
+
+        reports.push(
+            verify_table(
+                provider,
+                schema.entity,
+                t.table,
+                &columns,
+                &keys,
+                None,
+                false,
+            )
+            .await?,
+        );
+    }
+
+    Ok(reports)
+}
+
+async fn verify_table(
+    provider: &MssqlProvider,
+    entity: &'static str,
+    table: &'static str,
+    expected: &[ColumnSchema],
+    keys: &[&str],
+    identity: Option<&'static str>,
+    view: bool,
+) -> Result<TableReport> {
+    let actual = load_columns(provider, table).await?;
+    let mut issues = Vec::new();
+
+    if actual.is_empty() {
+        issues.push(SchemaIssue::TableNotFound);
+
+        return Ok(TableReport {
+            entity,
+            table,
+            issues,
+        });
+    }
+
+    for column in expected {
+        match actual
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(&column.name))
+        {
+            Some(actual) => verify_column(column, actual, &mut issues),
+            None => issues.push(SchemaIssue::ColumnNotFound {
+                column: column.name.clone(),
+            }),
+        }
+    }
+
+    // a view has no primary key nor identity.
+    if view {
+        return Ok(TableReport {
+            entity,
+            table,
+            issues,
+        });
+    }
+
+    let mut expected_keys = keys.iter().map(|k| k.to_lowercase()).collect::<Vec<_>>();
+    let mut actual_keys = actual
+        .iter()
+        .filter(|c| c.is_key)
+        .map(|c| c.name.to_lowercase())
+        .collect::<Vec<_>>();
+
+    expected_keys.sort_unstable();
+    actual_keys.sort_unstable();
+
+    if expected_keys != actual_keys {
+        issues.push(SchemaIssue::KeysMismatch {
+            expected: expected_keys,
+            actual: actual_keys,
+        });
+    }
+
+    let expected_identity = identity.map(|i| i.to_lowercase());
+    let actual_identity = actual
+        .iter()
+        .find(|c| c.is_identity)
+        .map(|c| c.name.to_lowercase());
+
+    if expected_identity != actual_identity {
+        issues.push(SchemaIssue::IdentityMismatch {
+            expected: expected_identity,
+            actual: actual_identity,
+        });
+    }
+
+    Ok(TableReport {
+        entity,
+        table,
+        issues,
+    })
+}
+
+fn verify_column(expected: &ColumnSchema, actual: &DbColumn, issues: &mut Vec<SchemaIssue>) {
+    let column = &expected.name;
+
+    if let Some(expected_type) = expected.sql_type.as_deref() {
+        let same_base = compatible_types(&base_type(expected_type))
+            .contains(&base_type(&actual.sql_type).as_str());
+
+        // the length of a string or binary column is checked with the max length.
+        let same_params = actual.length().is_some()
+            || type_params(expected_type) == type_params(&actual.sql_type);
+
+        if !same_base || !same_params {
+            issues.push(SchemaIssue::TypeMismatch {
+                column: column.clone(),
+                expected: expected_type.to_string(),
+                actual: actual.sql_type.clone(),
+            });
+        }
+    }
+
+    if expected.nullable != actual.nullable {
+        issues.push(SchemaIssue::NullableMismatch {
+            column: column.clone(),
+            expected: expected.nullable,
+        });
+    }
+
+    if let Some(length) = actual.length() {
+        if expected.max_length > 0 && length != Some(expected.max_length) {
+            issues.push(SchemaIssue::MaxLengthMismatch {
+                column: column.clone(),
+                expected: expected.max_length,
+                actual: length,
+            });
+        }
+    }
+}
+
+fn base_type(sql_type: &str) -> String {
+    sql_type
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase()
+}
+
+/// The column types a rust type can be read from, the type generated in the schema first.
+fn compatible_types(base_type: &str) -> Vec<&str> {
+    match base_type {
+        "NVARCHAR" => vec!["NVARCHAR", "VARCHAR", "NCHAR", "CHAR"],
+        "VARBINARY" => vec!["VARBINARY", "BINARY"],
+        "DATETIME2" => vec!["DATETIME2", "DATETIME", "SMALLDATETIME"],
+        t => vec![t],
+    }
+}
+
+/// The precision and scale of the type, without whitespaces.
+fn type_params(sql_type: &str) -> String {
+    sql_type
+        .split_once('(')
+        .map(|(_, params)| params)
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+struct DbColumn {
+    name: String,
+    sql_type: String,
+    type_name: String,
+    max_length: i32,
+    nullable: bool,
+    is_identity: bool,
+    is_key: bool,
+}
+
+impl DbColumn {
+    /// The length in characters or bytes of a string or binary column, `Some(None)` for `MAX`.
+    fn length(&self) -> Option<Option<usize>> {
+        let len = |v: i32| usize::try_from(v).ok();
+
+        match self.type_name.as_str() {
+            _ if !is_length_type(&self.type_name) => None,
+            _ if self.max_length == -1 => Some(None),
+            "nchar" | "nvarchar" => Some(len(self.max_length >> 1)),
+            _ => Some(len(self.max_length)),
+        }
+    }
+}
+
+fn is_length_type(type_name: &str) -> bool {
+    matches!(
+        type_name,
+        "binary" | "char" | "nchar" | "nvarchar" | "varbinary" | "varchar"
+    )
+}
+
+async fn load_columns(provider: &MssqlProvider, table: &str) -> Result<Vec<DbColumn>> {
+    let (db, object) = object_name(table);
+
+    let sql = format!(
+        r"
+        SELECT
+            c.[name],
+            TYPE_NAME(c.system_type_id),
+            CAST(c.max_length AS INT),
+            CAST(c.[precision] AS INT),
+            CAST(c.scale AS INT),
+            c.is_nullable,
+            c.is_identity,
+            CAST(CASE WHEN ic.column_id IS NULL THEN 0 ELSE 1 END AS BIT)
+        FROM
+            {db}sys.all_columns c
+            LEFT JOIN {db}sys.indexes i
+            ON i.object_id = c.object_id AND i.is_primary_key = 1
+            LEFT JOIN {db}sys.index_columns ic
+            ON ic.object_id = i.object_id AND ic.index_id = i.index_id AND ic.column_id = c.column_id
+        WHERE
+            c.object_id = OBJECT_ID(@P1)"
+    );
+
+    provider
+        .query_rows(
+            sql,
+            &[&&*object],
+            |row| {
+                let type_name = row.get::<&str, _>(1).unwrap_or_default().to_lowercase();
+
+                let max_length = row.get::<i32, _>(2).unwrap_or_default();
+                let precision = row.get::<i32, _>(3).unwrap_or_default();
+                let scale = row.get::<i32, _>(4).unwrap_or_default();
+
+                let sql_type = match type_name.as_str() {
+                    t if is_length_type(t) && max_length == -1 => format!("{t}(MAX)"),
+                    t @ ("nchar" | "nvarchar") => format!("{t}({})", max_length >> 1),
+                    t if is_length_type(t) => format!("{t}({max_length})"),
+                    t @ ("decimal" | "numeric") => format!("{t}({precision}, {scale})"),
+                    t => t.to_string(),
+                };
+
+                Ok(DbColumn {
+                    name: row.get::<&str, _>(0).unwrap_or_default().to_string(),
+                    sql_type: sql_type.to_uppercase(),
+                    type_name,
+                    max_length,
+                    nullable: row.get::<bool, _>(5).unwrap_or_default(),
+                    is_identity: row.get::<bool, _>(6).unwrap_or_default(),
+                    is_key: row.get::<bool, _>(7).unwrap_or_default(),
+                })
+            },
+            false,
+        )
+        .await
+}
+
+/// The differences between the registered entities and the database, see [verify_schema].
+#[derive(Clone, Debug, Default)]
+pub struct SchemaReport {
+    pub tables: Vec<TableReport>,
+}
+
+impl SchemaReport {
+    pub fn is_ok(&self) -> bool {
+        self.tables.iter().all(|t| t.issues.is_empty())
+    }
+
+    /// Returns an error describing all the issues, if any.
+    pub fn into_result(self) -> Result<Self> {
+        match self.is_ok() {
+            true => Ok(self),
+            false => Err(Error::String(self.to_string())),
+        }
+    }
+}
+
+impl Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+
+        for table in &self.tables {
+            for issue in &table.issues {
+                if !first {
+                    f.write_str("\n")?;
+                }
+
+                write!(f, "{} ({}): {issue}", table.table, table.entity)?;
+                first = false;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TableReport {
+    pub entity: &'static str,
+    pub table: &'static str,
+    pub issues: Vec<SchemaIssue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaIssue {
+    TableNotFound,
+    ColumnNotFound {
+        column: Cow<'static, str>,
+    },
+    TypeMismatch {
+        column: Cow<'static, str>,
+        expected: String,
+        actual: String,
+    },
+    NullableMismatch {
+        column: Cow<'static, str>,
+        expected: bool,
+    },
+    /// `None` is a `MAX` length.
+    MaxLengthMismatch {
+        column: Cow<'static, str>,
+        expected: usize,
+        actual: Option<usize>,
+    },
+    /// The lowercase names of the primary key columns, sorted.
+    KeysMismatch {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// The lowercase names of the identity columns.
+    IdentityMismatch {
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+}
+
+impl Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TableNotFound => f.write_str("table not found."),
+            Self::ColumnNotFound { column } => write!(f, "column `{column}` not found."),
+            Self::TypeMismatch {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "column `{column}` type differ, expected: {expected}, actual: {actual}."
+            ),
+            Self::NullableMismatch { column, expected } => match expected {
+                true => write!(f, "column `{column}` is expected to be nullable."),
+                false => write!(f, "column `{column}` is expected to be not nullable."),
+            },
+            Self::MaxLengthMismatch {
+                column,
+                expected,
+                actual,
+            } => match actual {
+                Some(actual) => write!(
+                    f,
+                    "column `{column}` max length differ, expected: {expected}, actual: {actual}."
+                ),
+                None => write!(
+                    f,
+                    "column `{column}` max length differ, expected: {expected}, actual: MAX."
+                ),
+            },
+            Self::KeysMismatch { expected, actual } => write!(
+                f,
+                "primary key differ, expected: {expected:?}, actual: {actual:?}."
+            ),
+            Self::IdentityMismatch { expected, actual } => write!(
+                f,
+                "identity differ, expected: {expected:?}, actual: {actual:?}."
+            ),
+        }
+    }
+}
+
+#[test]
+fn verify_columns() {
+    let column = |name, sql_type: &str, nullable, max_length| DbColumn {
+        name: String::from(name),
+        sql_type: sql_type.to_string(),
+        type_name: base_type(sql_type).to_lowercase(),
+        max_length,
+        nullable,
+        is_identity: false,
+        is_key: false,
+    };
+
+    let expected = |name, sql_type: &'static str, nullable, max_length| ColumnSchema {
+        name: Cow::Borrowed(name),
+        sql_type: Some(sql_type.into()),
+        nullable,
+        max_length,
+    };
+
+    let mut issues = Vec::new();
+
+    verify_column(
+        &expected("Name", "NVARCHAR(50)", false, 50),
+        &column("name", "NVARCHAR(50)", false, 100),
+        &mut issues,
+    );
+
+    verify_column(
+        &expected("Amount", "DECIMAL(19, 5)", true, 0),
+        &column("amount", "DECIMAL(19,5)", true, 9),
+        &mut issues,
+    );
+
+    // no max length specified, the length is not checked.
+    verify_column(
+        &expected("Code", "NVARCHAR(MAX)", false, 0),
+        &column("code", "NVARCHAR(10)", false, 20),
+        &mut issues,
+    );
+
+    // a compatible type the rust type can be read from.
+    verify_column(
+        &expected("Code", "NVARCHAR(10)", false, 10),
+        &column("code", "VARCHAR(10)", false, 10),
+        &mut issues,
+    );
+
+    verify_column(
+        &expected("Created", "DATETIME2", false, 0),
+        &column("created", "DATETIME", false, 8),
+        &mut issues,
+    );
+
+    assert!(issues.is_empty());
+
+    verify_column(
+        &expected("Name", "NVARCHAR(50)", true, 50),
+        &column("name", "NVARCHAR(MAX)", false, -1),
+        &mut issues,
+    );
+
+    verify_column(
+        &expected("Id", "INT", false, 0),
+        &column("id", "BIGINT", false, 8),
+        &mut issues,
+    );
+
+    verify_column(
+        &expected("Created", "DATETIME", false, 0),
+        &column("created", "DATETIME2", false, 8),
+        &mut issues,
+    );
+
+    assert_eq!(
+        issues,
+        vec![
+            SchemaIssue::NullableMismatch {
+                column: Cow::Borrowed("Name"),
+                expected: true,
+            },
+            SchemaIssue::MaxLengthMismatch {
+                column: Cow::Borrowed("Name"),
+                expected: 50,
+                actual: None,
+            },
+            SchemaIssue::TypeMismatch {
+                column: Cow::Borrowed("Id"),
+                expected: "INT".to_string(),
+                actual: "BIGINT".to_string(),
+            },
+            SchemaIssue::TypeMismatch {
+                column: Cow::Borrowed("Created"),
+                expected: "DATETIME".to_string(),
+                actual: "DATETIME2".to_string(),
+            },
+        ]
+    );
+}
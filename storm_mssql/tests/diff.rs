@@ -63,6 +63,7 @@ async fn diff_insert() -> Result<()> {
                 &[],
                 ExecuteArgs {
                     use_transaction: false,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -0,0 +1,164 @@
+#![allow(clippy::unwrap_used)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use storm::{prelude::*, EntityValidate, MssqlDelete, MssqlFlatten, MssqlLoad, MssqlSave, Result};
+use storm_mssql::{
+    ApplyEntityDiff, EntityDiff, Execute, ExecuteArgs, Flatten, MssqlFactory, MssqlProvider,
+    MssqlSchema,
+};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    QueueRwLock::new(provider().into())
+}
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[tokio::test]
+async fn flatten_crud() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let ctx = create_ctx();
+        let ctx = ctx.read().await?;
+        let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+        provider
+            .execute_with_args(
+                "CREATE TABLE ##FlattenTbl (Id INT NOT NULL, Name NVARCHAR(100) NOT NULL, BillingCity NVARCHAR(50) NOT NULL, BillingZip NVARCHAR(10) NULL, ShippingCity NVARCHAR(50) NOT NULL, ShippingZip NVARCHAR(10) NULL);",
+                &[],
+                ExecuteArgs {
+                    use_transaction: false,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+        let mut customers = trx.tbl_of::<Customer>().await?;
+
+        let customer = Customer {
+            name: "Alice".to_string(),
+            billing: address("Montreal", Some("H2X")),
+            shipping: address("Quebec", None),
+        };
+
+        customers.insert(1, customer.clone(), &()).await?;
+
+        let log = trx.commit().await?;
+        let mut ctx = ctx.write().await?;
+        ctx.apply_log(log);
+
+        // reloaded from the prefixed columns.
+        let loaded: HashMap<i32, Customer> =
+            storm::provider::LoadAll::load_all(&self::provider(), &()).await?;
+
+        assert_eq!(loaded.get(&1), Some(&customer));
+
+        Ok(())
+    })
+    .await
+}
+
+#[test]
+fn flatten_columns() {
+    assert_eq!(Address::COLUMNS, &["City", "Zip"]);
+
+    let schema = Customer::schema();
+    let columns = schema.columns.iter().map(|c| &*c.name).collect::<Vec<_>>();
+
+    assert_eq!(
+        columns,
+        vec![
+            "Id",
+            "Name",
+            "BillingCity",
+            "BillingZip",
+            "ShippingCity",
+            "ShippingZip"
+        ]
+    );
+}
+
+#[test]
+fn flatten_validate_and_diff() {
+    let old = Customer {
+        name: "Alice".to_string(),
+        billing: address("Montreal", None),
+        shipping: address("Quebec", None),
+    };
+
+    let mut new = old.clone();
+    new.billing.city = String::new();
+
+    let mut error = None;
+    new.entity_validate(&mut error);
+    assert!(error.is_some());
+
+    new.billing.city = "Laval".to_string();
+
+    let mut map = HashMap::new();
+    new.entity_diff(&old, &mut map);
+
+    assert_eq!(map.len(), 1);
+    assert!(map.contains_key(&CustomerFields::Billing));
+
+    let diff = map
+        .into_iter()
+        .map(|(k, v)| (storm::FieldsOrStr::Fields(k), v))
+        .collect::<HashMap<_, _>>();
+
+    new.apply_entity_diff(&diff).unwrap();
+
+    assert_eq!(new, old);
+}
+
+fn address(city: &str, zip: Option<&str>) -> Address {
+    Address {
+        city: city.to_string(),
+        zip: zip.map(ToString::to_string),
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, MssqlFlatten, PartialEq, Serialize)]
+#[storm(rename_all = "PascalCase", diff)]
+struct Address {
+    #[storm(not_empty)]
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##FlattenTbl",
+    keys = "Id",
+    collection = "hash_table",
+    rename_all = "PascalCase",
+    diff = true,
+    no_test = true
+)]
+struct Customer {
+    name: String,
+
+    #[storm(flatten, prefix = "Billing")]
+    billing: Address,
+
+    #[storm(flatten, prefix = "Shipping")]
+    shipping: Address,
+}
+
+impl Entity for Customer {
+    type Key = i32;
+    type TrackCtx = ();
+}
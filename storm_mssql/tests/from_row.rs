@@ -0,0 +1,136 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{prelude::*, Error, FromRow, Result};
+use storm_mssql::{from_row, MssqlFactory, MssqlProvider, QueryRows};
+use tiberius::Config;
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[tokio::test]
+async fn query_from_row() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+
+        // the columns are read by name, their order does not matter.
+        let rows: Vec<Sales> = provider
+            .query_rows(
+                "SELECT 'CA' Country, CAST(10.5 AS FLOAT) Total, 'East' Region, CAST(NULL AS NVARCHAR(10)) Notes UNION ALL SELECT 'US', 4, 'West', 'late'"
+                    .to_string(),
+                &[],
+                from_row,
+                false,
+            )
+            .await?;
+
+        assert_eq!(
+            rows,
+            vec![
+                Sales {
+                    region: "East".to_string(),
+                    amount: 10.5,
+                    notes: None,
+                    place: Place {
+                        country: "CA".to_string()
+                    },
+                },
+                Sales {
+                    region: "West".to_string(),
+                    amount: 4.0,
+                    notes: Some("late".to_string()),
+                    place: Place {
+                        country: "US".to_string()
+                    },
+                },
+            ]
+        );
+
+        let missing = provider
+            .query_rows::<_, _, Sales, Vec<_>>(
+                "SELECT 'East' Region, CAST(1 AS FLOAT) Total, CAST(NULL AS NVARCHAR(10)) Notes".to_string(),
+                &[],
+                from_row,
+                false,
+            )
+            .await;
+
+        assert!(
+            matches!(missing, Err(Error::ConvertFailed(msg)) if msg == "Column `Country` not found.")
+        );
+
+        let mistyped = provider
+            .query_rows::<_, _, Sales, Vec<_>>(
+                "SELECT 'CA' Country, 'ten' Total, 'East' Region, CAST(NULL AS NVARCHAR(10)) Notes".to_string(),
+                &[],
+                from_row,
+                false,
+            )
+            .await;
+
+        // the source error is kept, the column is in its context.
+        let mistyped = mistyped.unwrap_err();
+        assert!(matches!(mistyped.root(), Error::Mssql(_)));
+        assert!(mistyped
+            .frames()
+            .iter()
+            .any(|f| f.entity == "Sales" && f.column.as_deref() == Some("Total")));
+
+        // the columns are matched regardless of their case.
+        let lowercase: Vec<Sales> = provider
+            .query_rows(
+                "SELECT 'CA' country, CAST(1 AS FLOAT) total, 'East' region, CAST(NULL AS NVARCHAR(10)) notes"
+                    .to_string(),
+                &[],
+                from_row,
+                false,
+            )
+            .await?;
+
+        assert_eq!(lowercase.len(), 1);
+
+        let null = provider
+            .query_rows::<_, _, Sales, Vec<_>>(
+                "SELECT 'CA' Country, CAST(1 AS FLOAT) Total, CAST(NULL AS NVARCHAR(10)) Region, CAST(NULL AS NVARCHAR(10)) Notes".to_string(),
+                &[],
+                from_row,
+                false,
+            )
+            .await;
+
+        assert!(matches!(null, Err(Error::ConvertFailed(msg)) if msg == "Column `Region` is null."));
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Debug, FromRow, PartialEq)]
+#[storm(rename_all = "PascalCase")]
+struct Sales {
+    region: String,
+
+    #[storm(column = "Total")]
+    amount: f64,
+
+    notes: Option<String>,
+
+    #[from_row(flatten)]
+    place: Place,
+}
+
+#[derive(Debug, FromRow, PartialEq)]
+#[storm(rename_all = "PascalCase")]
+struct Place {
+    country: String,
+}
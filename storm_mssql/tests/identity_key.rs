@@ -34,6 +34,7 @@ async fn identity_key_crud() -> Result<()> {
                 &[],
                 ExecuteArgs {
                     use_transaction: false,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -31,13 +31,14 @@ async fn identity_translate_key() -> storm::Result<()> {
         let provider = ctx.provider().provide::<MssqlProvider>("").await?;
         let no_transaction = ExecuteArgs {
             use_transaction: false,
+            ..Default::default()
         };
 
         provider
             .execute_with_args(
                 "CREATE TABLE ##Labels (Id Int NOT NULL IDENTITY);",
                 &[],
-                no_transaction,
+                no_transaction.clone(),
             )
             .await?;
 
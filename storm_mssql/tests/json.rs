@@ -0,0 +1,140 @@
+#![allow(clippy::unwrap_used)]
+
+use serde::{Deserialize, Serialize};
+use storm::{prelude::*, MssqlLoad, MssqlSave, Result};
+use storm_mssql::{
+    create_table_sql, Execute, ExecuteArgs, Json, MssqlFactory, MssqlProvider, MssqlSchema,
+};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    QueueRwLock::new(provider.into())
+}
+
+#[tokio::test]
+async fn json_flow() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let lock = create_ctx();
+
+        {
+            let ctx = lock.read().await?;
+            let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+            provider
+                .execute_with_args(
+                    "CREATE TABLE ##JsonUsers (Id INT NOT NULL, Settings NVARCHAR(200) NOT NULL, Extra NVARCHAR(MAX) NULL);",
+                    &[],
+                    ExecuteArgs {
+                        use_transaction: false,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        let user = User {
+            settings: Json(Settings {
+                dark: true,
+                tabs: vec!["home".to_string(), "reports".to_string()],
+            }),
+            extra: None,
+        };
+
+        {
+            let ctx = lock.queue().await?;
+            let mut trx = ctx.transaction();
+
+            trx.tbl_of::<User>().await?.insert(1, user.clone(), &()).await?;
+
+            let log = trx.commit().await?;
+            ctx.write().await?.apply_log(log);
+        }
+
+        let ctx = lock.read().await?;
+        let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+        // the entity is reloaded from the database.
+        let v: Option<User> = storm::provider::LoadOne::load_one(ctx.provider(), &1).await?;
+        assert_eq!(v.as_ref(), Some(&user));
+        assert_eq!(v.unwrap().settings.tabs.len(), 2);
+
+        provider
+            .execute_with_args(
+                "UPDATE ##JsonUsers SET Settings = '{'",
+                &[],
+                ExecuteArgs::default(),
+            )
+            .await?;
+
+        let r = storm::provider::LoadOne::<User>::load_one(ctx.provider(), &1).await;
+        assert!(matches!(
+            r.map_err(storm::Error::into_root),
+            Err(storm::Error::ConvertFailed(_))
+        ));
+
+        Ok(())
+    })
+    .await
+}
+
+#[test]
+fn json_schema() {
+    assert_eq!(
+        create_table_sql(&User::schema()).unwrap(),
+        "CREATE TABLE ##JsonUsers ([Id] INT NOT NULL, [Settings] NVARCHAR(200) NOT NULL, [Extra] NVARCHAR(MAX) NULL, PRIMARY KEY ([Id]));"
+    );
+}
+
+#[test]
+fn json_max_length() {
+    let user = User {
+        settings: Json(Settings {
+            dark: false,
+            tabs: vec!["x".repeat(200)],
+        }),
+        extra: None,
+    };
+
+    let mut error = None;
+    storm::EntityValidate::entity_validate(&user, &mut error);
+
+    assert!(matches!(
+        error,
+        Some(storm::Error::FieldTooLong { max: 200, .. })
+    ));
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##JsonUsers",
+    keys = "Id",
+    collection = "hash_table",
+    no_test = true
+)]
+struct User {
+    #[storm(column = "Settings", max_length = 200)]
+    settings: Json<Settings>,
+
+    #[storm(column = "Extra")]
+    extra: Option<Json<Vec<i32>>>,
+}
+
+impl Entity for User {
+    type Key = i32;
+    type TrackCtx = ();
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct Settings {
+    dark: bool,
+    tabs: Vec<String>,
+}
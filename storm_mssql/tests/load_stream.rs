@@ -0,0 +1,118 @@
+#![allow(clippy::unwrap_used)]
+
+use futures::TryStreamExt;
+use storm::{
+    prelude::*,
+    provider::{LoadAll, LoadAllStream},
+    MssqlLoad, Result,
+};
+use storm_mssql::{Execute, ExecuteArgs, MssqlFactory, MssqlProvider, QueryRowsStream, ToSql};
+use tiberius::Config;
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[tokio::test]
+async fn load_all_stream() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+
+        provider
+            .execute_with_args(
+                "CREATE TABLE ##StreamRows (Id INT NOT NULL, Name NVARCHAR(100) NOT NULL);
+                INSERT INTO ##StreamRows (Id, Name) SELECT value, CONCAT('row ', value) FROM GENERATE_SERIES(1, 1000);",
+                &[],
+                ExecuteArgs {
+                    use_transaction: false,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        // the rows are counted as they arrive, without collecting them.
+        let mut stream = LoadAllStream::<Row, _>::load_all_stream(&container, &());
+        let mut count = 0;
+
+        while let Some((id, row)) = stream.try_next().await? {
+            assert_eq!(row.name, format!("row {id}"));
+            count += 1;
+        }
+
+        drop(stream);
+        assert_eq!(count, 1000);
+
+        // the stream loads the same rows as the collection.
+        let params: &[&dyn ToSql] = &[&10];
+        let filter = ("Id <= @p1", params);
+        let streamed: Vec<(i32, Row)> =
+            LoadAllStream::<Row, _>::load_all_stream(&container, &filter)
+                .try_collect()
+                .await?;
+        let loaded: Vec<(i32, Row)> = container.load_all(&filter).await?;
+        assert_eq!(streamed, loaded);
+
+        // a stream dropped early releases its connection.
+        let first = LoadAllStream::<Row, _>::load_all_stream(&container, &())
+            .try_next()
+            .await?;
+        assert!(first.is_some());
+
+        let names: Vec<String> = provider
+            .query_rows_stream(
+                "SELECT Name FROM ##StreamRows WHERE Id > @p1 ORDER BY Id".to_string(),
+                &[&998 as &dyn ToSql][..],
+                |row| Ok(row.get::<&str, _>(0).unwrap_or_default().to_string()),
+                false,
+            )
+            .try_collect()
+            .await?;
+        assert_eq!(names, vec!["row 999", "row 1000"]);
+
+        // the timeout applies while the rows are read.
+        let r: Result<Vec<i32>> = provider
+            .query_rows_stream_with_args(
+                "SELECT 1 WAITFOR DELAY '00:00:05' SELECT 2".to_string(),
+                &[][..],
+                |row| Ok(row.get::<i32, _>(0).unwrap_or_default()),
+                ExecuteArgs {
+                    use_transaction: false,
+                    timeout: Some(std::time::Duration::from_millis(500)),
+                    ..Default::default()
+                },
+            )
+            .try_collect()
+            .await;
+        assert!(matches!(r, Err(storm::Error::Timeout)));
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, PartialEq)]
+#[storm(
+    table = "##StreamRows",
+    keys = "Id",
+    collection = "hash_table",
+    no_test = true
+)]
+struct Row {
+    #[storm(column = "Name")]
+    name: String,
+}
+
+impl Entity for Row {
+    type Key = i32;
+    type TrackCtx = ();
+}
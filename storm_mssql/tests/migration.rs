@@ -0,0 +1,87 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{embed_migrations, provider::ProviderContainer, Result};
+use storm_mssql::{Migration, Migrator, MssqlFactory, MssqlProvider, QueryRows};
+use tiberius::Config;
+
+const MIGRATIONS: &[Migration] = embed_migrations!("tests/migrations");
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[test]
+fn embedded_in_version_order() {
+    let names = MIGRATIONS
+        .iter()
+        .map(|m| (m.version, m.name))
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, vec![(1, "create_users"), (2, "add_user_email")]);
+    assert!(MIGRATIONS[1].sql.contains("GO"));
+}
+
+#[tokio::test]
+async fn migrate() -> Result<()> {
+    let container = provider();
+    let provider = container.provide::<MssqlProvider>("").await?;
+    let migrator = Migrator::new(MIGRATIONS).history_table("##StormMigrations");
+
+    // dry run, the history table is not created.
+    let pending = migrator.pending(provider).await?;
+    assert_eq!(pending.len(), 2);
+
+    let history: Vec<bool> = provider
+        .query_rows(
+            "SELECT CAST(CASE WHEN OBJECT_ID('tempdb..##StormMigrations') IS NULL THEN 0 ELSE 1 END AS BIT)"
+                .to_string(),
+            &[],
+            |row| Ok(row.get::<bool, _>(0).unwrap()),
+            false,
+        )
+        .await?;
+
+    assert_eq!(history, vec![false]);
+
+    let applied = migrator.run(provider).await?;
+    assert_eq!(applied.len(), 2);
+
+    let names: Vec<String> = provider
+        .query_rows(
+            "SELECT Name FROM ##MigratedUsers".to_string(),
+            &[],
+            |row| Ok(row.get::<&str, _>(0).unwrap().to_string()),
+            false,
+        )
+        .await?;
+
+    assert_eq!(names, vec!["admin".to_string()]);
+
+    // already applied
+    assert!(migrator.run(provider).await?.is_empty());
+    assert_eq!(migrator.applied(provider).await?.len(), 2);
+
+    // a changed script is refused
+    let changed = [
+        MIGRATIONS[0],
+        Migration {
+            sql: "ALTER TABLE ##MigratedUsers ADD Phone NVARCHAR(20) NULL;",
+            ..MIGRATIONS[1]
+        },
+    ];
+
+    let migrator = Migrator::new(&changed).history_table("##StormMigrations");
+    assert!(migrator.pending(provider).await.is_err());
+    assert!(migrator.run(provider).await.is_err());
+
+    Ok(())
+}
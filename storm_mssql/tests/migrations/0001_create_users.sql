@@ -0,0 +1 @@
+CREATE TABLE ##MigratedUsers (Id INT NOT NULL PRIMARY KEY, Name NVARCHAR(100) NOT NULL);
@@ -0,0 +1,4 @@
+ALTER TABLE ##MigratedUsers ADD Email NVARCHAR(255) NULL;
+GO
+
+INSERT INTO ##MigratedUsers (Id, Name, Email) VALUES (1, 'admin', NULL);
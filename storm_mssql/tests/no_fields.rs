@@ -34,6 +34,7 @@ async fn no_fields() -> Result<()> {
                 &[],
                 ExecuteArgs {
                     use_transaction: false,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -0,0 +1,204 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{prelude::*, FromRow, MssqlDelete, MssqlLoad, MssqlSave, Result};
+use storm_mssql::{Execute, ExecuteArgs, MssqlFactory, MssqlProvider, Procedure, QueryRows};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    QueueRwLock::new(provider().into())
+}
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+async fn create(provider: &MssqlProvider, sql: &'static str) -> Result<()> {
+    provider
+        .execute_with_args(
+            sql,
+            &[],
+            ExecuteArgs {
+                use_transaction: false,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn procedure_call() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+
+        create(
+            provider,
+            r"CREATE PROCEDURE ##ProcCall @Value INT, @Total INT OUTPUT, @Label NVARCHAR(20) OUTPUT
+            AS
+            BEGIN
+                SET @Total = @Total + @Value;
+                SET @Label = CONCAT('total ', @Total);
+                SELECT n AS Value FROM (VALUES (1), (2), (3)) t(n) WHERE n <= @Value;
+                SELECT CAST(@Value AS INT) AS Id, N'value' AS Name;
+                RETURN 7;
+            END",
+        )
+        .await?;
+
+        let value = 2;
+
+        let result = Procedure::new("##ProcCall")
+            .input("Value", &value)
+            .in_out("@Total", 40)
+            .output_typed("Label", "NVARCHAR(20)")
+            .execute(provider)
+            .await?;
+
+        assert_eq!(result.return_value()?, 7);
+        assert_eq!(result.output::<i32>("Total")?, 42);
+        assert_eq!(result.output::<String>("@Label")?, "total 42");
+        assert_eq!(result.result_sets(), 2);
+        assert_eq!(result.values::<i32>(0)?, vec![1, 2]);
+        assert_eq!(
+            result.rows::<Row>(1)?,
+            vec![Row {
+                id: 2,
+                name: "value".to_string()
+            }]
+        );
+        assert!(result.values::<i32>(2).is_err());
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn save_and_delete_through_procedures() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+
+        create(
+            provider,
+            "CREATE TABLE ##ProcTbl (Id INT NOT NULL IDENTITY, Name NVARCHAR(100) NOT NULL, Upper AS UPPER(Name), Deleted BIT NOT NULL DEFAULT 0);",
+        )
+        .await?;
+
+        create(
+            provider,
+            r"CREATE PROCEDURE ##SaveProcTbl @Id INT OUTPUT, @Name NVARCHAR(100)
+            AS
+            BEGIN
+                IF @Id = 0
+                BEGIN
+                    INSERT INTO ##ProcTbl (Name) VALUES (@Name);
+                    SET @Id = SCOPE_IDENTITY();
+                END
+                ELSE
+                    UPDATE ##ProcTbl SET Name = @Name WHERE Id = @Id;
+            END",
+        )
+        .await?;
+
+        create(
+            provider,
+            "CREATE PROCEDURE ##DeleteProcTbl @Id INT AS UPDATE ##ProcTbl SET Deleted = 1 WHERE Id = @Id;",
+        )
+        .await?;
+
+        let ctx = create_ctx();
+        let ctx = ctx.read().await?;
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+        let mut entities = trx.tbl_of::<Entity1>().await?;
+
+        let e1 = Entity1 {
+            name: "first".to_string(),
+            upper: String::new(),
+        };
+
+        // insert, the identity is set by the procedure and the computed column reloaded.
+        let i1 = entities.insert_mut(0, e1, &()).await?;
+
+        assert_eq!(i1, 1);
+        assert_eq!(entities.get(&i1).unwrap().upper, "FIRST");
+
+        let e2 = Entity1 {
+            name: "second".to_string(),
+            upper: String::new(),
+        };
+
+        let i2 = entities.insert_mut(0, e2, &()).await?;
+
+        // update
+        let mut e1 = entities.get(&i1).unwrap().clone();
+        e1.name = "renamed".to_string();
+
+        let k = entities.insert_mut(i1, e1, &()).await?;
+
+        assert_eq!(k, i1);
+        assert_eq!(entities.get(&i1).unwrap().upper, "RENAMED");
+
+        // delete, the procedure flags the row.
+        entities.remove(i2, &()).await?;
+
+        trx.commit().await?;
+
+        let deleted: Vec<bool> = provider
+            .query_rows(
+                "SELECT Deleted FROM ##ProcTbl WHERE Id = @p1".to_string(),
+                &[&i2],
+                |row| Ok(row.get::<bool, _>(0).unwrap_or_default()),
+                false,
+            )
+            .await?;
+
+        assert_eq!(deleted, vec![true]);
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Debug, FromRow, PartialEq)]
+#[storm(rename_all = "PascalCase")]
+struct Row {
+    id: i32,
+    name: String,
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##ProcTbl",
+    keys = "Id",
+    collection = "hash_table",
+    identity = "id",
+    rename_all = "PascalCase",
+    reload_on_upsert = true,
+    save_proc = "##SaveProcTbl",
+    delete_proc = "##DeleteProcTbl",
+    no_test = true
+)]
+struct Entity1 {
+    name: String,
+
+    #[storm(skip_save = true)]
+    upper: String,
+}
+
+impl Entity for Entity1 {
+    type Key = i32;
+    type TrackCtx = ();
+}
@@ -0,0 +1,148 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{
+    prelude::*,
+    provider::{LoadAll, LoadOne},
+    MssqlLoad, Result,
+};
+use storm_mssql::{
+    create_table_sql, verify_table_schema, Execute, ExecuteArgs, MssqlFactory, MssqlProvider,
+    MssqlSchema, ToSql,
+};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    QueueRwLock::new(provider().into())
+}
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[tokio::test]
+async fn load_custom_select() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+
+        provider
+            .execute_with_args(
+                "CREATE TABLE ##Customers (Id INT NOT NULL, Name NVARCHAR(100) NOT NULL);
+                CREATE TABLE ##Orders (Id INT NOT NULL, CustomerId INT NOT NULL, Amount INT NOT NULL);
+                INSERT INTO ##Customers (Id, Name) VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol');
+                INSERT INTO ##Orders (Id, CustomerId, Amount) VALUES (1, 1, 10), (2, 1, 5), (3, 2, 7);",
+                &[],
+                ExecuteArgs {
+                    use_transaction: false,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let ctx = create_ctx();
+        let ctx = ctx.read().await?;
+        let totals = ctx.tbl_of::<CustomerTotal>().await?;
+
+        // the where_clause excludes the customers without orders.
+        assert_eq!(totals.len(), 2);
+        assert_eq!(
+            totals.get(&1),
+            Some(&CustomerTotal {
+                name: "Alice".to_string(),
+                total: 15
+            })
+        );
+
+        // the filters apply to the columns of the subquery.
+        let params: &[&dyn ToSql] = &[&10];
+        let filter = ("t.[Total] < @p1", params);
+        let v: Vec<(i32, CustomerTotal)> = container.load_all(&filter).await?;
+
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].1.name, "Bob");
+
+        let one: Option<CustomerTotal> = container.load_one(&2).await?;
+        assert_eq!(one.map(|c| c.total), Some(7));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn load_view() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let types: Vec<(i32, SqlTypeName)> = container.load_all(&()).await?;
+
+        assert!(types.iter().any(|(_, t)| t.name == "nvarchar"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn verify_view_schema() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let container = provider();
+        let provider = container.provide::<MssqlProvider>("").await?;
+        let schema = SqlTypeName::schema();
+
+        // the view has no primary key, only its columns are verified.
+        let reports = verify_table_schema(provider, &schema).await?;
+        assert!(reports.iter().all(|r| r.issues.is_empty()));
+
+        assert!(schema.view);
+        assert!(create_table_sql(&schema).is_err());
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, PartialEq)]
+#[storm(
+    read_only,
+    load_sql = "SELECT c.Id, c.Name, SUM(o.Amount) AS Total FROM ##Customers c LEFT JOIN ##Orders o ON o.CustomerId = c.Id GROUP BY c.Id, c.Name",
+    where_clause = "t.[Total] IS NOT NULL",
+    keys = "Id",
+    collection = "hash_table",
+    rename_all = "PascalCase",
+    no_test = true
+)]
+struct CustomerTotal {
+    name: String,
+    total: i32,
+}
+
+impl Entity for CustomerTotal {
+    type Key = i32;
+    type TrackCtx = ();
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, PartialEq)]
+#[storm(
+    read_only = true,
+    view = "sys.types",
+    keys = "user_type_id",
+    collection = "hash_table",
+    no_test = true
+)]
+struct SqlTypeName {
+    name: String,
+}
+
+impl Entity for SqlTypeName {
+    type Key = i32;
+    type TrackCtx = ();
+}
@@ -52,3 +52,18 @@ impl Entity for EntityPart {
     type Key = i32;
     type TrackCtx = ();
 }
+
+#[derive(MssqlSave)]
+#[storm(table = "t", keys = "id")]
+pub struct EntityWithRules {
+    #[storm(max_length = 20, min_length = 2, not_empty)]
+    pub name: String,
+
+    #[storm(range(min = 0, max = 10))]
+    pub rank: Option<i32>,
+}
+
+impl Entity for EntityWithRules {
+    type Key = i32;
+    type TrackCtx = ();
+}
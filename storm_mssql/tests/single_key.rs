@@ -34,6 +34,7 @@ async fn crud() -> Result<()> {
                 &[],
                 ExecuteArgs {
                     use_transaction: false,
+                    ..Default::default()
                 },
             )
             .await?;
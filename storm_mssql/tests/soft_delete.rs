@@ -0,0 +1,120 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{
+    prelude::*,
+    provider::{LoadAll, LoadArgs},
+    MssqlDelete, MssqlLoad, MssqlSave, Result,
+};
+use storm_mssql::{Execute, ExecuteArgs, MssqlFactory, MssqlProvider};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    QueueRwLock::new(provider().into())
+}
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+#[tokio::test]
+async fn soft_delete() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let ctx = create_ctx();
+        let ctx = ctx.read().await?;
+        let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+        provider
+            .execute_with_args(
+                "CREATE TABLE ##SoftTbl (Id INT NOT NULL, Name NVARCHAR(100) NOT NULL, IsDeleted BIT NOT NULL DEFAULT 0);",
+                &[],
+                ExecuteArgs {
+                    use_transaction: false,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let ctx = ctx.queue().await?;
+        let mut trx = ctx.transaction();
+        let mut entities = trx.tbl_of::<SoftEntity>().await?;
+
+        entities
+            .insert(1, SoftEntity { name: "E1".to_string() }, &())
+            .await?;
+
+        entities
+            .insert(2, SoftEntity { name: "E2".to_string() }, &())
+            .await?;
+
+        // the row is kept and marked as deleted.
+        entities.remove(2, &()).await?;
+
+        let log = trx.commit().await?;
+        let mut ctx = ctx.write().await?;
+
+        ctx.apply_log(log);
+
+        let ctx = ctx.read().await?;
+        let entities = ctx.tbl_of::<SoftEntity>().await?;
+
+        assert!(entities.get(&1).is_some());
+        assert!(entities.get(&2).is_none());
+
+        let v: Vec<(i32, SoftEntity)> = ctx.provider().load_all(&()).await?;
+        assert_eq!(v.len(), 1);
+
+        let args = LoadArgs {
+            include_deleted: true,
+            ..Default::default()
+        };
+
+        let v: Vec<(i32, SoftEntity)> = ctx.provider().load_all_with_args(&(), args).await?;
+        assert_eq!(v.len(), 2);
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##SoftTbl",
+    keys = "Id",
+    soft_delete = "IsDeleted",
+    collection = "hash_table",
+    no_test = true
+)]
+struct SoftEntity {
+    name: String,
+}
+
+impl Entity for SoftEntity {
+    type Key = i32;
+    type TrackCtx = ();
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##SoftAtTbl",
+    keys = "Id",
+    soft_delete_at = "DeletedAt",
+    collection = "hash_table",
+    no_test = true
+)]
+struct SoftAtEntity {
+    name: String,
+}
+
+impl Entity for SoftAtEntity {
+    type Key = i32;
+    type TrackCtx = ();
+}
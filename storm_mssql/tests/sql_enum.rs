@@ -0,0 +1,132 @@
+#![allow(clippy::unwrap_used)]
+
+use serde::{Deserialize, Serialize};
+use storm::{prelude::*, Error, MssqlLoad, MssqlSave, SqlEnum};
+use storm_mssql::{
+    create_table_sql, ApplyFieldDiff, FieldDiff, FromFieldDiff, FromSql, MssqlSchema, ToSql,
+};
+use tiberius::ColumnData;
+
+#[derive(Clone, Copy, Debug, PartialEq, SqlEnum)]
+#[sql(repr = "i32")]
+enum Status {
+    Draft,
+    #[sql(value = 10)]
+    Active,
+    Closed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SqlEnum)]
+#[sql(repr = "string")]
+enum Kind {
+    #[sql(value = "P")]
+    Person,
+    Company,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SqlEnum)]
+#[sql(repr = "u8")]
+enum Level {
+    Low = 1,
+    High = 5,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SqlEnum)]
+#[sql(repr = "i16", no_serde)]
+enum Direction {
+    Down = -1,
+    Still,
+    Up,
+}
+
+#[test]
+fn int_enum() {
+    assert_eq!(Status::Draft.sql_value(), 0);
+    assert_eq!(Status::Closed.sql_value(), 11);
+    assert_eq!(Level::High.sql_value(), 5);
+
+    assert!(matches!(Status::Active.to_sql(), ColumnData::I32(Some(10))));
+    assert_eq!(Status::from_sql(Some(11)).ok(), Some(Status::Closed));
+    assert_eq!(Level::from_sql(Some(1)).ok(), Some(Level::Low));
+
+    match Status::from_sql(Some(3)) {
+        Err(Error::ConvertFailed(s)) => assert_eq!(s, "`3` is not a valid `Status`."),
+        _ => panic!("expected a conversion error"),
+    }
+
+    assert_eq!(Direction::Down.sql_value(), -1);
+    assert_eq!(Direction::Up.sql_value(), 1);
+    assert_eq!(Direction::from_sql(Some(0)).ok(), Some(Direction::Still));
+
+    // the serde impls are the ones derived.
+    assert_eq!(
+        serde_json::to_value(Direction::Down).ok(),
+        Some(serde_json::json!("Down"))
+    );
+
+    assert!(matches!(Status::from_sql(None), Err(Error::ColumnNull)));
+    assert_eq!(Option::<Status>::from_sql(None).ok(), Some(None));
+}
+
+#[test]
+fn string_enum() {
+    assert_eq!(Kind::Company.sql_value(), "Company");
+    assert!(matches!(Kind::Person.to_sql(), ColumnData::String(Some(s)) if s == "P"));
+    assert_eq!(Kind::from_sql(Some("P")).ok(), Some(Kind::Person));
+    assert!(matches!(
+        Kind::from_sql(Some("X")),
+        Err(Error::ConvertFailed(_))
+    ));
+}
+
+#[test]
+fn enum_diff() {
+    assert_eq!(Kind::Person.field_diff(&Kind::Person), None);
+
+    let diff = Kind::Person.field_diff(&Kind::Company).unwrap();
+    assert_eq!(diff, serde_json::json!("Company"));
+
+    let mut v = Kind::Person;
+    v.apply_field_diff(diff).unwrap();
+    assert_eq!(v, Kind::Company);
+
+    assert_eq!(
+        Status::from_field_diff(serde_json::json!(10)).ok(),
+        Some(Status::Active)
+    );
+    assert!(Status::from_field_diff(serde_json::json!(4)).is_err());
+
+    assert_eq!(
+        serde_json::to_value(Some(Kind::Person)).ok(),
+        Some(serde_json::json!("P"))
+    );
+}
+
+#[test]
+fn enum_schema() {
+    assert_eq!(
+        create_table_sql(&Account::schema()).unwrap(),
+        "CREATE TABLE ##Accounts ([Id] INT NOT NULL, [Status] INT NOT NULL, [Kind] NVARCHAR(7) NULL, PRIMARY KEY ([Id]));"
+    );
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##Accounts",
+    keys = "Id",
+    collection = "hash_table",
+    diff = true,
+    no_test = true
+)]
+struct Account {
+    #[storm(column = "Status")]
+    status: Status,
+
+    #[storm(column = "Kind")]
+    kind: Option<Kind>,
+}
+
+impl Entity for Account {
+    type Key = i32;
+    type TrackCtx = ();
+}
@@ -0,0 +1,98 @@
+#![allow(clippy::unwrap_used)]
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use storm::Error;
+use storm_mssql::{FieldDiff, FromFieldDiff, FromSql, SqlType, ToSql, ToSqlNull};
+use tiberius::{xml::XmlData, ColumnData};
+
+#[test]
+fn checked_integers() {
+    assert_eq!(u16::from_sql(Some(65_535)).unwrap(), u16::MAX);
+    assert_eq!(u32::from_sql(Some(4_294_967_295)).unwrap(), u32::MAX);
+    assert_eq!(u64::from_sql(Some(i64::MAX)).unwrap(), i64::MAX as u64);
+    assert_eq!(i8::from_sql(Some(-128)).unwrap(), i8::MIN);
+
+    assert!(matches!(
+        u16::from_sql(Some(-1)),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(
+        u16::from_sql(Some(65_536)),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(
+        u32::from_sql(Some(-1)),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(
+        u64::from_sql(Some(-1)),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(
+        i8::from_sql(Some(128)),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(u64::from_sql(None), Err(Error::ColumnNull)));
+
+    assert!(matches!(8i8.to_sql(), ColumnData::I16(Some(8))));
+    assert!(matches!(u16::MAX.to_sql(), ColumnData::I32(Some(65_535))));
+    assert!(matches!(
+        u32::MAX.to_sql(),
+        ColumnData::I64(Some(4_294_967_295))
+    ));
+    assert!(matches!(7u64.to_sql(), ColumnData::I64(Some(7))));
+    assert!(matches!(
+        u64::MAX.try_to_sql(),
+        Err(Error::ConvertFailed(_))
+    ));
+    assert!(matches!(u64::to_sql_null(), ColumnData::I64(None)));
+
+    assert_eq!(u16::sql_type(0), "INT");
+    assert_eq!(u64::sql_type(0), "BIGINT");
+}
+
+#[test]
+fn shared_strings() {
+    let s = Arc::<str>::from_sql(Some("abc")).unwrap();
+    assert_eq!(&*s, "abc");
+    assert!(matches!(s.to_sql(), ColumnData::String(Some(v)) if v == "abc"));
+
+    assert_eq!(&*Arc::<[u8]>::from_sql(Some(&[1, 2][..])).unwrap(), &[1, 2]);
+    assert_eq!(String::from_sql(Some("abc")).unwrap(), "abc");
+    assert_eq!(Vec::<u8>::from_sql(Some(&[3][..])).unwrap(), vec![3]);
+
+    let old: Arc<str> = Arc::from("old");
+    assert_eq!(s.field_diff(&old), Some(json!("old")));
+    assert_eq!(old.field_diff(&Arc::from("old")), None);
+    assert_eq!(&*Arc::<str>::from_field_diff(json!("old")).unwrap(), "old");
+}
+
+#[test]
+fn local_date_time() {
+    let utc = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+    let local = utc.with_timezone(&Local);
+
+    let v = DateTime::<Local>::from_sql(Some(utc.fixed_offset())).unwrap();
+    assert_eq!(v, local);
+    assert!(matches!(
+        local.to_sql(),
+        ColumnData::DateTimeOffset(Some(_))
+    ));
+    assert_eq!(DateTime::<Local>::sql_type(0), "DATETIMEOFFSET");
+}
+
+#[test]
+fn xml() {
+    let xml = XmlData::new("<a>1</a>");
+
+    assert_eq!(XmlData::from_sql(Some(&xml)).unwrap(), xml);
+    assert!(matches!(xml.to_sql(), ColumnData::Xml(Some(_))));
+    assert!(matches!(XmlData::to_sql_null(), ColumnData::Xml(None)));
+    assert_eq!(XmlData::sql_type(0), "XML");
+
+    let old = XmlData::new("<a>0</a>");
+    assert_eq!(xml.field_diff(&old), Some(json!("<a>0</a>")));
+    assert_eq!(XmlData::from_field_diff(json!("<a>0</a>")).unwrap(), old);
+}
@@ -0,0 +1,82 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{prelude::*, IsDefined, MssqlLoad, MssqlSave, StormKey};
+use storm_mssql::{create_table_sql, FromSql, MssqlSchema, ToSql};
+use tiberius::ColumnData;
+use uuid::Uuid;
+
+#[test]
+fn integer_key() {
+    let id = UserId(7);
+
+    assert_eq!(usize::from(id), 7);
+    assert_eq!(UserId::from(7usize), id);
+    assert!(id.is_defined());
+    assert!(!UserId(0).is_defined());
+
+    assert!(matches!(id.to_sql(), ColumnData::I32(Some(7))));
+    assert_eq!(UserId::from_sql(Some(7)).unwrap(), id);
+    assert!(matches!(
+        UserId::from_sql(None),
+        Err(storm::Error::ColumnNull)
+    ));
+
+    assert_eq!(serde_json::to_string(&id).unwrap(), "7");
+    assert_eq!(serde_json::from_str::<UserId>("7").unwrap(), id);
+}
+
+#[test]
+#[should_panic(expected = "The `UserId` is not a valid index.")]
+fn negative_key_index() {
+    let _ = usize::from(UserId(-1));
+}
+
+#[test]
+#[should_panic(expected = "The index is out of the range of `LevelId`.")]
+fn key_index_overflow() {
+    let _ = LevelId::from(256usize);
+}
+
+#[test]
+fn uuid_key() {
+    let id = SessionId(Uuid::new_v4());
+
+    assert!(id.is_defined());
+    assert!(!SessionId(Uuid::nil()).is_defined());
+    assert!(matches!(id.to_sql(), ColumnData::Guid(Some(v)) if v == id.0));
+
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!(serde_json::from_str::<SessionId>(&json).unwrap(), id);
+}
+
+#[test]
+fn storm_key_schema() {
+    assert_eq!(
+        create_table_sql(&User::schema()).unwrap(),
+        "CREATE TABLE ##KeyUsers ([Id] INT NOT NULL, [Name] NVARCHAR(50) NOT NULL, [Session] UNIQUEIDENTIFIER NULL, PRIMARY KEY ([Id]));"
+    );
+}
+
+#[derive(Debug, StormKey)]
+pub struct UserId(i32);
+
+#[derive(Debug, StormKey)]
+pub struct SessionId(Uuid);
+
+#[derive(Debug, StormKey)]
+pub struct LevelId(u8);
+
+#[derive(Ctx, MssqlLoad, MssqlSave)]
+#[storm(table = "##KeyUsers", keys = "Id", no_test = true)]
+struct User {
+    #[storm(column = "Name", max_length = 50)]
+    name: String,
+
+    #[storm(column = "Session")]
+    session: Option<SessionId>,
+}
+
+impl Entity for User {
+    type Key = UserId;
+    type TrackCtx = ();
+}
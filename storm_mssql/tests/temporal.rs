@@ -0,0 +1,146 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{prelude::*, MssqlLoad, MssqlSave, Result};
+use storm_mssql::{
+    EntityVersion, Execute, ExecuteArgs, LoadAllAsOf, LoadHistory, LoadOneAsOf, MssqlFactory,
+    MssqlProvider,
+};
+use tiberius::Config;
+
+fn create_ctx() -> QueueRwLock<Ctx> {
+    QueueRwLock::new(provider().into())
+}
+
+fn provider() -> ProviderContainer {
+    let mut config = Config::default();
+    config.database("master");
+    #[cfg(target_os = "windows")]
+    config.authentication(tiberius::AuthMethod::Integrated);
+    config.trust_cert();
+
+    let mut provider = ProviderContainer::new();
+    provider.register("", MssqlFactory(config));
+
+    provider
+}
+
+fn no_transaction() -> ExecuteArgs {
+    ExecuteArgs {
+        use_transaction: false,
+        ..Default::default()
+    }
+}
+
+// temp tables cannot be system-versioned.
+const DROP_TABLES: &str = "IF OBJECT_ID('dbo.StormTemporal') IS NOT NULL
+BEGIN
+    ALTER TABLE dbo.StormTemporal SET (SYSTEM_VERSIONING = OFF);
+    DROP TABLE dbo.StormTemporal;
+    DROP TABLE dbo.StormTemporalHistory;
+END";
+
+#[tokio::test]
+async fn temporal() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let lock = create_ctx();
+
+        {
+            let ctx = lock.read().await?;
+            let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+            provider
+                .execute_with_args(DROP_TABLES, &[], no_transaction())
+                .await?;
+
+            provider
+                .execute_with_args(
+                    "CREATE TABLE dbo.StormTemporal (
+                        Id INT NOT NULL PRIMARY KEY,
+                        Name NVARCHAR(100) NOT NULL,
+                        ValidFrom DATETIME2 GENERATED ALWAYS AS ROW START NOT NULL,
+                        ValidTo DATETIME2 GENERATED ALWAYS AS ROW END NOT NULL,
+                        PERIOD FOR SYSTEM_TIME (ValidFrom, ValidTo)
+                    ) WITH (SYSTEM_VERSIONING = ON (HISTORY_TABLE = dbo.StormTemporalHistory));",
+                    &[],
+                    no_transaction(),
+                )
+                .await?;
+        }
+
+        for name in ["V1", "V2"] {
+            let ctx = lock.queue().await?;
+            let mut trx = ctx.transaction();
+            let mut entities = trx.tbl_of::<TemporalEntity>().await?;
+
+            entities
+                .insert(
+                    1,
+                    TemporalEntity {
+                        name: name.to_string(),
+                    },
+                    &(),
+                )
+                .await?;
+
+            let log = trx.commit().await?;
+            ctx.write().await?.apply_log(log);
+        }
+
+        let ctx = lock.read().await?;
+        let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+        let history: Vec<EntityVersion<TemporalEntity>> = ctx.provider().load_history(&1).await?;
+
+        assert_eq!(
+            history
+                .iter()
+                .map(|v| v.entity.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["V1", "V2"]
+        );
+
+        assert!(history[0].valid_from < history[0].valid_to);
+        assert_eq!(history[0].valid_to, history[1].valid_from);
+
+        let first = history[0].valid_from;
+
+        let v: Option<TemporalEntity> = ctx.provider().load_one_as_of(&1, first).await?;
+        assert_eq!(v.unwrap().name, "V1");
+
+        let v: Vec<(i32, TemporalEntity)> = ctx
+            .provider()
+            .load_all_as_of(&("t.[Id] = @p2", &[&1i32 as _][..]), first)
+            .await?;
+
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].1.name, "V1");
+
+        // the current version is unchanged.
+        let v = ctx.tbl_of::<TemporalEntity>().await?;
+        assert_eq!(v.get(&1).unwrap().name, "V2");
+
+        provider
+            .execute_with_args(DROP_TABLES, &[], no_transaction())
+            .await?;
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Clone, Ctx, Debug, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "dbo.StormTemporal",
+    keys = "Id",
+    temporal = true,
+    collection = "hash_table",
+    no_test = true
+)]
+struct TemporalEntity {
+    name: String,
+}
+
+impl Entity for TemporalEntity {
+    type Key = i32;
+    type TrackCtx = ();
+}
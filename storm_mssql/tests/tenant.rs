@@ -0,0 +1,163 @@
+#![allow(clippy::unwrap_used)]
+
+use storm::{
+    prelude::*, provider::LoadAll, Error, MssqlDelete, MssqlLoad, MssqlSave, Result, TenantRegistry,
+};
+use storm_mssql::{Execute, ExecuteArgs, MssqlFactory, MssqlProvider, MssqlTenant};
+use tiberius::Config;
+
+fn registry() -> TenantRegistry<i32> {
+    TenantRegistry::new(|_| {
+        let mut config = Config::default();
+        config.database("master");
+        #[cfg(target_os = "windows")]
+        config.authentication(tiberius::AuthMethod::Integrated);
+        config.trust_cert();
+
+        let mut provider = ProviderContainer::new();
+        provider.register("", MssqlFactory(config));
+
+        provider.with_mssql_tenant_type::<i32>()
+    })
+}
+
+#[tokio::test]
+async fn tenant() -> Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let registry = registry();
+        let tenant1 = registry.ctx(&1);
+        let tenant2 = registry.ctx(&2);
+
+        {
+            let ctx = tenant1.read().await?;
+            let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+            provider
+                .execute_with_args(
+                    "CREATE TABLE ##TenantTbl (Id INT NOT NULL, TenantId INT NOT NULL, Name NVARCHAR(100) NOT NULL);",
+                    &[],
+                    ExecuteArgs {
+                        use_transaction: false,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        for (lock, id) in [(&tenant1, 1), (&tenant2, 2)] {
+            let ctx = lock.queue().await?;
+            let mut trx = ctx.transaction();
+
+            trx.tbl_of::<TenantEntity>()
+                .await?
+                .insert(
+                    id,
+                    TenantEntity {
+                        name: format!("E{id}"),
+                    },
+                    &(),
+                )
+                .await?;
+
+            let log = trx.commit().await?;
+            ctx.write().await?.apply_log(log);
+        }
+
+        {
+            let ctx = tenant1.read().await?;
+            assert_eq!(ctx.tenant::<i32>(), Some(&1));
+
+            let v: Vec<(i32, TenantEntity)> = ctx.provider().load_all(&()).await?;
+            assert_eq!(v.len(), 1);
+            assert_eq!(v[0].0, 1);
+
+            let v: Vec<(i32, TenantFieldEntity)> = ctx.provider().load_all(&()).await?;
+            assert_eq!(v[0].1.tenant_id, 1);
+        }
+
+        // the entity of another tenant is not deleted.
+        {
+            let ctx = tenant2.queue().await?;
+            let mut trx = ctx.transaction();
+            trx.remove::<TenantEntity>(1, &()).await?;
+            trx.commit().await?;
+        }
+
+        {
+            let ctx = tenant1.read().await?;
+            let v: Vec<(i32, TenantEntity)> = ctx.provider().load_all(&()).await?;
+            assert_eq!(v.len(), 1);
+        }
+
+        // the tenant field must be the tenant of the context.
+        {
+            let ctx = tenant1.queue().await?;
+            let mut trx = ctx.transaction();
+
+            let r = trx
+                .tbl_of::<TenantFieldEntity>()
+                .await?
+                .insert(
+                    3,
+                    TenantFieldEntity {
+                        tenant_id: 2,
+                        name: "E3".to_string(),
+                    },
+                    &(),
+                )
+                .await;
+
+            assert!(matches!(
+                r.map_err(Error::into_root),
+                Err(Error::TenantMismatch)
+            ));
+        }
+
+        drop((tenant1, tenant2));
+        registry.gc().await?;
+        registry.gc().await?;
+        assert!(registry.is_empty());
+
+        Ok(())
+    })
+    .await
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##TenantTbl",
+    keys = "Id",
+    tenant = "TenantId",
+    collection = "hash_table",
+    no_test = true
+)]
+struct TenantEntity {
+    #[storm(column = "Name")]
+    name: String,
+}
+
+impl Entity for TenantEntity {
+    type Key = i32;
+    type TrackCtx = ();
+}
+
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave, PartialEq)]
+#[storm(
+    table = "##TenantTbl",
+    keys = "Id",
+    tenant = "TenantId",
+    collection = "hash_table",
+    no_test = true
+)]
+struct TenantFieldEntity {
+    #[storm(column = "TenantId")]
+    tenant_id: i32,
+
+    #[storm(column = "Name")]
+    name: String,
+}
+
+impl Entity for TenantFieldEntity {
+    type Key = i32;
+    type TrackCtx = ();
+}
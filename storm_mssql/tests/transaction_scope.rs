@@ -34,6 +34,7 @@ async fn transaction_scoped() -> Result<()> {
                 &[],
                 ExecuteArgs {
                     use_transaction: false,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -1,8 +1,14 @@
 #![allow(clippy::unwrap_used)]
 
 use std::borrow::Cow;
-use storm::{prelude::*, Error, MssqlLoad, MssqlSave, Result};
-use storm_mssql::{Execute, ExecuteArgs, FromSql, MssqlFactory, MssqlProvider, ToSql, ToSqlNull};
+use storm::{
+    prelude::*, provider::LoadAll, Error, FallbackChain, HashTable, MssqlDelete, MssqlLoad,
+    MssqlSave, Result, Translations,
+};
+use storm_mssql::{
+    create_table_sql, registered_schemas, Execute, ExecuteArgs, FromSql, MssqlFactory,
+    MssqlProvider, MssqlSchema, ToSql, ToSqlNull,
+};
 use tiberius::Config;
 
 fn create_ctx() -> QueueRwLock<Ctx> {
@@ -31,13 +37,14 @@ async fn translated_flow() -> storm::Result<()> {
         let provider = ctx.provider().provide::<MssqlProvider>("").await?;
         let no_transaction = ExecuteArgs {
             use_transaction: false,
+            ..Default::default()
         };
 
         provider
             .execute_with_args(
                 "CREATE TABLE ##Labels (Id Int PRIMARY KEY NOT NULL);",
                 &[],
-                no_transaction,
+                no_transaction.clone(),
             )
             .await?;
 
@@ -82,6 +89,80 @@ async fn translated_flow() -> storm::Result<()> {
     .await
 }
 
+#[tokio::test]
+async fn translations_flow() -> storm::Result<()> {
+    async_cell_lock::with_deadlock_check(async move {
+        let ctx = create_ctx();
+
+        {
+            let ctx = ctx.read().await?;
+            let provider = ctx.provider().provide::<MssqlProvider>("").await?;
+
+            provider
+                .execute_with_args(
+                    "CREATE TABLE ##Tags (Id Int PRIMARY KEY NOT NULL);
+                    CREATE TABLE ##TagsTranslatedValues (Id Int NOT NULL, Culture Int NOT NULL, Name NVARCHAR(50) NULL, Description NVARCHAR(MAX) NULL, PRIMARY KEY (Id, Culture));",
+                    &[],
+                    ExecuteArgs {
+                        use_transaction: false,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        let names = [
+            Translations::new()
+                .with(Culture::En, "english")
+                .with(Culture::Fr, "french"),
+            // the french row is deleted.
+            Translations::new().with(Culture::En, "english 2"),
+        ];
+
+        for name in names {
+            // the english description is saved as null.
+            let description = Translations::new().with(Culture::Fr, "description");
+            let ctx = ctx.queue().await?;
+            let mut trx = ctx.transaction();
+
+            trx.tbl_of::<Tag>()
+                .await?
+                .insert(1, Tag { name, description }, &())
+                .await?;
+
+            let log = trx.commit().await?;
+            ctx.write().await?.apply_log(log);
+        }
+
+        let ctx = ctx.read().await?;
+        let tags: HashTable<Tag> = ctx.provider().load_all(&()).await?;
+        let tag = tags.get(&1).unwrap();
+        let name = &tag.name;
+
+        assert_eq!(name.get(&Culture::Fr), None);
+        assert_eq!(tag.description.get(&Culture::En), None);
+        assert_eq!(tag.description.get(&Culture::Fr), Some("description"));
+        assert_eq!(
+            name.get_or_fallback(&Culture::Fr, &FallbackChain::new().with_default([Culture::En])),
+            Some("english 2")
+        );
+
+        Ok(())
+    })
+    .await
+}
+
+#[test]
+fn translated_schema() {
+    assert_eq!(
+        create_table_sql(&Label::schema()).unwrap(),
+        "CREATE TABLE ##Labels ([Id] INT NOT NULL, PRIMARY KEY ([Id]));\n\
+        CREATE TABLE ##LabelsTranslatedValues ([Id2] INT NOT NULL, [Culture] INT NOT NULL, [name] NVARCHAR(MAX) NOT NULL, PRIMARY KEY ([Id2], [Culture]));"
+    );
+
+    assert!(registered_schemas().iter().any(|s| s.entity == "Label"));
+}
+
 #[derive(Clone, Ctx, Debug, MssqlLoad, MssqlSave)]
 #[storm(
     table = "##Labels",
@@ -99,6 +180,28 @@ impl Entity for Label {
     type TrackCtx = ();
 }
 
+#[derive(Clone, Ctx, Debug, MssqlDelete, MssqlLoad, MssqlSave)]
+#[storm(
+    table = "##Tags",
+    keys = "Id",
+    translate_table = "##TagsTranslatedValues",
+    translate_keys = "Id",
+    collection = "hash_table",
+    no_test = true
+)]
+struct Tag {
+    #[storm(column = "Name", max_length = 50)]
+    name: Translations<Culture>,
+
+    #[storm(column = "Description")]
+    description: Translations<Culture>,
+}
+
+impl Entity for Tag {
+    type Key = i32;
+    type TrackCtx = ();
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct LabelId(i32);
 
@@ -161,7 +264,7 @@ impl Translated {
 
 impl storm::Gc for Translated {}
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Culture {
     Fr = 0,
     En = 1,